@@ -0,0 +1,691 @@
+//! Mappings between garden-related numbers, as described in Advent of Code 2023 Day 5.
+//! https://adventofcode.com/2023/day/5
+//!
+//! The almanac holds a list of seeds, then a series of maps each converting numbers of one type,
+//! such as seeds, to numbers of another, such as soil. Each line of a map sends a range of source
+//! numbers to a range of destination numbers of the same length, and numbers in no range are
+//! unchanged.
+//!
+//! Every such map is piecewise linear: the numbers are split into ranges, and each range is
+//! shifted by its own offset. Two piecewise linear maps compose into another, by splitting each
+//! piece of the first where its image crosses between pieces of the second. So the whole chain
+//! from seeds to locations becomes a single `PiecewiseMap`, with at most a few pieces for each line
+//! of the almanac. The lowest output over a range of inputs is then at the start of one of the
+//! pieces the range overlaps, and looking up which inputs give an output only needs each piece's
+//! offset reversed.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+const INPUT_TOKEN_SEEDS: &str = "seeds:";
+const INPUT_TOKEN_MAP_SUFFIX: &str = " map:";
+const INPUT_TOKEN_MAP_TYPE_SEPARATOR: &str = "-to-";
+
+pub type Value = u64;
+type Offset = i128;
+
+/// Every map is defined for values in this range. The end is excluded so that ranges of values
+/// can be held in a `Range`.
+const DOMAIN: Range<Value> = 0..Value::MAX;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DataType {
+    Fertilizer,
+    Humidity,
+    Light,
+    Location,
+    Seed,
+    Soil,
+    Temperature,
+    Water,
+}
+
+impl FromStr for DataType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "fertilizer" => Ok(Self::Fertilizer),
+            "humidity" => Ok(Self::Humidity),
+            "light" => Ok(Self::Light),
+            "location" => Ok(Self::Location),
+            "seed" => Ok(Self::Seed),
+            "soil" => Ok(Self::Soil),
+            "temperature" => Ok(Self::Temperature),
+            "water" => Ok(Self::Water),
+            _ => Err(format!("Unrecognized data type '{}'", s.trim())),
+        }
+    }
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Fertilizer => "fertilizer",
+            Self::Humidity => "humidity",
+            Self::Light => "light",
+            Self::Location => "location",
+            Self::Seed => "seed",
+            Self::Soil => "soil",
+            Self::Temperature => "temperature",
+            Self::Water => "water",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Values in `source` are mapped to themselves plus `offset`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Piece {
+    source: Range<Value>,
+    offset: Offset,
+}
+
+impl Piece {
+    fn shift(&self, v: Value) -> Value {
+        (v as Offset + self.offset) as Value
+    }
+
+    /// Returns the values that `source` is mapped to.
+    fn destination(&self) -> Range<Value> {
+        self.shift(self.source.start)..self.shift(self.source.end)
+    }
+}
+
+/// A map from every value in `DOMAIN` to a value, which shifts each of a series of ranges by an
+/// offset.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PiecewiseMap {
+    /// Sorted pieces whose sources exactly cover `DOMAIN`, with no two neighbours sharing an
+    /// offset.
+    pieces: Vec<Piece>,
+}
+
+impl PiecewiseMap {
+    /// Returns the map that sends every value to itself.
+    pub fn identity() -> Self {
+        Self {
+            pieces: vec![Piece {
+                source: DOMAIN,
+                offset: 0,
+            }],
+        }
+    }
+
+    /// Returns a map that sends each value in a range `source` to the value the same distance
+    /// into the range starting at `destination`, for each `(destination, source)` in `ranges`.
+    /// Values in none of the ranges are unchanged.
+    ///
+    /// Returns an error if the source ranges overlap, or a range extends beyond `DOMAIN`.
+    pub fn from_ranges(ranges: &[(Value, Range<Value>)]) -> Result<Self, String> {
+        let mut shifted: Vec<Piece> = ranges
+            .iter()
+            .filter(|(_, source)| !source.is_empty())
+            .map(|(destination, source)| {
+                let length = source.end - source.start;
+                if destination.checked_add(length).is_none() {
+                    return Err(format!(
+                        "The range of {} values from {} to {} is too large",
+                        length, source.start, destination
+                    ));
+                }
+                Ok(Piece {
+                    source: source.clone(),
+                    offset: *destination as Offset - source.start as Offset,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        shifted.sort_by_key(|piece| piece.source.start);
+
+        let mut pieces = Vec::new();
+        let mut next = DOMAIN.start;
+        for piece in shifted {
+            if piece.source.start < next {
+                return Err(format!(
+                    "The source ranges overlap at {}",
+                    piece.source.start
+                ));
+            }
+            pieces.push(Piece {
+                source: next..piece.source.start,
+                offset: 0,
+            });
+            next = piece.source.end;
+            pieces.push(piece);
+        }
+        pieces.push(Piece {
+            source: next..DOMAIN.end,
+            offset: 0,
+        });
+
+        Ok(Self::from_pieces(pieces))
+    }
+
+    /// Returns a map of `pieces`, which must be sorted and cover `DOMAIN`, after dropping empty
+    /// pieces and merging neighbours with the same offset.
+    fn from_pieces(pieces: Vec<Piece>) -> Self {
+        let mut merged: Vec<Piece> = Vec::new();
+
+        for piece in pieces.into_iter().filter(|piece| !piece.source.is_empty()) {
+            match merged.last_mut() {
+                Some(last) if last.offset == piece.offset => last.source.end = piece.source.end,
+                _ => merged.push(piece),
+            }
+        }
+
+        Self { pieces: merged }
+    }
+
+    /// Returns the index of the piece containing `v`.
+    fn piece_index(&self, v: Value) -> usize {
+        self.pieces.partition_point(|piece| piece.source.end <= v)
+    }
+
+    /// Returns the value that `v` is mapped to.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `v` is `Value::MAX`, which is outside `DOMAIN`.
+    pub fn apply(&self, v: Value) -> Value {
+        assert!(
+            DOMAIN.contains(&v),
+            "{} is outside the domain of the map",
+            v
+        );
+        self.pieces[self.piece_index(v)].shift(v)
+    }
+
+    /// Returns the map that applies `self` and then `next`.
+    pub fn then(&self, next: &Self) -> Self {
+        let mut pieces = Vec::new();
+
+        for piece in &self.pieces {
+            let destination = piece.destination();
+
+            // Split this piece wherever its destination crosses into another piece of `next`.
+            let mut start = destination.start;
+            for next_piece in &next.pieces[next.piece_index(start)..] {
+                if start >= destination.end {
+                    break;
+                }
+                let end = next_piece.source.end.min(destination.end);
+                pieces.push(Piece {
+                    source: piece.source.start + (start - destination.start)
+                        ..piece.source.start + (end - destination.start),
+                    offset: piece.offset + next_piece.offset,
+                });
+                start = end;
+            }
+        }
+
+        Self::from_pieces(pieces)
+    }
+
+    /// Returns the map that undoes this one, so that `m.inverse()?.apply(m.apply(v)) == v`.
+    ///
+    /// Returns an error if the map is not invertible, because two values are mapped to the same
+    /// value or some value in `DOMAIN` is not mapped to at all.
+    pub fn inverse(&self) -> Result<Self, String> {
+        let mut pieces: Vec<Piece> = self
+            .pieces
+            .iter()
+            .map(|piece| Piece {
+                source: piece.destination(),
+                offset: -piece.offset,
+            })
+            .collect();
+        pieces.sort_by_key(|piece| piece.source.start);
+
+        let mut next = DOMAIN.start;
+        for piece in &pieces {
+            match piece.source.start.cmp(&next) {
+                Ordering::Less => {
+                    return Err(format!(
+                        "The map is not invertible, as more than one value maps to {}",
+                        piece.source.start
+                    ))
+                }
+                Ordering::Greater => {
+                    return Err(format!(
+                        "The map is not invertible, as no value maps to {}",
+                        next
+                    ))
+                }
+                Ordering::Equal => next = piece.source.end,
+            }
+        }
+        if next != DOMAIN.end {
+            return Err(format!(
+                "The map is not invertible, as no value maps to {}",
+                next
+            ));
+        }
+
+        Ok(Self::from_pieces(pieces))
+    }
+
+    /// Returns every value that is mapped to `v`, in ascending order. This does not need the map
+    /// to be invertible.
+    pub fn preimage(&self, v: Value) -> Vec<Value> {
+        let mut values: Vec<Value> = self
+            .pieces
+            .iter()
+            .filter(|piece| piece.destination().contains(&v))
+            .map(|piece| (v as Offset - piece.offset) as Value)
+            .collect();
+        values.sort_unstable();
+        values
+    }
+
+    /// Returns the lowest value that any value in `ranges` is mapped to, or `None` if the ranges
+    /// are all empty.
+    pub fn min_over(&self, ranges: &[Range<Value>]) -> Option<Value> {
+        ranges
+            .iter()
+            .filter(|range| !range.is_empty())
+            .flat_map(|range| {
+                // Each piece shifts values up by the same amount, so the lowest value from each
+                // piece the range overlaps comes from the start of the overlap.
+                self.pieces[self.piece_index(range.start)..]
+                    .iter()
+                    .take_while(move |piece| piece.source.start < range.end)
+                    .map(move |piece| piece.shift(piece.source.start.max(range.start)))
+            })
+            .min()
+    }
+}
+
+/// Displays one line per piece in the form of the almanac, "destination source length", skipping
+/// the pieces that map values to themselves.
+impl fmt::Display for PiecewiseMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for piece in self.pieces.iter().filter(|piece| piece.offset != 0) {
+            writeln!(
+                f,
+                "{} {} {}",
+                piece.destination().start,
+                piece.source.start,
+                piece.source.end - piece.source.start
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A map from one `DataType` to another, as listed in the almanac.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Layer {
+    pub source: DataType,
+    pub destination: DataType,
+    pub map: PiecewiseMap,
+}
+
+impl FromStr for Layer {
+    type Err = String;
+
+    /// Parses a map from the almanac, such as:
+    ///
+    /// ```text
+    /// seed-to-soil map:
+    /// 50 98 2
+    /// 52 50 48
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().filter(|line| !line.trim().is_empty());
+
+        let header = lines.next().ok_or("A map cannot be empty")?;
+        let (source, destination) = header
+            .trim()
+            .strip_suffix(INPUT_TOKEN_MAP_SUFFIX)
+            .and_then(|types| types.split_once(INPUT_TOKEN_MAP_TYPE_SEPARATOR))
+            .ok_or_else(|| format!("Malformed map header '{}'", header))?;
+
+        let ranges = lines
+            .map(|line| {
+                let numbers = line
+                    .split_whitespace()
+                    .map(str::parse)
+                    .collect::<Result<Vec<Value>, _>>()
+                    .map_err(|e| format!("Malformed map line '{}': {}", line, e))?;
+                match numbers.as_slice() {
+                    [destination, source, length] => source
+                        .checked_add(*length)
+                        .map(|end| (*destination, *source..end))
+                        .ok_or_else(|| format!("The range in map line '{}' is too large", line)),
+                    _ => Err(format!("Expected 3 numbers in map line '{}'", line)),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            source: source.parse()?,
+            destination: destination.parse()?,
+            map: PiecewiseMap::from_ranges(&ranges)?,
+        })
+    }
+}
+
+/// The seeds and the maps between types listed in the almanac.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Almanac {
+    pub seeds: Vec<Value>,
+    pub layers: Vec<Layer>,
+}
+
+impl FromStr for Almanac {
+    type Err = String;
+
+    /// Parses the seeds line, such as "seeds: 79 14 55 13", then each map, separated by blank
+    /// lines.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.replace("\r\n", "\n");
+        let mut sections = s.split("\n\n").filter(|section| !section.trim().is_empty());
+
+        let seeds_line = sections.next().ok_or("The almanac is empty")?;
+        let seeds = seeds_line
+            .trim()
+            .strip_prefix(INPUT_TOKEN_SEEDS)
+            .ok_or_else(|| format!("Expected a list of seeds, not '{}'", seeds_line))?
+            .split_whitespace()
+            .map(|seed| {
+                seed.parse()
+                    .map_err(|_| format!("Invalid seed number '{}'", seed))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            seeds,
+            layers: sections.map(str::parse).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl Almanac {
+    /// Returns the seeds as ranges, reading the list of seeds as pairs of a start and a length.
+    ///
+    /// Returns an error if there is an odd number of seed values.
+    pub fn seed_ranges(&self) -> Result<Vec<Range<Value>>, String> {
+        if !self.seeds.len().is_multiple_of(2) {
+            return Err("The seed ranges must be pairs of a start and a length".to_string());
+        }
+
+        self.seeds
+            .chunks(2)
+            .map(|pair| {
+                pair[0]
+                    .checked_add(pair[1])
+                    .map(|end| pair[0]..end)
+                    .ok_or_else(|| format!("The seed range {} {} is too large", pair[0], pair[1]))
+            })
+            .collect()
+    }
+
+    /// Returns a single map from `from` values to `to` values, following the layers from `from`
+    /// to whichever type it maps to, and so on until `to`.
+    ///
+    /// Returns an error if there is no such chain of layers, or the layers loop back to a type
+    /// already passed through.
+    pub fn chain(&self, from: DataType, to: DataType) -> Result<PiecewiseMap, String> {
+        let mut map = PiecewiseMap::identity();
+        let mut current = from;
+        let mut visited = vec![from];
+
+        while current != to {
+            let layer = self
+                .layers
+                .iter()
+                .find(|layer| layer.source == current)
+                .ok_or_else(|| format!("There is no map from {} values", current))?;
+
+            map = map.then(&layer.map);
+            current = layer.destination;
+            if visited.contains(&current) {
+                return Err(format!(
+                    "The maps from {} values loop back to {} values",
+                    from, current
+                ));
+            }
+            visited.push(current);
+        }
+
+        Ok(map)
+    }
+}
+
+/// Options for querying the almanac, given on the command line.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RunOptions {
+    /// Whether to print the single map from seeds to locations.
+    pub print_map: bool,
+    /// Locations to find the seeds for.
+    pub locations: Vec<Value>,
+}
+
+impl RunOptions {
+    /// Parses the command line arguments `args`, which may contain "--print-map" and
+    /// "--location <value>", given once per location.
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut options = Self {
+            print_map: false,
+            locations: Vec::new(),
+        };
+
+        let mut args = args.iter();
+        while let Some(option) = args.next() {
+            match option.as_str() {
+                "--print-map" => options.print_map = true,
+                "--location" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("Missing value for option '{}'", option))?;
+                    options.locations.push(
+                        value
+                            .parse()
+                            .map_err(|_| format!("Invalid location '{}'", value))?,
+                    );
+                }
+                _ => return Err(format!("Unknown option '{}'", option)),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+// Test data based on examples on the challenge page.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "\
+seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4
+";
+
+    fn seed_to_location() -> PiecewiseMap {
+        let almanac: Almanac = TEST_INPUT.parse().unwrap();
+        almanac.chain(DataType::Seed, DataType::Location).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let almanac: Almanac = TEST_INPUT.parse().unwrap();
+
+        assert_eq!(almanac.seeds, vec![79, 14, 55, 13]);
+        assert_eq!(almanac.seed_ranges(), Ok(vec![79..93, 55..68]));
+        assert_eq!(almanac.layers.len(), 7);
+        assert_eq!(almanac.layers[0].source, DataType::Seed);
+        assert_eq!(almanac.layers[0].destination, DataType::Soil);
+        assert_eq!(almanac.layers[0].map.to_string(), "52 50 48\n50 98 2\n");
+
+        assert!("seeds: 1 2\n\nseed-to-dirt map:\n1 2 3"
+            .parse::<Almanac>()
+            .is_err());
+        assert!("seeds: 1 2\n\nseed-to-soil map:\n1 2"
+            .parse::<Almanac>()
+            .is_err());
+        assert!("seeds: 1 2\n\nseed-to-soil map:\n1 2 3\n8 3 2"
+            .parse::<Almanac>()
+            .is_err());
+        assert!("seed: 1 2".parse::<Almanac>().is_err());
+        assert!("seeds: 1 2 3"
+            .parse::<Almanac>()
+            .unwrap()
+            .seed_ranges()
+            .is_err());
+    }
+
+    #[test]
+    fn test_apply() {
+        let almanac: Almanac = TEST_INPUT.parse().unwrap();
+        let seed_to_soil = &almanac.layers[0].map;
+
+        assert_eq!(seed_to_soil.apply(49), 49);
+        assert_eq!(seed_to_soil.apply(53), 55);
+        assert_eq!(seed_to_soil.apply(98), 50);
+        assert_eq!(seed_to_soil.apply(100), 100);
+
+        let map = seed_to_location();
+        let locations: Vec<Value> = [79, 14, 55, 13].iter().map(|&s| map.apply(s)).collect();
+        assert_eq!(locations, [82, 43, 86, 35]);
+    }
+
+    #[test]
+    fn test_compose() {
+        let almanac: Almanac = TEST_INPUT.parse().unwrap();
+        let map = seed_to_location();
+
+        // Composing gives the same values as applying each layer in turn.
+        for seed in 0..200 {
+            let by_layer = almanac
+                .layers
+                .iter()
+                .fold(seed, |value, layer| layer.map.apply(value));
+            assert_eq!(map.apply(seed), by_layer);
+        }
+
+        let half = almanac
+            .chain(DataType::Seed, DataType::Water)
+            .unwrap()
+            .then(&almanac.chain(DataType::Water, DataType::Location).unwrap());
+        assert_eq!(half, map);
+        assert_eq!(
+            almanac.chain(DataType::Soil, DataType::Soil),
+            Ok(PiecewiseMap::identity())
+        );
+        assert!(almanac.chain(DataType::Location, DataType::Seed).is_err());
+    }
+
+    #[test]
+    fn test_chain_loop() {
+        let almanac: Almanac = "\
+seeds: 1 2
+
+seed-to-soil map:
+5 0 3
+
+soil-to-water map:
+9 5 3
+
+water-to-soil map:
+5 9 3
+"
+        .parse()
+        .unwrap();
+
+        assert_eq!(
+            almanac.chain(DataType::Seed, DataType::Location),
+            Err("The maps from seed values loop back to soil values".to_string())
+        );
+        assert!(almanac.chain(DataType::Seed, DataType::Water).is_ok());
+    }
+
+    #[test]
+    fn test_min_over() {
+        let almanac: Almanac = TEST_INPUT.parse().unwrap();
+        let map = seed_to_location();
+
+        let seeds: Vec<Range<Value>> = almanac.seeds.iter().map(|&s| s..s + 1).collect();
+        assert_eq!(map.min_over(&seeds), Some(35));
+        assert_eq!(map.min_over(&almanac.seed_ranges().unwrap()), Some(46));
+        assert_eq!(map.min_over(&[82..83, 5..5]), Some(46));
+        assert_eq!(map.min_over(&[5..5, 7..7]), None);
+
+        for range in [0..10, 13..80, 90..120] {
+            assert_eq!(
+                map.min_over(std::slice::from_ref(&range)),
+                range.map(|s| map.apply(s)).min()
+            );
+        }
+    }
+
+    #[test]
+    fn test_inverse() {
+        let map = seed_to_location();
+        let inverse = map.inverse().unwrap();
+
+        assert_eq!(inverse.apply(46), 82);
+        assert_eq!(map.preimage(46), vec![82]);
+        for seed in 0..200 {
+            assert_eq!(inverse.apply(map.apply(seed)), seed);
+        }
+        assert_eq!(inverse.inverse(), Ok(map.clone()));
+        assert_eq!(map.then(&inverse), PiecewiseMap::identity());
+
+        // Mapping 10..20 onto 0..10 leaves nothing mapped to 10..20, and 0..10 mapped twice.
+        let squash = PiecewiseMap::from_ranges(&[(0, 10..20)]).unwrap();
+        assert!(squash.inverse().is_err());
+        assert_eq!(squash.preimage(5), vec![5, 15]);
+        assert_eq!(squash.preimage(15), vec![]);
+        assert_eq!(squash.to_string(), "0 10 10\n");
+    }
+
+    #[test]
+    fn test_run_options() {
+        let args = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+
+        assert_eq!(
+            RunOptions::from_args(&args("--location 46 --print-map --location 35")),
+            Ok(RunOptions {
+                print_map: true,
+                locations: vec![46, 35],
+            })
+        );
+        assert!(RunOptions::from_args(&args("--location")).is_err());
+        assert!(RunOptions::from_args(&args("--location -1")).is_err());
+        assert!(RunOptions::from_args(&args("--seed 3")).is_err());
+    }
+}
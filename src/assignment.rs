@@ -0,0 +1,331 @@
+//! Assigning each of a set of rows to a different column, where each row may only be assigned
+//! one of its candidate columns. This is a bipartite matching problem, as in matching ticket
+//! fields to columns of ticket data in Advent of Code 2020 Day 16, and allergens to ingredients
+//! in Day 21.
+//!
+//! First, any row with only one candidate column claims it, which removes that column from
+//! every other row's candidates, and this is repeated until no more rows are settled. For
+//! well-behaved inputs that solves everything, but it can stall when every row still has several
+//! candidates. An assignment is then found using augmenting paths: each row in turn takes a free
+//! candidate column, or takes a column from another row that can move to a different column of
+//! its own, and so on. This always finds an assignment if one exists.
+//!
+//! Another assignment exists if, for some row, forbidding the column it was assigned still
+//! leaves an assignment, which is checked the same way, one row at a time.
+
+use std::fmt;
+
+/// The candidate columns for each row.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CandidateMatrix {
+    rows: Vec<String>,
+    columns: Vec<String>,
+    /// `candidates[r][c]` is whether column `c` is a candidate for row `r`.
+    candidates: Vec<Vec<bool>>,
+}
+
+impl CandidateMatrix {
+    /// Returns a matrix with rows and columns with the labels passed, where column `c` is a
+    /// candidate for row `r` if `is_candidate(r, c)` is true.
+    pub fn new(
+        rows: Vec<String>,
+        columns: Vec<String>,
+        is_candidate: impl Fn(usize, usize) -> bool,
+    ) -> Self {
+        let candidates = (0..rows.len())
+            .map(|row| {
+                (0..columns.len())
+                    .map(|column| is_candidate(row, column))
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            rows,
+            columns,
+            candidates,
+        }
+    }
+
+    pub fn rows(&self) -> &[String] {
+        &self.rows
+    }
+
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    pub fn is_candidate(&self, row: usize, column: usize) -> bool {
+        self.candidates[row][column]
+    }
+
+    /// Returns the column assigned to each row in an assignment where every row has a different
+    /// candidate column, or `None` if there is no such assignment.
+    pub fn find_assignment(&self) -> Option<Vec<usize>> {
+        let candidates = self.propagate_single_candidates()?;
+        let mut row_of_column = vec![None; self.columns.len()];
+
+        for row in 0..self.rows.len() {
+            let mut visited = vec![false; self.columns.len()];
+            if !augment(&candidates, row, &mut row_of_column, &mut visited) {
+                return None;
+            }
+        }
+
+        Some(columns_of_rows(&row_of_column, self.rows.len()))
+    }
+
+    /// Returns an assignment that differs from `assignment` for at least one row, or `None` if
+    /// `assignment` is the only one.
+    pub fn alternative_assignment(&self, assignment: &[usize]) -> Option<Vec<usize>> {
+        let candidates = self.propagate_single_candidates()?;
+
+        for (row, &column) in assignment.iter().enumerate() {
+            let mut candidates = candidates.clone();
+            candidates[row][column] = false;
+
+            let mut row_of_column = vec![None; self.columns.len()];
+            for (other_row, &other_column) in assignment.iter().enumerate() {
+                if other_row != row {
+                    row_of_column[other_column] = Some(other_row);
+                }
+            }
+
+            let mut visited = vec![false; self.columns.len()];
+            if augment(&candidates, row, &mut row_of_column, &mut visited) {
+                return Some(columns_of_rows(&row_of_column, self.rows.len()));
+            }
+        }
+
+        None
+    }
+
+    /// Returns the column assigned to each row in the only assignment where every row has a
+    /// different candidate column.
+    ///
+    /// Returns an error if there is no such assignment, or more than one.
+    pub fn solve(&self) -> Result<Vec<usize>, String> {
+        let assignment = self
+            .find_assignment()
+            .ok_or("No assignment of a different column to every row fits the candidates")?;
+
+        match self.alternative_assignment(&assignment) {
+            None => Ok(assignment),
+            Some(alternative) => {
+                let row = (0..assignment.len())
+                    .find(|&row| assignment[row] != alternative[row])
+                    .expect("An alternative assignment differs for some row");
+                Err(format!(
+                    "More than one assignment fits the candidates, as '{}' could be '{}' or '{}'",
+                    self.rows[row], self.columns[assignment[row]], self.columns[alternative[row]]
+                ))
+            }
+        }
+    }
+
+    /// Returns the candidates after repeatedly removing the only candidate of any row from every
+    /// other row, or `None` if a row is left with no candidates.
+    fn propagate_single_candidates(&self) -> Option<Vec<Vec<bool>>> {
+        let mut candidates = self.candidates.clone();
+        let mut settled = vec![false; self.rows.len()];
+
+        loop {
+            let mut progress = false;
+
+            for row in 0..self.rows.len() {
+                let mut row_candidates = (0..self.columns.len()).filter(|&c| candidates[row][c]);
+                match (row_candidates.next(), row_candidates.next()) {
+                    (None, _) => return None,
+                    (Some(column), None) if !settled[row] => {
+                        settled[row] = true;
+                        progress = true;
+                        for (other_row, other_candidates) in candidates.iter_mut().enumerate() {
+                            if other_row != row {
+                                other_candidates[column] = false;
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            if !progress {
+                return Some(candidates);
+            }
+        }
+    }
+}
+
+/// Tries to assign `row` a column, moving rows already assigned to other candidate columns if
+/// needed, and returns whether it succeeded. `visited` records the columns already tried.
+fn augment(
+    candidates: &[Vec<bool>],
+    row: usize,
+    row_of_column: &mut [Option<usize>],
+    visited: &mut [bool],
+) -> bool {
+    for column in 0..row_of_column.len() {
+        if !candidates[row][column] || visited[column] {
+            continue;
+        }
+        visited[column] = true;
+
+        let column_free = match row_of_column[column] {
+            None => true,
+            Some(other_row) => augment(candidates, other_row, row_of_column, visited),
+        };
+        if column_free {
+            row_of_column[column] = Some(row);
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Converts the row assigned to each column into the column assigned to each of `rows` rows.
+fn columns_of_rows(row_of_column: &[Option<usize>], rows: usize) -> Vec<usize> {
+    let mut assignment = vec![0; rows];
+    for (column, row) in row_of_column.iter().enumerate() {
+        if let Some(row) = row {
+            assignment[*row] = column;
+        }
+    }
+    assignment
+}
+
+/// Shows the matrix as a grid, with an 'X' for each candidate and a '.' otherwise. Columns that
+/// are not a candidate for any row are left out.
+impl fmt::Display for CandidateMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let columns: Vec<usize> = (0..self.columns.len())
+            .filter(|&column| self.candidates.iter().any(|row| row[column]))
+            .collect();
+        let row_width = self.rows.iter().map(String::len).max().unwrap_or(0);
+
+        write!(f, "{:row_width$}", "")?;
+        for &column in &columns {
+            write!(f, " {}", self.columns[column])?;
+        }
+        writeln!(f)?;
+
+        for (row, label) in self.rows.iter().enumerate() {
+            write!(f, "{:row_width$}", label)?;
+            for &column in &columns {
+                let mark = if self.candidates[row][column] {
+                    "X"
+                } else {
+                    "."
+                };
+                write!(f, " {:^width$}", mark, width = self.columns[column].len())?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(labels: &str) -> Vec<String> {
+        labels.split_whitespace().map(String::from).collect()
+    }
+
+    /// Returns a matrix where each row's candidates are listed as a string of column indexes,
+    /// e.g., "01".
+    fn matrix(candidates: &[&str], columns: usize) -> CandidateMatrix {
+        let rows = (0..candidates.len()).map(|r| format!("r{}", r)).collect();
+        let columns = (0..columns).map(|c| format!("c{}", c)).collect();
+
+        CandidateMatrix::new(rows, columns, |row, column| {
+            candidates[row].contains(&column.to_string())
+        })
+    }
+
+    #[test]
+    fn test_single_candidates() {
+        let matrix = matrix(&["1", "01", "012"], 3);
+
+        assert_eq!(matrix.find_assignment(), Some(vec![1, 0, 2]));
+        assert_eq!(matrix.solve(), Ok(vec![1, 0, 2]));
+    }
+
+    #[test]
+    fn test_no_single_candidates() {
+        // The example from the old day 16 code, where every row has several candidates, so
+        // removing single candidates makes no progress. Assignments are still found, and there
+        // is more than one.
+        let matrix = CandidateMatrix::new(
+            labels("c0 c1 c2"),
+            labels("class duration row train"),
+            |row, column| {
+                [
+                    [true, false, true, false],
+                    [false, true, true, false],
+                    [false, true, true, true],
+                ][row][column]
+            },
+        );
+
+        let assignment = matrix.find_assignment().unwrap();
+        assert!((0..3).all(|row| matrix.is_candidate(row, assignment[row])));
+        assert!((0..3).all(|row| !assignment[row + 1..].contains(&assignment[row])));
+        assert!(matrix.alternative_assignment(&assignment).is_some());
+        assert!(matrix.solve().is_err());
+    }
+
+    #[test]
+    fn test_more_columns_than_rows() {
+        let matrix = matrix(&["23", "2"], 5);
+
+        assert_eq!(matrix.solve(), Ok(vec![3, 2]));
+    }
+
+    #[test]
+    fn test_multiple_assignments() {
+        let matrix = matrix(&["01", "01", "2"], 3);
+        let assignment = matrix.find_assignment().unwrap();
+        let alternative = matrix.alternative_assignment(&assignment).unwrap();
+
+        assert_ne!(assignment, alternative);
+        assert_eq!(assignment[2], 2);
+        assert_eq!(alternative[2], 2);
+        assert!(matrix
+            .solve()
+            .unwrap_err()
+            .starts_with("More than one assignment fits the candidates, as 'r0' could be"));
+    }
+
+    #[test]
+    fn test_no_assignment() {
+        assert!(matrix(&["0", "0"], 2).solve().is_err());
+        assert!(matrix(&["01", "01", "01"], 3).solve().is_err());
+        assert!(matrix(&["", "0"], 2).find_assignment().is_none());
+    }
+
+    #[test]
+    fn test_display() {
+        let matrix = CandidateMatrix::new(
+            labels("dairy fish soy"),
+            labels("kfcds mxmxvkd sqjhc fvjkl"),
+            |r, c| {
+                [
+                    [false, true, false, false],
+                    [false, true, true, false],
+                    [false, false, true, true],
+                ][r][c]
+            },
+        );
+
+        assert_eq!(
+            matrix.to_string(),
+            "      mxmxvkd sqjhc fvjkl\n\
+             dairy    X      .     .  \n\
+             fish     X      X     .  \n\
+             soy      .      X     X  \n"
+        );
+    }
+}
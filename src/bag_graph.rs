@@ -0,0 +1,555 @@
+//! Bag containment rules, as described in Advent of Code 2020 Day 7.
+//! https://adventofcode.com/2020/day/7
+//!
+//! Each rule gives the bags, and how many of each, that a bag of some colour must directly
+//! contain. The rules form a directed graph from each bag to the bags it contains, which is
+//! indexed in both directions so that both the bags inside a bag and the bags that can hold it
+//! are quick to find.
+//!
+//! A bag that eventually contains itself would need infinitely many bags, so rules that form a
+//! cycle are rejected when parsing. The graph is then acyclic, so the number of bags inside each
+//! bag is found by adding up the contents of the bags it directly contains, each only once.
+
+use crate::CommandLine;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::str::FromStr;
+
+const INPUT_TOKEN_CONTAIN: &str = " bags contain ";
+const INPUT_TOKEN_NO_BAGS: &str = "no other bags";
+const INPUT_TOKEN_SEPARATOR: &str = ", ";
+
+pub type BagId = usize;
+pub type BagCount = u64;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BagGraph {
+    names: Vec<String>,
+    ids: HashMap<String, BagId>,
+    /// `contents[b]` lists the bags that bag `b` directly contains, and how many of each.
+    contents: Vec<Vec<(BagId, BagCount)>>,
+    /// `containers[b]` lists the bags that directly contain bag `b`.
+    containers: Vec<Vec<BagId>>,
+}
+
+/// The settings that can be changed from the command line.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RunOptions {
+    /// The bag to ask about.
+    pub bag: String,
+    /// Whether to print the rules in Graphviz DOT format.
+    pub dot: bool,
+    /// Whether to print the longest chain of bags nested inside `bag`.
+    pub chain: bool,
+}
+
+impl RunOptions {
+    /// Parses the command line arguments `args`, which may contain "--bag <name>" to ask about a
+    /// bag other than `default_bag`, "--dot" and "--chain".
+    ///
+    /// Returns an error if an option is not recognized or is missing its value.
+    pub fn from_args(args: &[String], default_bag: &str) -> Result<Self, String> {
+        let command_line = CommandLine::from_args(args, &["--dot", "--chain"], &["--bag"])?;
+
+        Ok(Self {
+            bag: command_line
+                .value("--bag")
+                .unwrap_or(default_bag)
+                .to_string(),
+            dot: command_line.flag("--dot"),
+            chain: command_line.flag("--chain"),
+        })
+    }
+}
+
+impl FromStr for BagGraph {
+    type Err = String;
+
+    /// Parses one rule per line, e.g., "light red bags contain 1 bright white bag, 2 muted yellow
+    /// bags." A bag that appears inside other bags but has no rule of its own contains no bags.
+    ///
+    /// Returns an error if a rule is malformed, a bag has more than one rule, or the rules form a
+    /// cycle.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut graph = Self {
+            names: Vec::new(),
+            ids: HashMap::new(),
+            contents: Vec::new(),
+            containers: Vec::new(),
+        };
+        let mut has_rule = Vec::new();
+
+        for line in s.lines().filter(|line| !line.trim().is_empty()) {
+            let malformed = || format!("Malformed bag rule: {}", line);
+            let (outer, inner) = line
+                .trim()
+                .strip_suffix('.')
+                .and_then(|rule| rule.split_once(INPUT_TOKEN_CONTAIN))
+                .ok_or_else(malformed)?;
+
+            let outer = graph.add_bag(outer);
+            has_rule.resize(graph.names.len(), false);
+            if has_rule[outer] {
+                return Err(format!(
+                    "Bag '{}' has more than one rule",
+                    graph.names[outer]
+                ));
+            }
+            has_rule[outer] = true;
+
+            if inner == INPUT_TOKEN_NO_BAGS {
+                continue;
+            }
+            for bags in inner.split(INPUT_TOKEN_SEPARATOR) {
+                let (count, name) = bags.split_once(' ').ok_or_else(malformed)?;
+                let count: BagCount = count.parse().map_err(|_| malformed())?;
+                let name = name
+                    .strip_suffix(" bags")
+                    .or_else(|| name.strip_suffix(" bag"))
+                    .ok_or_else(malformed)?;
+
+                let inner = graph.add_bag(name);
+                graph.contents[outer].push((inner, count));
+                graph.containers[inner].push(outer);
+            }
+        }
+
+        if let Some(cycle) = graph.find_cycle() {
+            let names: Vec<&str> = cycle.iter().map(|&bag| graph.name(bag)).collect();
+            return Err(format!(
+                "The bag rules form a cycle: {}",
+                names.join(" -> ")
+            ));
+        }
+
+        Ok(graph)
+    }
+}
+
+impl BagGraph {
+    /// Returns the `BagId` of bag `name`, adding it if it is new.
+    fn add_bag(&mut self, name: &str) -> BagId {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+
+        let id = self.names.len();
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        self.contents.push(Vec::new());
+        self.containers.push(Vec::new());
+        id
+    }
+
+    /// Returns the bags on a cycle, starting and ending with the same bag, if there is one.
+    fn find_cycle(&self) -> Option<Vec<BagId>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum State {
+            Unvisited,
+            OnPath,
+            Done,
+        }
+
+        fn visit(graph: &BagGraph, bag: BagId, state: &mut [State], path: &mut Vec<BagId>) -> bool {
+            state[bag] = State::OnPath;
+            path.push(bag);
+
+            for &(inner, _) in &graph.contents[bag] {
+                let inner_state = state[inner];
+                match inner_state {
+                    State::OnPath => {
+                        let start = path.iter().position(|&b| b == inner).unwrap_or(0);
+                        path.drain(..start);
+                        path.push(inner);
+                        return true;
+                    }
+                    State::Unvisited if visit(graph, inner, state, path) => return true,
+                    _ => {}
+                }
+            }
+
+            state[bag] = State::Done;
+            path.pop();
+            false
+        }
+
+        let mut state = vec![State::Unvisited; self.names.len()];
+        let mut path = Vec::new();
+        (0..self.names.len())
+            .find(|&bag| state[bag] == State::Unvisited && visit(self, bag, &mut state, &mut path))
+            .map(|_| path)
+    }
+
+    /// Returns the names of all bags, in the order they first appear in the rules.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn bag_id(&self, name: &str) -> Option<BagId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, bag: BagId) -> &str {
+        &self.names[bag]
+    }
+
+    /// Returns the `BagId` of `name`, or an error if there is no such bag.
+    fn existing_bag_id(&self, name: &str) -> Result<BagId, String> {
+        self.bag_id(name)
+            .ok_or_else(|| format!("There is no bag named '{}'", name))
+    }
+
+    /// Returns the bags that bag `name` directly contains, and how many of each.
+    pub fn contents(&self, name: &str) -> Result<Vec<(&str, BagCount)>, String> {
+        let bag = self.existing_bag_id(name)?;
+        Ok(self.contents[bag]
+            .iter()
+            .map(|&(inner, count)| (self.name(inner), count))
+            .collect())
+    }
+
+    /// Returns the names of every bag that can eventually contain bag `name`.
+    pub fn ancestors(&self, name: &str) -> Result<BTreeSet<&str>, String> {
+        let bag = self.existing_bag_id(name)?;
+        Ok(self.reachable(bag, |b| self.containers[b].clone()))
+    }
+
+    /// Returns the names of every bag that bag `name` eventually contains.
+    pub fn descendants(&self, name: &str) -> Result<BTreeSet<&str>, String> {
+        let bag = self.existing_bag_id(name)?;
+        Ok(self.reachable(bag, |b| {
+            self.contents[b].iter().map(|&(inner, _)| inner).collect()
+        }))
+    }
+
+    /// Returns the names of the bags reachable from `bag`, not including `bag` itself, where
+    /// `next` gives the bags one step away from a bag.
+    fn reachable(&self, bag: BagId, next: impl Fn(BagId) -> Vec<BagId>) -> BTreeSet<&str> {
+        let mut seen = vec![false; self.names.len()];
+        let mut to_visit = next(bag);
+
+        while let Some(b) = to_visit.pop() {
+            if !seen[b] {
+                seen[b] = true;
+                to_visit.extend(next(b));
+            }
+        }
+
+        (0..self.names.len())
+            .filter(|&b| seen[b])
+            .map(|b| self.name(b))
+            .collect()
+    }
+
+    /// Returns how many of each bag bag `name` contains in total, at any depth.
+    ///
+    /// Returns an error if there is no such bag, or a count is too large to hold.
+    pub fn total_contents_by_bag(&self, name: &str) -> Result<BTreeMap<&str, BagCount>, String> {
+        let bag = self.existing_bag_id(name)?;
+        // A bag's count is final once every bag containing it has been processed, so the bags
+        // are processed from the outside in.
+        let mut counts = vec![0 as BagCount; self.names.len()];
+        counts[bag] = 1;
+        for outer in self.topological_order(bag) {
+            for &(inner, count) in &self.contents[outer] {
+                let added = counts[outer]
+                    .checked_mul(count)
+                    .and_then(|added| counts[inner].checked_add(added))
+                    .ok_or_else(|| format!("Bag '{}' holds too many bags to count", name))?;
+                counts[inner] = added;
+            }
+        }
+
+        Ok((0..self.names.len())
+            .filter(|&b| b != bag && counts[b] > 0)
+            .map(|b| (self.name(b), counts[b]))
+            .collect())
+    }
+
+    /// Returns the total number of bags bag `name` contains, at any depth.
+    ///
+    /// Returns an error if there is no such bag, or the total is too large to hold.
+    pub fn total_contents(&self, name: &str) -> Result<BagCount, String> {
+        self.total_contents_by_bag(name)?
+            .values()
+            .try_fold(0 as BagCount, |total, &count| total.checked_add(count))
+            .ok_or_else(|| format!("Bag '{}' holds too many bags to count", name))
+    }
+
+    /// Returns `bag` and every bag inside it, with each bag before the bags it contains.
+    fn topological_order(&self, bag: BagId) -> Vec<BagId> {
+        fn visit(graph: &BagGraph, bag: BagId, seen: &mut [bool], order: &mut Vec<BagId>) {
+            seen[bag] = true;
+            for &(inner, _) in &graph.contents[bag] {
+                if !seen[inner] {
+                    visit(graph, inner, seen, order);
+                }
+            }
+            order.push(bag);
+        }
+
+        let mut seen = vec![false; self.names.len()];
+        let mut order = Vec::new();
+        visit(self, bag, &mut seen, &mut order);
+        order.reverse();
+        order
+    }
+
+    /// Returns the longest chain of bags nested inside each other, starting with bag `name`. If
+    /// several chains are the longest, the first found in rule order is returned.
+    pub fn longest_chain(&self, name: &str) -> Result<Vec<&str>, String> {
+        let bag = self.existing_bag_id(name)?;
+
+        // `next[b]` is the bag after `b` in the longest chain from `b`, and `length[b]` is the
+        // number of bags in that chain. Bags are processed innermost first.
+        let mut next = vec![None; self.names.len()];
+        let mut length = vec![1; self.names.len()];
+        for outer in self.topological_order(bag).into_iter().rev() {
+            for &(inner, _) in &self.contents[outer] {
+                if length[inner] + 1 > length[outer] {
+                    length[outer] = length[inner] + 1;
+                    next[outer] = Some(inner);
+                }
+            }
+        }
+
+        let mut chain = vec![self.name(bag)];
+        let mut current = bag;
+        while let Some(inner) = next[current] {
+            chain.push(self.name(inner));
+            current = inner;
+        }
+        Ok(chain)
+    }
+
+    /// Returns the rules in Graphviz DOT format, with an edge from each bag to each bag it
+    /// directly contains, labelled with how many.
+    pub fn to_dot(&self) -> String {
+        let mut dot = "digraph bags {\n".to_string();
+
+        for (bag, name) in self.names.iter().enumerate() {
+            dot += &format!("    \"{}\";\n", name);
+            for &(inner, count) in &self.contents[bag] {
+                dot += &format!(
+                    "    \"{}\" -> \"{}\" [label={}];\n",
+                    name,
+                    self.name(inner),
+                    count
+                );
+            }
+        }
+
+        dot + "}\n"
+    }
+}
+
+// Test data based on examples on the challenge page.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_RULES_0: &str = "\
+light red bags contain 1 bright white bag, 2 muted yellow bags.
+dark orange bags contain 3 bright white bags, 4 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
+dark olive bags contain 3 faded blue bags, 4 dotted black bags.
+vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
+faded blue bags contain no other bags.
+dotted black bags contain no other bags.";
+
+    const TEST_RULES_1: &str = "\
+shiny gold bags contain 2 dark red bags.
+dark red bags contain 2 dark orange bags.
+dark orange bags contain 2 dark yellow bags.
+dark yellow bags contain 2 dark green bags.
+dark green bags contain 2 dark blue bags.
+dark blue bags contain 2 dark violet bags.
+dark violet bags contain no other bags.";
+
+    fn names<'a>(names: impl IntoIterator<Item = &'a str>) -> BTreeSet<&'a str> {
+        names.into_iter().collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let graph: BagGraph = TEST_RULES_0.parse().unwrap();
+
+        assert_eq!(graph.names().len(), 9);
+        assert_eq!(
+            graph.contents("muted yellow"),
+            Ok(vec![("shiny gold", 2), ("faded blue", 9)])
+        );
+        assert_eq!(graph.contents("faded blue"), Ok(vec![]));
+        assert!(graph.contents("plaid purple").is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!("light red bags contain 1 bright white bag"
+            .parse::<BagGraph>()
+            .is_err());
+        assert!("light red bags hold 1 bright white bag."
+            .parse::<BagGraph>()
+            .is_err());
+        assert!("light red bags contain one bright white bag."
+            .parse::<BagGraph>()
+            .is_err());
+        assert!("light red bags contain 1 bright white."
+            .parse::<BagGraph>()
+            .is_err());
+        assert!(
+            "faded blue bags contain no other bags.\nfaded blue bags contain no other bags."
+                .parse::<BagGraph>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_cycle() {
+        let rules = "\
+light red bags contain 1 bright white bag.
+bright white bags contain 2 muted yellow bags, 1 faded blue bag.
+muted yellow bags contain 1 light red bag.
+faded blue bags contain no other bags.";
+
+        assert_eq!(
+            rules.parse::<BagGraph>(),
+            Err(
+                "The bag rules form a cycle: light red -> bright white -> muted yellow -> light red"
+                    .to_string()
+            )
+        );
+        assert!("shiny gold bags contain 1 shiny gold bag."
+            .parse::<BagGraph>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_ancestors() {
+        let graph: BagGraph = TEST_RULES_0.parse().unwrap();
+
+        assert_eq!(
+            graph.ancestors("shiny gold"),
+            Ok(names([
+                "bright white",
+                "muted yellow",
+                "light red",
+                "dark orange"
+            ]))
+        );
+        assert_eq!(graph.ancestors("light red"), Ok(names([])));
+    }
+
+    #[test]
+    fn test_descendants() {
+        let graph: BagGraph = TEST_RULES_0.parse().unwrap();
+
+        assert_eq!(
+            graph.descendants("shiny gold"),
+            Ok(names([
+                "dark olive",
+                "vibrant plum",
+                "faded blue",
+                "dotted black"
+            ]))
+        );
+        assert_eq!(graph.descendants("faded blue"), Ok(names([])));
+    }
+
+    #[test]
+    fn test_total_contents() {
+        let graph: BagGraph = TEST_RULES_0.parse().unwrap();
+        assert_eq!(graph.total_contents("shiny gold"), Ok(32));
+        assert_eq!(graph.total_contents("faded blue"), Ok(0));
+
+        let by_bag = graph.total_contents_by_bag("shiny gold").unwrap();
+        assert_eq!(by_bag["dark olive"], 1);
+        assert_eq!(by_bag["vibrant plum"], 2);
+        assert_eq!(by_bag["faded blue"], 3 + 2 * 5);
+        assert_eq!(by_bag["dotted black"], 4 + 2 * 6);
+
+        let graph: BagGraph = TEST_RULES_1.parse().unwrap();
+        assert_eq!(graph.total_contents("shiny gold"), Ok(126));
+        assert_eq!(
+            graph.total_contents_by_bag("shiny gold").unwrap()["dark violet"],
+            64
+        );
+    }
+
+    #[test]
+    fn test_total_contents_overflow() {
+        let rules = "\
+a bags contain 4294967296 b bags.
+b bags contain 4294967296 c bags.
+c bags contain no other bags.";
+        let graph: BagGraph = rules.parse().unwrap();
+
+        assert_eq!(graph.total_contents("b"), Ok(4294967296));
+        assert!(graph.total_contents("a").is_err());
+    }
+
+    #[test]
+    fn test_longest_chain() {
+        let graph: BagGraph = TEST_RULES_0.parse().unwrap();
+        assert_eq!(
+            graph.longest_chain("light red"),
+            Ok(vec![
+                "light red",
+                "bright white",
+                "shiny gold",
+                "dark olive",
+                "faded blue"
+            ])
+        );
+        assert_eq!(graph.longest_chain("faded blue"), Ok(vec!["faded blue"]));
+
+        let graph: BagGraph = TEST_RULES_1.parse().unwrap();
+        assert_eq!(graph.longest_chain("shiny gold").unwrap().len(), 7);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let graph: BagGraph = "\
+bright white bags contain 1 shiny gold bag.
+shiny gold bags contain 2 dark olive bags.
+dark olive bags contain no other bags."
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            graph.to_dot(),
+            "digraph bags {\n\
+            \x20   \"bright white\";\n\
+            \x20   \"bright white\" -> \"shiny gold\" [label=1];\n\
+            \x20   \"shiny gold\";\n\
+            \x20   \"shiny gold\" -> \"dark olive\" [label=2];\n\
+            \x20   \"dark olive\";\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn test_run_options() {
+        let args = |s: &str| -> Vec<String> { s.split_whitespace().map(String::from).collect() };
+
+        assert_eq!(
+            RunOptions::from_args(&[], "shiny gold"),
+            Ok(RunOptions {
+                bag: "shiny gold".to_string(),
+                dot: false,
+                chain: false
+            })
+        );
+        assert_eq!(
+            RunOptions::from_args(&args("--chain --bag faded --dot"), "shiny gold"),
+            Ok(RunOptions {
+                bag: "faded".to_string(),
+                dot: true,
+                chain: true
+            })
+        );
+        assert!(RunOptions::from_args(&args("--bag"), "shiny gold").is_err());
+        assert!(RunOptions::from_args(&args("--bags"), "shiny gold").is_err());
+    }
+}
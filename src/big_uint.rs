@@ -0,0 +1,303 @@
+//! An arbitrary-precision unsigned integer, for challenges whose numbers outgrow even 128-bit
+//! variables when no modulus is applied to keep them small.
+//!
+//! Only the operations the challenges need are provided: addition, subtraction, multiplication,
+//! and division by a value that fits in 64 bits. The number is held as base 2^32 digits, least
+//! significant first, with no trailing zero digits, so zero has no digits at all.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul};
+use std::str::FromStr;
+
+const DIGIT_BITS: u32 = u32::BITS;
+
+/// The largest power of ten that fits in a `u32`, used to convert to and from decimal in chunks.
+const DECIMAL_CHUNK: u32 = 1_000_000_000;
+const DECIMAL_CHUNK_DIGITS: usize = 9;
+
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct BigUint {
+    digits: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self { digits: Vec::new() }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// Returns the value as a `u64`, or `None` if it is too big.
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.digits[..] {
+            [] => Some(0),
+            [low] => Some(low.into()),
+            [low, high] => Some(u64::from(high) << DIGIT_BITS | u64::from(low)),
+            _ => None,
+        }
+    }
+
+    /// Returns `self - other`, or `None` if `other` is bigger than `self`.
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        if *self < *other {
+            return None;
+        }
+
+        let mut digits = Vec::with_capacity(self.digits.len());
+        let mut borrow = false;
+        for (i, &digit) in self.digits.iter().enumerate() {
+            let subtrahend = other.digits.get(i).copied().unwrap_or(0);
+            let (difference, borrow_a) = digit.overflowing_sub(subtrahend);
+            let (difference, borrow_b) = difference.overflowing_sub(borrow.into());
+            digits.push(difference);
+            borrow = borrow_a || borrow_b;
+        }
+
+        Some(Self::from_digits(digits))
+    }
+
+    /// Returns the quotient and remainder of dividing `self` by `divisor`, or `None` if `divisor`
+    /// is zero.
+    pub fn div_rem_u64(&self, divisor: u64) -> Option<(Self, u64)> {
+        if divisor == 0 {
+            return None;
+        }
+
+        let divisor = u128::from(divisor);
+        let mut quotient = vec![0; self.digits.len()];
+        let mut remainder = 0u128;
+        for (i, &digit) in self.digits.iter().enumerate().rev() {
+            let current = remainder << DIGIT_BITS | u128::from(digit);
+            // `remainder` is less than `divisor`, so the quotient digit fits in 32 bits.
+            quotient[i] = (current / divisor) as u32;
+            remainder = current % divisor;
+        }
+
+        Some((Self::from_digits(quotient), remainder as u64))
+    }
+
+    /// Returns the remainder of dividing `self` by `divisor`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `divisor` is zero.
+    pub fn rem_u64(&self, divisor: u64) -> u64 {
+        self.div_rem_u64(divisor)
+            .expect("Cannot divide a BigUint by zero")
+            .1
+    }
+
+    /// Returns a `BigUint` with the base 2^32 `digits` passed, least significant first, after
+    /// removing any leading zeros.
+    fn from_digits(mut digits: Vec<u32>) -> Self {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        Self { digits }
+    }
+
+    /// Returns `self * factor + addend`, used when converting from decimal.
+    fn mul_add_small(&self, factor: u32, addend: u32) -> Self {
+        let mut digits = Vec::with_capacity(self.digits.len() + 1);
+        let mut carry = u64::from(addend);
+        for &digit in &self.digits {
+            let product = u64::from(digit) * u64::from(factor) + carry;
+            digits.push(product as u32);
+            carry = product >> DIGIT_BITS;
+        }
+        digits.push(carry as u32);
+
+        Self::from_digits(digits)
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        Self::from_digits(vec![value as u32, (value >> DIGIT_BITS) as u32])
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.digits
+            .len()
+            .cmp(&other.digits.len())
+            .then_with(|| self.digits.iter().rev().cmp(other.digits.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: Self) -> BigUint {
+        let length = self.digits.len().max(other.digits.len());
+        let mut digits = Vec::with_capacity(length + 1);
+        let mut carry = 0u64;
+        for i in 0..length {
+            let sum = u64::from(self.digits.get(i).copied().unwrap_or(0))
+                + u64::from(other.digits.get(i).copied().unwrap_or(0))
+                + carry;
+            digits.push(sum as u32);
+            carry = sum >> DIGIT_BITS;
+        }
+        digits.push(carry as u32);
+
+        BigUint::from_digits(digits)
+    }
+}
+
+impl Add for BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: Self) -> BigUint {
+        &self + &other
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: Self) -> BigUint {
+        let mut digits = vec![0u32; self.digits.len() + other.digits.len()];
+        for (i, &a) in self.digits.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in other.digits.iter().enumerate() {
+                let product = u64::from(a) * u64::from(b) + u64::from(digits[i + j]) + carry;
+                digits[i + j] = product as u32;
+                carry = product >> DIGIT_BITS;
+            }
+            digits[i + other.digits.len()] = carry as u32;
+        }
+
+        BigUint::from_digits(digits)
+    }
+}
+
+impl Mul for BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: Self) -> BigUint {
+        &self * &other
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = String;
+
+    /// Parses a non-empty string of decimal digits.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!("'{}' is not an unsigned integer", s));
+        }
+
+        let first_chunk = match s.len() % DECIMAL_CHUNK_DIGITS {
+            0 => DECIMAL_CHUNK_DIGITS,
+            n => n,
+        };
+        let mut value = BigUint::zero();
+        let mut start = 0;
+        let mut end = first_chunk;
+        while start < s.len() {
+            let chunk: u32 = s[start..end].parse().unwrap();
+            let factor = 10u32.pow((end - start) as u32);
+            value = value.mul_add_small(factor, chunk);
+            start = end;
+            end += DECIMAL_CHUNK_DIGITS;
+        }
+
+        Ok(value)
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut chunks = Vec::new();
+        let mut remaining = self.clone();
+        while !remaining.is_zero() {
+            let (quotient, chunk) = remaining.div_rem_u64(DECIMAL_CHUNK.into()).unwrap();
+            chunks.push(chunk);
+            remaining = quotient;
+        }
+
+        let Some(most_significant) = chunks.pop() else {
+            return write!(f, "0");
+        };
+        let mut output = most_significant.to_string();
+        for chunk in chunks.iter().rev() {
+            output += &format!("{:0width$}", chunk, width = DECIMAL_CHUNK_DIGITS);
+        }
+        f.pad(&output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(s: &str) -> BigUint {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        for s in ["0", "7", "4294967296", "123456789012345678901234567890"] {
+            assert_eq!(big(s).to_string(), s);
+        }
+        assert_eq!(big("000123").to_string(), "123");
+        assert!("".parse::<BigUint>().is_err());
+        assert!("12a".parse::<BigUint>().is_err());
+        assert!("-1".parse::<BigUint>().is_err());
+    }
+
+    #[test]
+    fn test_to_u64() {
+        assert_eq!(BigUint::zero().to_u64(), Some(0));
+        assert_eq!(BigUint::from(u64::MAX).to_u64(), Some(u64::MAX));
+        assert_eq!(big("18446744073709551616").to_u64(), None);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = BigUint::from(u64::MAX);
+        assert_eq!((&a + &BigUint::from(1)).to_string(), "18446744073709551616");
+        assert_eq!(
+            (&a * &a).to_string(),
+            "340282366920938463426481119284349108225"
+        );
+        assert_eq!(
+            big("18446744073709551616")
+                .checked_sub(&BigUint::from(1))
+                .unwrap(),
+            a
+        );
+        assert_eq!(BigUint::from(1).checked_sub(&BigUint::from(2)), None);
+        assert_eq!(a.checked_sub(&a), Some(BigUint::zero()));
+    }
+
+    #[test]
+    fn test_division() {
+        let (quotient, remainder) = big("123456789012345678901234567890")
+            .div_rem_u64(1_000_000_007)
+            .unwrap();
+        assert_eq!(quotient.to_string(), "123456788148148161864");
+        assert_eq!(remainder, 197434842);
+        assert_eq!(big("100").rem_u64(7), 2);
+        assert_eq!(BigUint::from(5).div_rem_u64(0), None);
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(big("4294967296") > big("4294967295"));
+        assert!(big("5") < big("6"));
+        assert!(BigUint::zero() < BigUint::from(1));
+    }
+}
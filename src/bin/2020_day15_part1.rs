@@ -3,62 +3,55 @@
 //!
 //! Challenge part 1
 //!
-//! Follow the game rules explained in the challenge until the given game round is reached, at
+//! Follow the game rules explained in the challenge until the given game turn is reached, at
 //! which point the answer to the challenge is obtained.
 
-const CHALLENGE_INPUT: &str = "7,14,0,17,11,1,2";
-const STOP_AT_ROUND: usize = 2020;
-
-type GameState = Vec<u32>;
-
-/// Searches backwards through vector `gs` for up to two occurrences of `num`. If not found,
-/// returns (None, None). If two occurrences are found, both are returned, with the one with the
-/// lowest vector index returned first. If only one occurrence is found, returns 'None' and the
-/// vector index in that order.
-fn rsearch_for_number(gs: &GameState, num: u32) -> (Option<usize>, Option<usize>) {
-    let mut earlier = None;
-
-    let later = gs.iter().rposition(|n| *n == num);
-
-    if let Some(pos) = later {
-        earlier = gs[..pos].iter().rposition(|n| *n == num);
+use advent_of_code_rust::memory_game::{self, Number, RunOptions, Turn};
+use std::env;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+
+const INPUT_FILENAME: &str = "2020_day15_input.txt";
+const STOP_AT_TURN: Turn = 2020;
+
+/// Plays the game with `starting_numbers` for `turns` turns, returning the last number spoken. If
+/// `output_filename` is passed, every number spoken is also written to that file.
+///
+/// # Panics
+///
+/// Panics if there are no starting numbers, or the output file cannot be written.
+fn play_game(starting_numbers: &[Number], turns: Turn, output_filename: Option<&str>) -> Number {
+    let mut output = output_filename.map(|filename| {
+        BufWriter::new(File::create(filename).expect("Error creating output file"))
+    });
+
+    let result = memory_game::play(
+        starting_numbers,
+        turns,
+        output.as_mut().map(|output| output as &mut dyn Write),
+    )
+    .unwrap_or_else(|e| panic!("{}", e));
+
+    if let Some(mut output) = output {
+        output.flush().expect("Error writing output file");
     }
-
-    // println!("\tSearching {} in vec {:?} returns ({:?}, {:?})", num, gs, earlier, later);
-    (earlier, later)
-}
-
-fn initialize_game(start_string: &str) -> GameState {
-    start_string
-        .split(',')
-        .map(|n| n.parse().unwrap())
-        .collect()
+    result
 }
 
-fn play_one_round(game: &mut GameState) {
-    let last_number = game.last().unwrap();
-
-    let (earlier, later) = rsearch_for_number(game, *last_number);
+/// The starting numbers are read from the input file, unless "--start <numbers>" is passed as a
+/// command line argument. "--turns <turns>" changes the number of turns played, and
+/// "--output <file>" also writes every number spoken to the file passed.
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = RunOptions::from_args(&args, STOP_AT_TURN).unwrap_or_else(|e| panic!("{}", e));
 
-    if earlier.is_none() && later.is_some() {
-        game.push(0);
-    } else if earlier.is_some() && later.is_some() {
-        game.push((later.unwrap() - earlier.unwrap()) as u32);
-    } else {
-        panic!("Unexpected error - search could not find any occurrences of last number");
-    }
-}
+    let starting_numbers = options.starting_numbers.unwrap_or_else(|| {
+        let input_file = fs::read_to_string(INPUT_FILENAME).expect("Error reading input file");
+        memory_game::parse_starting_numbers(&input_file).unwrap_or_else(|e| panic!("{}", e))
+    });
 
-fn play_game(game: &mut GameState, play_until_round: usize) {
-    while game.len() < play_until_round {
-        play_one_round(game);
-    }
-}
-
-fn main() {
-    let mut game = initialize_game(CHALLENGE_INPUT);
-    play_game(&mut game, STOP_AT_ROUND);
-    println!("The answer to the challenge is {:?}", game.last().unwrap());
+    let result = play_game(&starting_numbers, options.turns, options.output.as_deref());
+    println!("The answer to the challenge is {:?}", result);
 }
 
 // Test data based on examples on the challenge page.
@@ -66,91 +59,20 @@ fn main() {
 mod tests {
     use super::*;
 
-    const TEST_INPUT_0: &str = "0,3,6";
-    const TEST_INPUT_1: &str = "1,3,2";
-    const TEST_INPUT_2: &str = "2,1,3";
-    const TEST_INPUT_3: &str = "1,2,3";
-    const TEST_INPUT_4: &str = "2,3,1";
-    const TEST_INPUT_5: &str = "3,2,1";
-    const TEST_INPUT_6: &str = "3,1,2";
-
-    #[test]
-    fn test_game_0() {
-        let mut game = initialize_game(TEST_INPUT_0);
-        play_game(&mut game, STOP_AT_ROUND);
-
-        assert_eq!(*game.last().unwrap(), 436);
-    }
-
-    #[test]
-    fn test_game_1() {
-        let mut game = initialize_game(TEST_INPUT_1);
-        play_game(&mut game, STOP_AT_ROUND);
-
-        assert_eq!(*game.last().unwrap(), 1);
-    }
-
-    #[test]
-    fn test_game_2() {
-        let mut game = initialize_game(TEST_INPUT_2);
-        play_game(&mut game, STOP_AT_ROUND);
-
-        assert_eq!(*game.last().unwrap(), 10);
-    }
-
-    #[test]
-    fn test_game_3() {
-        let mut game = initialize_game(TEST_INPUT_3);
-        play_game(&mut game, STOP_AT_ROUND);
-
-        assert_eq!(*game.last().unwrap(), 27);
-    }
-
-    #[test]
-    fn test_game_4() {
-        let mut game = initialize_game(TEST_INPUT_4);
-        play_game(&mut game, STOP_AT_ROUND);
-
-        assert_eq!(*game.last().unwrap(), 78);
-    }
-
-    #[test]
-    fn test_game_5() {
-        let mut game = initialize_game(TEST_INPUT_5);
-        play_game(&mut game, STOP_AT_ROUND);
-
-        assert_eq!(*game.last().unwrap(), 438);
-    }
-
-    #[test]
-    fn test_game_6() {
-        let mut game = initialize_game(TEST_INPUT_6);
-        play_game(&mut game, STOP_AT_ROUND);
-
-        assert_eq!(*game.last().unwrap(), 1836);
-    }
-
-    #[test]
-    fn test_rsearch_for_number_0() {
-        assert_eq!(
-            rsearch_for_number(&vec![16, 26, 16, 7, 9, 0, 16, 44], 99),
-            (None, None)
-        );
-    }
-
-    #[test]
-    fn test_rsearch_for_number_1() {
-        assert_eq!(
-            rsearch_for_number(&vec![16, 26, 16, 7, 9, 0, 16, 44], 26),
-            (None, Some(1))
-        );
-    }
-
     #[test]
-    fn test_rsearch_for_number_2() {
-        assert_eq!(
-            rsearch_for_number(&vec![16, 26, 16, 7, 9, 0, 16, 44], 16),
-            (Some(2), Some(6))
-        );
+    fn test_play_game() {
+        let expected = [
+            ([0, 3, 6], 436),
+            ([1, 3, 2], 1),
+            ([2, 1, 3], 10),
+            ([1, 2, 3], 27),
+            ([2, 3, 1], 78),
+            ([3, 2, 1], 438),
+            ([3, 1, 2], 1836),
+        ];
+
+        for (starting_numbers, result) in expected {
+            assert_eq!(play_game(&starting_numbers, STOP_AT_TURN, None), result);
+        }
     }
 }
@@ -9,6 +9,8 @@
 //! the named ticket fields provided in the input, and ticket data. Then return the elements of my
 //! ticket in the manner required by the challenge.
 
+use advent_of_code_rust::assignment::CandidateMatrix;
+use advent_of_code_rust::CommandLine;
 use std::collections::HashSet;
 use std::fs;
 use std::ops::RangeInclusive;
@@ -157,33 +159,34 @@ fn discard_invalid_tickets(data: &mut ChallengeData) {
 /// values. For example, if a field has ranges 0..3 and 7..9 and the 'nearby' ticket values are
 /// 8, 3 and 1, the field will be included in the vector returned.
 fn map_one_ticket_field(data: &ChallengeData, column: usize) -> Vec<&TicketField> {
-    // println!("Attempting to map column {} in 'nearby' tickets to a field definition.", column);
-    let mut possibilities = Vec::new();
-    for field in &data.field_definitions {
-        possibilities.push(field);
-    }
-
-    for ticket in &data.nearby_tickets {
-        let mut remaining_possibilities = Vec::new();
-        let ticket_val = ticket[column];
-        // println!("Checking ticket value {}", ticket_val);
-
-        for p in &possibilities {
-            // print!("\tChecking against possibility: {:?}. ", p);
-            if p.range0.contains(&ticket_val) || p.range1.contains(&ticket_val) {
-                // println!("\tStill a possibility.");
-                remaining_possibilities.push(*p);
-                // } else {
-                // println!("\tNot possible - discarding.");
-            }
-        }
+    data.field_definitions
+        .iter()
+        .filter(|field| {
+            data.nearby_tickets.iter().all(|ticket| {
+                field.range0.contains(&ticket[column]) || field.range1.contains(&ticket[column])
+            })
+        })
+        .collect()
+}
 
-        possibilities = remaining_possibilities;
-        if possibilities.len() == 1 {
-            break;
-        }
-    }
-    possibilities
+/// Returns a matrix with a row for each column of data in the 'nearby' tickets, and a column for
+/// each field definition, showing which fields each column of data could be.
+fn candidate_matrix(data: &ChallengeData) -> CandidateMatrix {
+    let num_of_fields = data.field_definitions.len();
+    let candidates: Vec<Vec<&TicketField>> = (0..num_of_fields)
+        .map(|column| map_one_ticket_field(data, column))
+        .collect();
+
+    CandidateMatrix::new(
+        (0..num_of_fields)
+            .map(|column| format!("column {}", column))
+            .collect(),
+        data.field_definitions
+            .iter()
+            .map(|field| field.name.clone())
+            .collect(),
+        |column, field| candidates[column].contains(&&data.field_definitions[field]),
+    )
 }
 
 /// Returns the field definition associated with each column of data in the 'nearby' tickets. The
@@ -192,98 +195,46 @@ fn map_one_ticket_field(data: &ChallengeData, column: usize) -> Vec<&TicketField
 /// # Panics
 ///
 /// Panics if every column cannot be uniquely mapped to a definition.
-//
-// TODO
-// Although the following code works for the example and my test input, it only finds a solution
-// if at least one column has only one possible field on each loop iteration. Situations can occur
-// where this is not the case but a solution can still be found. This happens when a field
-// definition is only listed in one column's remaining possibilities, but no column has only one
-// possibility. For example:
-// Column 0 possibilities: class, row
-// Column 1 possibilities: duration, row
-// Column 2 possibilities: duration, row, train
-//
-// All columns have multiple possibilities, but it can be seen that 'class' only appears once, for
-// column 0, and 'train' for column 2. Thus, a solution can be found. The following code could be
-// enhanced to perform this check before giving up and panicking.
 fn map_all_ticket_fields(data: &ChallengeData) -> Vec<&TicketField> {
-    let mut possibilities = Vec::new();
-    let num_of_fields = data.field_definitions.len();
-
-    for col in 0..num_of_fields {
-        possibilities.push(map_one_ticket_field(data, col));
-    }
-
-    let mut column_verified = Vec::new();
-    column_verified.resize(num_of_fields, false);
-
-    let mut verified_columns_total = usize::MAX;
-    loop {
-        // println!("column_verified at loop start {:#?}", column_verified);
-
-        for col in 0..num_of_fields {
-            // Skip columns that already have mappings.
-            if column_verified[col] {
-                continue;
-            }
-
-            // If previous iterations of this loop have eliminated all but one possibility for this
-            // column, update the state to indicate this. More importantly, remove this field from
-            // the possibilities for all *other* columns.
-            if possibilities[col].len() == 1 {
-                column_verified[col] = true;
-
-                for other_col in 0..num_of_fields {
-                    if (other_col == col) || (column_verified[other_col]) {
-                        continue;
-                    }
-
-                    if let Some(idx_to_remove) = possibilities[other_col]
-                        .iter()
-                        .position(|&i| i == possibilities[col][0])
-                    {
-                        possibilities[other_col].remove(idx_to_remove);
-                    }
-                }
-            }
-        }
-
-        let new_verified_columns_total = column_verified.iter().filter(|&n| *n).count();
-        if new_verified_columns_total == verified_columns_total {
-            panic!("Cannot uniquely map every column of data in 'nearby' tickets to a field defn");
-        } else if new_verified_columns_total == num_of_fields {
-            break;
-        } else {
-            verified_columns_total = new_verified_columns_total;
-        }
-    }
+    let mapping = candidate_matrix(data)
+        .solve()
+        .unwrap_or_else(|e| panic!("Cannot map the columns of the tickets to fields: {}", e));
+
+    mapping
+        .iter()
+        .map(|&field| &data.field_definitions[field])
+        .collect()
+}
 
-    possibilities.iter().map(|v| v[0]).collect()
+/// Returns the product of the values in my ticket for the fields whose names start with
+/// "departure", once invalid tickets have been discarded from `data`.
+fn perform_work(data: &ChallengeData) -> u64 {
+    let mapping = map_all_ticket_fields(data);
+
+    mapping
+        .iter()
+        .zip(&data.my_ticket)
+        .filter(|(field, _)| field.name.starts_with("departure"))
+        .map(|(_, &value)| value as u64)
+        .product()
 }
 
-fn perform_work(input: &str) -> u64 {
-    let mut data = ChallengeData::from_string(input);
-    discard_invalid_tickets(&mut data);
+/// Prints the product of my ticket's departure fields. "--matrix" first prints the fields each
+/// column of ticket data could be, to show how the columns were matched to fields.
+fn main() {
+    let command_line =
+        CommandLine::from_env(&["--matrix"], &[]).unwrap_or_else(|e| panic!("{}", e));
 
-    let mapping = map_all_ticket_fields(&data);
+    let input_file = fs::read_to_string(INPUT_FILENAME).expect("Error reading input file");
 
-    let mut answer = 1;
+    let mut data = ChallengeData::from_string(&input_file);
+    discard_invalid_tickets(&mut data);
 
-    let mapping_length = mapping.len();
-    #[allow(clippy::needless_range_loop)]
-    for i in 0..mapping_length {
-        if mapping[i].name.starts_with("departure") {
-            answer *= data.my_ticket[i] as u64;
-        }
+    if command_line.flag("--matrix") {
+        print!("{}", candidate_matrix(&data));
     }
 
-    answer
-}
-
-fn main() {
-    let input_file = fs::read_to_string(INPUT_FILENAME).expect("Error reading input file");
-
-    let answer = perform_work(&input_file);
+    let answer = perform_work(&data);
     println!("The answer to the challenge is {:?}", answer);
 }
 
@@ -405,4 +356,30 @@ nearby tickets:
             ]
         );
     }
+
+    #[test]
+    fn determine_field_mapping() {
+        let mut data = ChallengeData::from_string(TEST_INPUT_1);
+        discard_invalid_tickets(&mut data);
+
+        let names: Vec<&str> = map_all_ticket_fields(&data)
+            .iter()
+            .map(|field| field.name.as_str())
+            .collect();
+        assert_eq!(names, ["row", "class", "seat"]);
+    }
+
+    #[test]
+    fn test_candidate_matrix() {
+        let mut data = ChallengeData::from_string(TEST_INPUT_1);
+        discard_invalid_tickets(&mut data);
+
+        assert_eq!(
+            candidate_matrix(&data).to_string(),
+            "         class row seat\n\
+             column 0   .    X   .  \n\
+             column 1   X    X   .  \n\
+             column 2   X    X   X  \n"
+        );
+    }
 }
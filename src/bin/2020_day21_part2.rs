@@ -6,9 +6,10 @@
 //! Given an input file listing foods with ingredient and incomplete allergen information,
 //! determine the mapping between ingredients and allergens.
 
+use advent_of_code_rust::assignment::CandidateMatrix;
+use advent_of_code_rust::CommandLine;
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::iter::FromIterator;
 
 const INPUT_FILENAME: &str = "2020_day21_input.txt";
 const INPUT_DELIMITER: &str = " (contains ";
@@ -87,101 +88,75 @@ impl<'a> IngredientSets<'a> {
     }
 }
 
-/// An `AllergenMapTransition` is a transition object used to determine the unique mapping between
-/// each allergen and the one ingredient that contains it. The object maps each allergen to the set
-/// of ingredients that it could be in. A method is provided to iteratively narrow this down until
-/// each allergen maps to exactly one ingredient.
-#[derive(Clone, Debug, PartialEq)]
-struct AllergenMapTransition<'a> {
-    map: HashMap<&'a str, HashSet<&'a str>>,
+/// Returns a matrix with a row for each allergen and a column for each ingredient, both in
+/// alphabetical order, showing the ingredients each allergen could be in. An allergen could be in
+/// an ingredient if every food listing the allergen contains that ingredient.
+fn candidate_matrix(ingredient_sets: &IngredientSets) -> CandidateMatrix {
+    let mut allergens: Vec<&str> = ingredient_sets.sets.keys().cloned().collect();
+    allergens.sort_unstable();
+
+    let mut ingredients: Vec<&str> = ingredient_sets
+        .sets
+        .values()
+        .flatten()
+        .flatten()
+        .cloned()
+        .collect::<HashSet<&str>>()
+        .into_iter()
+        .collect();
+    ingredients.sort_unstable();
+
+    CandidateMatrix::new(
+        allergens.iter().map(|a| a.to_string()).collect(),
+        ingredients.iter().map(|i| i.to_string()).collect(),
+        |allergen, ingredient| {
+            ingredient_sets.sets[allergens[allergen]]
+                .iter()
+                .all(|food| food.contains(ingredients[ingredient]))
+        },
+    )
 }
 
-impl<'a> AllergenMapTransition<'a> {
-    /// Reduce the `IngredientSets` input so that each allergen maps to the set of ingredients it
-    /// could be in.
-    fn new(ingredient_sets: &'a IngredientSets) -> Self {
-        let mut map = HashMap::new();
-        for (allergen, ingredients) in &ingredient_sets.sets {
-            map.insert(
-                *allergen,
-                ingredients
-                    .clone()
-                    .iter()
-                    .fold(ingredients[0].clone(), |acc, hs| {
-                        acc.intersection(hs).cloned().collect::<HashSet<&str>>()
-                    }),
-            );
-        }
-
-        Self { map }
-    }
-
-    /// Repeatedly iterates over the map of allergens to ingredients until each allergen has
-    /// exactly one ingredient. Returns a `HashMap` of this allergen to ingredient mapping.
-    /// Consumes this object as all useful data is moved into the result returned.
-    ///
-    /// # Panics
-    ///
-    /// The challenge states that each allergen maps to exactly one ingredient, but if such a
-    /// mapping cannot be found, the function panics.
-    fn solve(mut self) -> HashMap<&'a str, &'a str> {
-        let mut solved_allergens: HashMap<&str, &str> = HashMap::new();
-
-        while solved_allergens.len() < self.map.len() {
-            let mut solved_this_turn: HashSet<&str> = HashSet::new();
-
-            for (allergen, ingredients) in &self.map {
-                if solved_allergens.contains_key(allergen) {
-                    continue;
-                }
-
-                if ingredients.len() == 1 {
-                    solved_this_turn.insert(*ingredients.iter().nth(0).unwrap());
-                    solved_allergens.insert(allergen, *ingredients.iter().nth(0).unwrap());
-                }
-            }
-
-            assert!(
-                !solved_this_turn.is_empty(),
-                "Could not uniquely map allergens to ingredients"
-            );
-
-            for (allergen, ingredients) in self.map.clone() {
-                let new_ingredients: HashSet<&str> = HashSet::from_iter(
-                    ingredients
-                        .difference(&solved_this_turn)
-                        .cloned()
-                        .collect::<Vec<&str>>(),
-                );
-
-                // println!("new_ingredients for allergen {} are\n{:#?}", &allergen, &new_ingredients);
-
-                let tmp = self.map.get_mut(allergen).unwrap();
-                *tmp = new_ingredients;
-            }
-        }
-        solved_allergens
-    }
+/// Returns the ingredient containing each allergen, with the allergens in alphabetical order.
+///
+/// # Panics
+///
+/// The challenge states that each allergen maps to exactly one ingredient, but if such a
+/// mapping cannot be found, or there is more than one, the function panics.
+fn solve(matrix: &CandidateMatrix) -> Vec<&str> {
+    let mapping = matrix
+        .solve()
+        .unwrap_or_else(|e| panic!("Could not uniquely map allergens to ingredients: {}", e));
+
+    mapping
+        .iter()
+        .map(|&ingredient| matrix.columns()[ingredient].as_str())
+        .collect()
 }
 
 fn do_challenge(input: &str) -> String {
     let foods = TokenizedInput::parse_input(input);
     let ing_sets = IngredientSets::map_allergens(&foods);
-    let initial_mapping = AllergenMapTransition::new(&ing_sets);
-    let mapping = initial_mapping.solve();
 
-    let mut allergens_ingredients: Vec<(&str, &str)> = mapping.clone().into_iter().collect();
-    allergens_ingredients.sort();
-    allergens_ingredients
-        .iter()
-        .map(|(_, ingredient)| *ingredient)
-        .collect::<Vec<&str>>()
-        .join(",")
+    solve(&candidate_matrix(&ing_sets)).join(",")
 }
 
+/// Prints the ingredients containing allergens. "--matrix" first prints a grid of the ingredients
+/// each allergen could be in, before the allergens are narrowed down to one ingredient each.
 fn main() {
+    let command_line =
+        CommandLine::from_env(&["--matrix"], &[]).unwrap_or_else(|e| panic!("{}", e));
+
     let input_file = fs::read_to_string(INPUT_FILENAME).expect("Error reading input file");
 
+    if command_line.flag("--matrix") {
+        let foods = TokenizedInput::parse_input(&input_file);
+        print!(
+            "{}",
+            candidate_matrix(&IngredientSets::map_allergens(&foods))
+        );
+    }
+
     let answer = do_challenge(&input_file);
 
     println!(
@@ -244,21 +219,15 @@ sqjhc mxmxvkd sbzzf (contains fish)";
     fn initial_mapping() {
         let foods = TokenizedInput::parse_input(TEST_INPUT);
         let ing_sets = IngredientSets::map_allergens(&foods);
-        let initial_mapping = AllergenMapTransition::new(&ing_sets);
+        let matrix = candidate_matrix(&ing_sets);
 
-        println!("{:#?}", initial_mapping);
-
-        assert_eq!(
-            initial_mapping.map["soy"],
-            ["fvjkl", "sqjhc"].iter().cloned().collect::<HashSet<_>>()
-        );
-        assert_eq!(
-            initial_mapping.map["dairy"],
-            ["mxmxvkd"].iter().cloned().collect::<HashSet<_>>()
-        );
+        assert_eq!(matrix.rows(), ["dairy", "fish", "soy"]);
         assert_eq!(
-            initial_mapping.map["fish"],
-            ["sqjhc", "mxmxvkd"].iter().cloned().collect::<HashSet<_>>()
+            matrix.to_string(),
+            "      fvjkl mxmxvkd sqjhc\n\
+             dairy   .      X      .  \n\
+             fish    .      X      X  \n\
+             soy     X      .      X  \n"
         );
     }
 
@@ -266,12 +235,9 @@ sqjhc mxmxvkd sbzzf (contains fish)";
     fn determine_allergen_to_ingredient_map() {
         let foods = TokenizedInput::parse_input(TEST_INPUT);
         let ing_sets = IngredientSets::map_allergens(&foods);
-        let initial_mapping = AllergenMapTransition::new(&ing_sets);
-        let mapping = initial_mapping.solve();
+        let matrix = candidate_matrix(&ing_sets);
 
-        assert_eq!(mapping["dairy"], "mxmxvkd");
-        assert_eq!(mapping["fish"], "sqjhc");
-        assert_eq!(mapping["soy"], "fvjkl");
+        assert_eq!(solve(&matrix), ["mxmxvkd", "sqjhc", "fvjkl"]);
     }
 
     #[test]
@@ -6,24 +6,16 @@
 //! Flip hexagonal tiles within a large grid of tiles following instructions in the input file, and
 //! count the number of flipped tiles to answer the challenge.
 //
-// The grid of hexagons is represented with a 2D coordinate system where the reference tile in the
-// middle is (0, 0), east is +x and north is +y. Moves east and west are x+2 and x-2
-// respectively. Other moves are a combination of 1 unit of move in both x and y directions, e.g.,
-// north-east is x+1 and y+1. Some grid positions are invalid as tile locations, e.g., x=0, y=1,
-// and are not used.
+// The tiles are pointy-topped hexagons, so each line of input is a path of "e", "se", "sw", "w",
+// "nw" and "ne" moves from the reference tile in the middle of the floor.
 
+use advent_of_code_rust::hex_grid::{Hex, Orientation};
 use std::collections::HashSet;
 use std::fs;
 
 const INPUT_FILENAME: &str = "2020_day24_input.txt";
 
-type FlippedTileGrid = HashSet<Position>;
-
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-struct Position {
-    x: i16,
-    y: i16,
-}
+type FlippedTileGrid = HashSet<Hex>;
 
 fn parse_input(input: &str) -> FlippedTileGrid {
     let mut grid = FlippedTileGrid::new();
@@ -40,66 +32,21 @@ fn parse_input(input: &str) -> FlippedTileGrid {
 
 /// Read one line of input, representing one set of moves, and return the position of the resultant
 /// tile.
-fn parse_one_line(line: &str) -> Position {
-    let mut x = 0;
-    let mut y = 0;
-    let mut index = 0;
-    let input: Vec<char> = line.chars().collect();
-    let input_len = input.len();
-
-    while index < input_len {
-        match input[index] {
-            'n' => {
-                y += 1;
-                match input[index + 1] {
-                    'e' => {
-                        x += 1;
-                    }
-                    'w' => {
-                        x -= 1;
-                    }
-                    _ => {
-                        panic!("Unrecognized input after 'n' of '{}'", &input[index + 1]);
-                    }
-                }
-                index += 2;
-            }
-            's' => {
-                y -= 1;
-                match input[index + 1] {
-                    'e' => {
-                        x += 1;
-                    }
-                    'w' => {
-                        x -= 1;
-                    }
-                    _ => {
-                        panic!("Unrecognized input after 's' of '{}'", &input[index + 1]);
-                    }
-                }
-                index += 2;
-            }
-            'e' => {
-                x += 2;
-                index += 1;
-            }
-            'w' => {
-                x -= 2;
-                index += 1;
-            }
-            _ => {
-                panic!("Unrecognized input '{}'", &input[index]);
-            }
-        }
-    }
-
-    // println!("parse_one_line returning position ({}, {})", x, y);
-    Position { x, y }
+///
+/// # Panics
+///
+/// Panics if the line contains anything other than the six directions.
+fn parse_one_line(line: &str) -> Hex {
+    let path = Orientation::PointyTop
+        .parse_path(line)
+        .unwrap_or_else(|e| panic!("{}", e));
+
+    Hex::ORIGIN.travel(&path)
 }
 
 /// Flips the tile at position `pos` within `grid`. If the tile is already present in `grid`,
 /// this flip will return it to its starting orientation, and it is therefore removed from `grid`.
-fn flip_tile(grid: &mut FlippedTileGrid, pos: &Position) {
+fn flip_tile(grid: &mut FlippedTileGrid, pos: &Hex) {
     if grid.get(pos).is_some() {
         grid.remove(pos);
     } else {
@@ -144,16 +91,16 @@ wseweeenwnesenwwwswnew";
 
     #[test]
     fn test_parse_one_line() {
-        assert_eq!(Position { x: 1, y: -1 }, parse_one_line("esew"));
-        assert_eq!(Position { x: 0, y: 0 }, parse_one_line("nwwswee"));
+        assert_eq!(Hex::new(0, 1), parse_one_line("esew"));
+        assert_eq!(Hex::new(0, 0), parse_one_line("nwwswee"));
 
         assert_eq!(
-            Position { x: -4, y: -2 },
+            Hex::new(-3, 2),
             parse_one_line("sesenwnenenewseeswwswswwnenewsewsw")
         );
 
         assert_eq!(
-            Position { x: -1, y: 3 },
+            Hex::new(1, -3),
             parse_one_line("neeenesenwnwwswnenewnwwsewnenwseswesw")
         );
     }
@@ -7,24 +7,17 @@
 //! Perform multiple rounds of tile flipping following the rules, then count the number of flipped
 //! tiles to answer the challenge.
 //
-// The grid of hexagons is represented with a 2D coordinate system where the reference tile in the
-// middle is (0, 0), east is +x and north is +y. Moves east and west are x+2 and x-2
-// respectively. Other moves are a combination of 1 unit of move in both x and y directions, e.g.,
-// north-east is x+1 and y+1. Some grid positions are invalid as tile locations, e.g., x=0, y=1,
-// and are not used.
+// The tiles are pointy-topped hexagons, so each line of input is a path of "e", "se", "sw", "w",
+// "nw" and "ne" moves from the reference tile in the middle of the floor.
 
+use advent_of_code_rust::hex_grid::{self, Hex, Orientation};
+use advent_of_code_rust::CommandLine;
 use std::collections::HashSet;
 use std::fs;
 
 const INPUT_FILENAME: &str = "2020_day24_input.txt";
 
-type FlippedTileGrid = HashSet<Position>;
-
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-struct Position {
-    x: i16,
-    y: i16,
-}
+type FlippedTileGrid = HashSet<Hex>;
 
 fn parse_input(input: &str) -> FlippedTileGrid {
     let mut grid = FlippedTileGrid::new();
@@ -41,66 +34,21 @@ fn parse_input(input: &str) -> FlippedTileGrid {
 
 /// Read one line of input, representing one set of moves, and return the position of the resultant
 /// tile.
-fn parse_one_line(line: &str) -> Position {
-    let mut x = 0;
-    let mut y = 0;
-    let mut index = 0;
-    let input: Vec<char> = line.chars().collect();
-    let input_len = input.len();
-
-    while index < input_len {
-        match input[index] {
-            'n' => {
-                y += 1;
-                match input[index + 1] {
-                    'e' => {
-                        x += 1;
-                    }
-                    'w' => {
-                        x -= 1;
-                    }
-                    _ => {
-                        panic!("Unrecognized input after 'n' of '{}'", &input[index + 1]);
-                    }
-                }
-                index += 2;
-            }
-            's' => {
-                y -= 1;
-                match input[index + 1] {
-                    'e' => {
-                        x += 1;
-                    }
-                    'w' => {
-                        x -= 1;
-                    }
-                    _ => {
-                        panic!("Unrecognized input after 's' of '{}'", &input[index + 1]);
-                    }
-                }
-                index += 2;
-            }
-            'e' => {
-                x += 2;
-                index += 1;
-            }
-            'w' => {
-                x -= 2;
-                index += 1;
-            }
-            _ => {
-                panic!("Unrecognized input '{}'", &input[index]);
-            }
-        }
-    }
-
-    // println!("parse_one_line returning position ({}, {})", x, y);
-    Position { x, y }
+///
+/// # Panics
+///
+/// Panics if the line contains anything other than the six directions.
+fn parse_one_line(line: &str) -> Hex {
+    let path = Orientation::PointyTop
+        .parse_path(line)
+        .unwrap_or_else(|e| panic!("{}", e));
+
+    Hex::ORIGIN.travel(&path)
 }
 
 /// Flips the tile at position `pos` within `grid`. If the tile is already present in `grid`,
 /// this flip will return it to its starting orientation, and it is therefore removed from `grid`.
-fn flip_tile(grid: &mut FlippedTileGrid, pos: &Position) {
+fn flip_tile(grid: &mut FlippedTileGrid, pos: &Hex) {
     match grid.get(pos) {
         Some(_) => {
             grid.remove(pos);
@@ -112,88 +60,40 @@ fn flip_tile(grid: &mut FlippedTileGrid, pos: &Position) {
 }
 
 /// Return how many of the tiles in the six adjacent to the tile at `p` are flipped.
-fn count_adjacent_flipped(grid: &FlippedTileGrid, p: &Position) -> u8 {
-    let mut count = 0;
-
-    if grid.contains(&Position { x: p.x - 2, y: p.y }) {
-        count += 1;
-    }
-    if grid.contains(&Position { x: p.x + 2, y: p.y }) {
-        count += 1;
-    }
-    if grid.contains(&Position {
-        x: p.x - 1,
-        y: p.y - 1,
-    }) {
-        count += 1;
-    }
-    if grid.contains(&Position {
-        x: p.x - 1,
-        y: p.y + 1,
-    }) {
-        count += 1;
-    }
-    if grid.contains(&Position {
-        x: p.x + 1,
-        y: p.y - 1,
-    }) {
-        count += 1;
-    }
-    if grid.contains(&Position {
-        x: p.x + 1,
-        y: p.y + 1,
-    }) {
-        count += 1;
-    }
-
-    count
+fn count_adjacent_flipped(grid: &FlippedTileGrid, p: &Hex) -> usize {
+    p.neighbors().filter(|n| grid.contains(n)).count()
 }
 
 /// Examine every tile to see if it should be flipped according to the following challenge rules:
 ///     - a flipped tile with zero, or more than 2, flipped tiles immediately adjacent to it is
 ///       unflipped.
 ///     - Any unflipped tile with exactly 2 flipped tiles immediately adjacent to it is flipped.
+//
+// Only flipped tiles and their neighbours can change, so no other tiles are examined.
 fn perform_day_flip(grid: &mut FlippedTileGrid) {
-    let flipped_list_x = grid.iter().map(|Position { x, y: _ }| x);
-    let flipped_min_x = flipped_list_x.clone().min().unwrap();
-    let flipped_max_x = flipped_list_x.max().unwrap();
-
-    let flipped_list_y = grid.iter().map(|Position { x: _, y }| y);
-    let flipped_min_y = flipped_list_y.clone().min().unwrap();
-    let flipped_max_y = flipped_list_y.max().unwrap();
-
-    // println!("x ranges from {} to {} and y ranges from {} to {}", flipped_min_x, flipped_max_x,
-    //     flipped_min_y, flipped_max_y
-    // );
+    let candidates: HashSet<Hex> = grid
+        .iter()
+        .flat_map(|p| p.neighbors().chain([*p]))
+        .collect();
 
     let mut flip = Vec::new();
     let mut unflip = Vec::new();
-    for y in flipped_min_y - 2..=flipped_max_y + 2 {
-        for x in flipped_min_x - 2..=flipped_max_x + 2 {
-            // Coordinates are only valid if both `x` and `y` are odd, or both are even.
-            if (x + y) % 2 != 0 {
-                continue;
-            }
-
-            let p = Position { x, y };
-            let adjacent_flipped = count_adjacent_flipped(grid, &p);
+    for p in candidates {
+        let adjacent_flipped = count_adjacent_flipped(grid, &p);
 
-            if grid.contains(&p) {
-                if (adjacent_flipped == 0) || (adjacent_flipped > 2) {
-                    unflip.push(p);
-                }
-            } else if adjacent_flipped == 2 {
-                flip.push(p);
+        if grid.contains(&p) {
+            if (adjacent_flipped == 0) || (adjacent_flipped > 2) {
+                unflip.push(p);
             }
+        } else if adjacent_flipped == 2 {
+            flip.push(p);
         }
     }
 
-    // println!("Unflipped (white) tiles to flip to black: {:?}", &flip);
     for f in flip {
         grid.insert(f);
     }
 
-    // println!("Flipped (black) tiles to unflip to white: {:?}", &unflip);
     for uf in unflip {
         grid.remove(&uf);
     }
@@ -205,13 +105,22 @@ fn perform_multiple_day_flips(grid: &mut FlippedTileGrid, days: usize) {
     }
 }
 
+/// Prints the number of black tiles after 100 days. "--render" also draws the final floor as
+/// pointy-topped hexagons, with '#' for each flipped tile.
 fn main() {
+    let command_line =
+        CommandLine::from_env(&["--render"], &[]).unwrap_or_else(|e| panic!("{}", e));
+
     let input_file = fs::read_to_string(INPUT_FILENAME).expect("Error reading input file");
 
     let mut grid = parse_input(&input_file);
 
     perform_multiple_day_flips(&mut grid, 100);
 
+    if command_line.flag("--render") {
+        print!("{}", hex_grid::render(&grid, Orientation::PointyTop));
+    }
+
     println!("Challenge answer is {}", grid.len());
 }
 
@@ -244,89 +153,77 @@ wseweeenwnesenwwwswnew";
 
     #[test]
     fn test_parse_one_line() {
-        assert_eq!(Position { x: 1, y: -1 }, parse_one_line("esew"));
-        assert_eq!(Position { x: 0, y: 0 }, parse_one_line("nwwswee"));
+        assert_eq!(Hex::new(0, 1), parse_one_line("esew"));
+        assert_eq!(Hex::new(0, 0), parse_one_line("nwwswee"));
 
         assert_eq!(
-            Position { x: -4, y: -2 },
+            Hex::new(-3, 2),
             parse_one_line("sesenwnenenewseeswwswswwnenewsewsw")
         );
         assert_eq!(
-            Position { x: -1, y: 3 },
+            Hex::new(1, -3),
             parse_one_line("neeenesenwnwwswnenewnwwsewnenwseswesw")
         );
+        assert_eq!(Hex::new(-3, 3), parse_one_line("seswneswswsenwwnwse"));
         assert_eq!(
-            Position { x: -3, y: -3 },
-            parse_one_line("seswneswswsenwwnwse")
-        );
-        assert_eq!(
-            Position { x: 2, y: 2 },
+            Hex::new(2, -2),
             parse_one_line("nwnwneseeswswnenewneswwnewseswneseene")
         );
         assert_eq!(
-            Position { x: 0, y: 2 },
+            Hex::new(1, -2),
             parse_one_line("swweswneswnenwsewnwneneseenw")
         );
         assert_eq!(
-            Position { x: -2, y: 0 },
+            Hex::new(-1, 0),
             parse_one_line("eesenwseswswnenwswnwnwsewwnwsene")
         );
         assert_eq!(
-            Position { x: -1, y: 3 },
+            Hex::new(1, -3),
             parse_one_line("sewnenenenesenwsewnenwwwse")
         );
+        assert_eq!(Hex::new(-2, 0), parse_one_line("wenwwweseeeweswwwnwwe"));
         assert_eq!(
-            Position { x: -4, y: 0 },
-            parse_one_line("wenwwweseeeweswwwnwwe")
-        );
-        assert_eq!(
-            Position { x: -1, y: 1 },
+            Hex::new(0, -1),
             parse_one_line("wsweesenenewnwwnwsenewsenwwsesesenwne")
         );
+        assert_eq!(Hex::new(-2, 1), parse_one_line("neeswseenwwswnwswswnw"));
         assert_eq!(
-            Position { x: -3, y: -1 },
-            parse_one_line("neeswseenwwswnwswswnw")
-        );
-        assert_eq!(
-            Position { x: -2, y: 2 },
+            Hex::new(0, -2),
             parse_one_line("nenwswwsewswnenenewsenwsenwnesesenew")
         );
         assert_eq!(
-            Position { x: -2, y: 2 },
+            Hex::new(0, -2),
             parse_one_line("enewnwewneswsewnwswenweswnenwsenwsw")
         );
         assert_eq!(
-            Position { x: 3, y: 3 },
+            Hex::new(3, -3),
             parse_one_line("sweneswneswneneenwnewenewwneswswnese")
         );
         assert_eq!(
-            Position { x: -2, y: 0 },
+            Hex::new(-1, 0),
             parse_one_line("swwesenesewenwneswnwwneseswwne")
         );
         assert_eq!(
-            Position { x: 2, y: -2 },
+            Hex::new(0, 2),
             parse_one_line("enesenwswwswneneswsenwnewswseenwsese")
         );
         assert_eq!(
-            Position { x: 0, y: 0 },
+            Hex::new(0, 0),
             parse_one_line("wnwnesenesenenwwnenwsewesewsesesew")
         );
         assert_eq!(
-            Position { x: 0, y: 2 },
+            Hex::new(1, -2),
             parse_one_line("nenewswnwewswnenesenwnesewesw")
         );
         assert_eq!(
-            Position { x: 2, y: 2 },
+            Hex::new(2, -2),
             parse_one_line("eneswnwswnwsenenwnwnwwseeswneewsenese")
         );
         assert_eq!(
-            Position { x: 4, y: 0 },
+            Hex::new(2, 0),
             parse_one_line("neswnwewnwnwseenwseesewsenwsweewe")
         );
-        assert_eq!(
-            Position { x: -3, y: 1 },
-            parse_one_line("wseweeenwnesenwwwswnew")
-        );
+        assert_eq!(Hex::new(-1, -1), parse_one_line("wseweeenwnesenwwwswnew"));
     }
 
     #[test]
@@ -336,6 +233,26 @@ wseweeenwnesenwwwswnew";
         assert_eq!(10, grid.len());
     }
 
+    #[test]
+    fn test_render_floor() {
+        let grid = parse_input(TEST_INPUT);
+
+        assert_eq!(
+            hex_grid::render(&grid, Orientation::PointyTop),
+            [
+                " . . . #",
+                ". . . . .",
+                " # # . .",
+                "# . # . #",
+                " # . . .",
+                "# . . # .",
+                " # . . .",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
     #[test]
     fn test_day_flip() {
         let mut grid = parse_input(TEST_INPUT);
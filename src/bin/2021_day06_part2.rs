@@ -7,64 +7,101 @@
 //! days. Part 2 of the challenge increases the number of days to run the simulation, requiring
 //! substantial changes to the Part 1 code.
 
+use advent_of_code_rust::big_uint::BigUint;
+use advent_of_code_rust::count_matrix::CountMode;
+use advent_of_code_rust::lanternfish::{Population, Timer};
+use advent_of_code_rust::CommandLine;
+use std::env;
 use std::fs;
 
 const INPUT_FILENAME: &str = "2021_day06_input.txt";
-const CHALLENGE_DAYS: u32 = 256;
-const STARTING_DAYS_TO_SPAWN: DaysToSpawn = 8; // For fish just born
-const RESET_DAYS_TO_SPAWN: DaysToSpawn = 6; // For fish that have just spawned
-
-type DaysToSpawn = u8;
-type Fish = [u64; STARTING_DAYS_TO_SPAWN as usize + 1];
-
-/// Parses an input string consisting of comma-separated numbers representing the time until fish
-/// spawn again. The return value is an array where the array index is the *number* of fish that
-/// have that number of days until they next spawn. For example, the index 0 contains the number
-/// of fish that have 0 days until they next spawn.
-fn parse_input(input: &str) -> Fish {
-    let mut fish = [0; STARTING_DAYS_TO_SPAWN as usize + 1];
-
-    let individual_fish = input.lines().collect::<Vec<&str>>()[0]
-        .split(",")
-        .map(|i| i.parse::<DaysToSpawn>().unwrap());
-
-    for i in individual_fish {
-        fish[i as usize] += 1;
-    }
-    fish
+const CHALLENGE_DAYS: u64 = 256;
+const STARTING_DAYS_TO_SPAWN: Timer = 8; // For fish just born
+const RESET_DAYS_TO_SPAWN: Timer = 6; // For fish that have just spawned
+
+/// The settings that can be changed from the command line.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Options {
+    days: u64,
+    mode: CountMode,
+    reset_timer: Timer,
+    new_timer: Timer,
+    csv: bool,
 }
 
-/// Decrement the days to spawn value for every fish. If a fish is already at 0 days, restart their
-/// cycle at 6 days and add a new fish with a cycle of 8 days.
-fn decrement_fish(fish: &mut Fish) {
-    let new_spawn = fish[0];
-
-    for num_fish in 0..STARTING_DAYS_TO_SPAWN as usize {
-        fish[num_fish] = fish[num_fish + 1];
-    }
-
-    fish[RESET_DAYS_TO_SPAWN as usize] += new_spawn;
-    fish[STARTING_DAYS_TO_SPAWN as usize] = new_spawn;
+/// Parses the command line arguments `args`, as described for `main`.
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let command_line = CommandLine::from_args(
+        args,
+        &["--csv"],
+        &["--days", "--reset-timer", "--new-timer", "--modulo"],
+    )?;
+
+    let mode = match command_line.parse("--modulo", "modulus")? {
+        Some(0) => return Err("The modulus must be non-zero".to_string()),
+        Some(modulus) => CountMode::Modulo(modulus),
+        None => CountMode::Exact,
+    };
+
+    Ok(Options {
+        days: command_line
+            .parse("--days", "number of days")?
+            .unwrap_or(CHALLENGE_DAYS),
+        mode,
+        reset_timer: command_line
+            .parse("--reset-timer", "reset timer")?
+            .unwrap_or(RESET_DAYS_TO_SPAWN),
+        new_timer: command_line
+            .parse("--new-timer", "new fish timer")?
+            .unwrap_or(STARTING_DAYS_TO_SPAWN),
+        csv: command_line.flag("--csv"),
+    })
 }
 
-/// Run the simulation for the given number of days and return the number of fish that exist at the
-/// end of the process.
-fn run_simulation(fish: &mut Fish, days: usize) -> u64 {
-    for _ in 0..days {
-        decrement_fish(fish);
-    }
+/// Returns the population of fish in `input`, with the timers given in `options`.
+///
+/// # Panics
+///
+/// Panics if the input is malformed.
+fn load_population(input: &str, options: &Options) -> Population {
+    Population::parse(input, options.reset_timer, options.new_timer)
+        .unwrap_or_else(|e| panic!("{}", e))
+}
 
-    fish.iter().sum::<u64>()
+/// Returns the total number of fish in `population` after the number of days in `options`.
+fn run_simulation(population: &Population, options: &Options) -> BigUint {
+    options
+        .mode
+        .reduce(population.after_days(options.days, options.mode).total())
 }
 
+/// The command line arguments "--days <days>", "--reset-timer <timer>" and "--new-timer <timer>"
+/// change the number of days and the timers fish spawn with. "--modulo <modulus>" reports the
+/// number of fish modulo `modulus`, which is needed for very large numbers of days. Passing
+/// "--csv" also prints the number of fish with each timer on every day, as CSV.
 fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = parse_args(&args).unwrap_or_else(|e| panic!("{}", e));
+
     let input_file = fs::read_to_string(INPUT_FILENAME).expect("Error reading input file");
-    let mut fish = parse_input(&input_file);
-    let result = run_simulation(&mut fish, CHALLENGE_DAYS as usize);
-    println!(
-        "The total number of fish after {} days is {}",
-        CHALLENGE_DAYS, result
-    );
+    let population = load_population(&input_file, &options);
+
+    if options.csv {
+        print!("{}", population.to_csv(options.days, options.mode));
+    }
+
+    let result = run_simulation(&population, &options);
+
+    match options.mode {
+        CountMode::Exact => println!(
+            "The total number of fish after {} days is {}",
+            options.days, result
+        ),
+        CountMode::Modulo(modulus) => println!(
+            "The total number of fish after {} days is {} (modulo {})",
+            options.days, result, modulus
+        ),
+    }
 }
 
 // Test using data from the examples on the challenge page.
@@ -74,42 +111,50 @@ mod tests {
 
     const TEST_INPUT: &str = "3,4,3,1,2";
 
-    #[test]
-    fn parse_test_input() {
-        let fish = parse_input(TEST_INPUT);
-
-        assert_eq!(fish, [0, 1, 1, 2, 1, 0, 0, 0, 0]);
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
     }
 
     #[test]
-    fn test_two_decrements() {
-        let mut fish = parse_input(TEST_INPUT);
-
-        decrement_fish(&mut fish);
-        assert_eq!(fish, [1, 1, 2, 1, 0, 0, 0, 0, 0]);
+    fn challenge_answer() {
+        let options = parse_args(&[]).unwrap();
 
-        decrement_fish(&mut fish);
-        assert_eq!(fish, [1, 2, 1, 0, 0, 0, 1, 0, 1]);
+        assert_eq!(
+            run_simulation(&load_population(TEST_INPUT, &options), &options),
+            BigUint::from(26984457539)
+        );
     }
 
     #[test]
-    fn test_18_decrements() {
-        let mut fish = parse_input(TEST_INPUT);
-
-        for _ in 0..18 {
-            decrement_fish(&mut fish);
-        }
+    fn test_parse_args() {
+        let options = parse_args(&args(
+            "--days 80 --modulo 1000 --reset-timer 5 --new-timer 7 --csv",
+        ))
+        .unwrap();
+        assert_eq!(
+            options,
+            Options {
+                days: 80,
+                mode: CountMode::Modulo(1000),
+                reset_timer: 5,
+                new_timer: 7,
+                csv: true,
+            }
+        );
 
-        assert_eq!(fish, [3, 5, 3, 2, 2, 1, 5, 1, 4]);
+        assert!(parse_args(&args("--days")).is_err());
+        assert!(parse_args(&args("--days many")).is_err());
+        assert!(parse_args(&args("--modulo 0")).is_err());
+        assert!(parse_args(&args("--weeks 2")).is_err());
     }
 
     #[test]
-    fn challenge_answer() {
-        let mut fish = parse_input(TEST_INPUT);
+    fn test_modulo() {
+        let options = parse_args(&args("--days 80 --modulo 1000")).unwrap();
 
         assert_eq!(
-            run_simulation(&mut fish, CHALLENGE_DAYS as usize),
-            26984457539
+            run_simulation(&load_population(TEST_INPUT, &options), &options),
+            BigUint::from(934)
         );
     }
 }
@@ -8,215 +8,201 @@
 //! possible permutation of dice throws.
 //
 // The challenge is worded to encourage a strategy of branching at each roll of the die, but it
-// is more efficient to branch for the total of 3 dice rolls, and then factor in the frequency of
-// permutations for that total. For example, the total 4 can be achieved by rolling 1, 1 then 2;
-// or 1, 2 then 1; or 2, 1 then 1, so the frequency is 3. This code considers dice roll totals and
-// uses the normal distribution of these totals to avoid unnecessarily repeating work.
+// is more efficient to branch for the total of all the rolls in a turn, and then factor in the
+// number of ways that total can be rolled. For example, with three rolls of a 3-sided die the
+// total 4 can be achieved by rolling 1, 1 then 2; or 1, 2 then 1; or 2, 1 then 1, so there are 3
+// ways. This distribution is derived from the rules rather than hard-coded, so variants of the
+// game with other dice, boards, win scores and numbers of players can be explored.
 //
+// The number of wins from a given state does not depend on how the state was reached, so the
+// outcome of each state is memoised. States are held from the point of view of the player about
+// to move, so states that differ only in whose turn it is are shared.
 
+use advent_of_code_rust::big_uint::BigUint;
 use std::collections::HashMap;
 use std::fs;
-use std::iter::FromIterator;
 
-type Position = u8;
+type Position = u32;
+type Score = u32;
 
 const INPUT_FILENAME: &str = "2021_day21_input.txt";
-const WIN_SCORE: u8 = 21;
-const BOARD_SIZE: u8 = 10;
-
-// The normal distribution of outcomes of rolling a 3-sided die 3 times. The index is the sum of
-// the three rolls and the value is the number of ways that sum can be achieved.
-const DIE_NORMAL_DIST: [u8; 10] = [0, 0, 0, 1, 3, 6, 7, 6, 3, 1];
-
-/// Contains state for both players, recording their position and total score.
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-struct PlayersState {
-    p1_position: Position,
-    p1_score: u8,
-    p2_position: Position,
-    p2_score: u8,
+const DIE_FACES: u32 = 3;
+const ROLLS_PER_TURN: u32 = 3;
+const BOARD_SIZE: Position = 10;
+const WIN_SCORE: Score = 21;
+
+/// The position and score of each player, starting with the player about to move.
+type GameState = Vec<(Position, Score)>;
+
+/// The rules of a game of Dirac Dice. Each turn, a player rolls a die with faces numbered from 1
+/// to `die_faces` a total of `rolls_per_turn` times, and moves forward the total rolled around a
+/// circular board with spaces numbered from 1 to `board_size`. The player's score is increased by
+/// the number of the space they land on, and the first player to reach `win_score` wins.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct GameRules {
+    board_size: Position,
+    win_score: Score,
+    /// Each possible total of the rolls in one turn, and the number of ways it can be rolled.
+    roll_distribution: Vec<(Position, u64)>,
 }
 
-impl PlayersState {
-    fn new(p1_start_position: Position, p2_start_position: Position) -> Self {
-        Self {
-            p1_position: p1_start_position,
-            p1_score: 0,
-            p2_position: p2_start_position,
-            p2_score: 0,
+impl GameRules {
+    /// Returns the rules for the dice, board and win score passed, or an error if any of them is
+    /// zero or there are too many ways to roll the dice to count.
+    fn new(
+        die_faces: u32,
+        rolls_per_turn: u32,
+        board_size: Position,
+        win_score: Score,
+    ) -> Result<Self, String> {
+        if die_faces == 0 || rolls_per_turn == 0 || board_size == 0 || win_score == 0 {
+            return Err(
+                "Die faces, rolls per turn, board size and win score must be non-zero".into(),
+            );
         }
-    }
-}
 
-#[derive(Clone, Debug, Eq, PartialEq)]
-struct GameState {
-    turn: u16,
-    most_recent_player: u8,
-    p1_wins: u64,
-    p2_wins: u64,
-    perms: HashMap<PlayersState, u64>,
-}
+        // `ways[total]` is the number of ways of rolling `total` with the rolls so far.
+        let mut ways: Vec<u64> = vec![1];
+        for _ in 0..rolls_per_turn {
+            let mut next: Vec<u64> = vec![0; ways.len() + die_faces as usize];
+            for (total, &count) in ways.iter().enumerate() {
+                for face in 1..=die_faces as usize {
+                    next[total + face] = next[total + face]
+                        .checked_add(count)
+                        .ok_or("There are too many ways to roll the dice to count")?;
+                }
+            }
+            ways = next;
+        }
+
+        Ok(Self {
+            board_size,
+            win_score,
+            roll_distribution: ways
+                .iter()
+                .enumerate()
+                .filter(|(_, &count)| count != 0)
+                .map(|(total, &count)| (total as Position, count))
+                .collect(),
+        })
+    }
 
-impl GameState {
-    fn new(p1_start_position: Position, p2_start_position: Position) -> Self {
-        Self {
-            turn: 0,
-            most_recent_player: 2,
-            p1_wins: 0,
-            p2_wins: 0,
-            perms: HashMap::from_iter([(
-                PlayersState::new(p1_start_position, p2_start_position),
-                1,
-            )]),
+    /// Plays every possible game from the `starting_positions` passed, one per player, and returns
+    /// the number of games each player wins.
+    fn count_wins(&self, starting_positions: &[Position]) -> Result<Vec<BigUint>, String> {
+        if starting_positions.is_empty() {
+            return Err("There must be at least one player".to_string());
+        }
+        if let Some(p) = starting_positions
+            .iter()
+            .find(|&&p| p == 0 || p > self.board_size)
+        {
+            return Err(format!(
+                "Starting position {} is not on a board of size {}",
+                p, self.board_size
+            ));
         }
+
+        let state: GameState = starting_positions.iter().map(|&p| (p, 0)).collect();
+        Ok(self.wins_from(&state, &mut HashMap::new()))
     }
 
-    /// Returns a new `GameState` object containing the outcomes of all possible moves starting
-    /// from the game state in `self`.
-    fn make_move(&self) -> Self {
-        let player = (self.most_recent_player % 2) + 1;
-        let turn = if player == 1 {
-            self.turn + 1
-        } else {
-            self.turn
-        };
-
-        // println!("\nTurn {} Player {}", turn, player);
-
-        let mut new_perms = HashMap::new();
-        let mut new_p1_wins = self.p1_wins;
-        let mut new_p2_wins = self.p2_wins;
-
-        for (players_state, occurrences) in &self.perms {
-            for dice in 3..=9 {
-                let new_score;
-                let mut new_position;
-                let new_occurrences: u64 = occurrences * DIE_NORMAL_DIST[dice as usize] as u64;
-
-                match player {
-                    1 => {
-                        new_position = players_state.p1_position + dice;
-
-                        if new_position > BOARD_SIZE {
-                            new_position %= BOARD_SIZE;
-                        }
-
-                        new_score = players_state.p1_score + new_position;
-
-                        if new_score < WIN_SCORE {
-                            let ps = PlayersState {
-                                p1_position: new_position,
-                                p1_score: new_score,
-                                p2_position: players_state.p2_position,
-                                p2_score: players_state.p2_score,
-                            };
-                            match new_perms.get_mut(&ps) {
-                                Some(state) => {
-                                    *state += new_occurrences;
-                                }
-                                None => {
-                                    new_perms.insert(ps, new_occurrences);
-                                }
-                            }
-                        } else {
-                            new_p1_wins += new_occurrences;
-                        }
-                    }
-                    2 => {
-                        new_position = players_state.p2_position + dice;
-
-                        if new_position > BOARD_SIZE {
-                            new_position %= BOARD_SIZE;
-                        }
-
-                        new_score = players_state.p2_score + new_position;
-
-                        if new_score < WIN_SCORE {
-                            let ps = PlayersState {
-                                p1_position: players_state.p1_position,
-                                p1_score: players_state.p1_score,
-                                p2_position: new_position,
-                                p2_score: new_score,
-                            };
-                            match new_perms.get_mut(&ps) {
-                                Some(state) => {
-                                    *state += new_occurrences;
-                                }
-                                None => {
-                                    new_perms.insert(ps, new_occurrences);
-                                }
-                            }
-                        } else {
-                            new_p2_wins += new_occurrences;
-                        }
-                    }
-                    _ => {
-                        panic!("Internal error - player id was neither 1 or 2.");
-                    }
-                }
-            }
+    /// Returns the number of games each player wins from `state`, where the player about to move
+    /// is first in both `state` and the returned counts.
+    fn wins_from(
+        &self,
+        state: &GameState,
+        memo: &mut HashMap<GameState, Vec<BigUint>>,
+    ) -> Vec<BigUint> {
+        if let Some(wins) = memo.get(state) {
+            return wins.clone();
         }
 
-        Self {
-            turn,
-            most_recent_player: player,
-            p1_wins: new_p1_wins,
-            p2_wins: new_p2_wins,
-            perms: new_perms,
+        let players = state.len();
+        let mut wins = vec![BigUint::zero(); players];
+        let (position, score) = state[0];
+
+        for &(total, ways) in &self.roll_distribution {
+            let ways = BigUint::from(ways);
+            let new_position = (position - 1 + total) % self.board_size + 1;
+            let new_score = score + new_position;
+
+            if new_score >= self.win_score {
+                wins[0] = &wins[0] + &ways;
+                continue;
+            }
+
+            // The next player moves first in the new state, and this player moves last.
+            let mut next_state = state[1..].to_vec();
+            next_state.push((new_position, new_score));
+            let next_wins = self.wins_from(&next_state, memo);
+
+            for (player, count) in next_wins.iter().enumerate() {
+                let player = (player + 1) % players;
+                wins[player] = &wins[player] + &(&ways * count);
+            }
         }
+
+        memo.insert(state.clone(), wins.clone());
+        wins
     }
 }
 
-/// Reads the start positions of both players from the string passed and returns as a tuple.
+/// Reads the start position of each player from the string passed, where the players must be
+/// numbered in order from 1.
 ///
 /// # Panics
 ///
 /// Panics if the input is malformed.
-fn parse_input(input: &str) -> (Position, Position) {
-    let mut lines = input.lines();
-
-    (
-        lines
-            .next()
-            .unwrap()
-            .strip_prefix("Player 1 starting position: ")
-            .unwrap()
-            .parse()
-            .unwrap(),
-        lines
-            .next()
-            .unwrap()
-            .strip_prefix("Player 2 starting position: ")
-            .unwrap()
-            .parse()
-            .unwrap(),
-    )
+fn parse_input(input: &str) -> Vec<Position> {
+    input
+        .lines()
+        .filter(|line| !line.is_empty())
+        .enumerate()
+        .map(|(i, line)| {
+            line.strip_prefix(&format!("Player {} starting position: ", i + 1))
+                .unwrap_or_else(|| panic!("Expected the starting position of player {}", i + 1))
+                .parse()
+                .unwrap_or_else(|e| panic!("Invalid starting position '{}': {}", line, e))
+        })
+        .collect()
 }
 
 /// Play a game beginning at the starting positions provided until all possible permutation of
-/// dice rolls have been considered. Return the number of wins for each player as a tuple.
-fn play_game(p1_start: u8, p2_start: u8) -> (u64, u64) {
-    let mut game = GameState::new(p1_start, p2_start);
+/// dice rolls have been considered. Return the number of wins for each player.
+///
+/// # Panics
+///
+/// Panics if any starting position is not on the board.
+fn play_game(starting_positions: &[Position]) -> Vec<BigUint> {
+    GameRules::new(DIE_FACES, ROLLS_PER_TURN, BOARD_SIZE, WIN_SCORE)
+        .and_then(|rules| rules.count_wins(starting_positions))
+        .unwrap_or_else(|e| panic!("{}", e))
+}
 
-    while !game.perms.is_empty() {
-        game = game.make_move();
+/// Returns a description of the wins such as "Player 1 wins 5 times, Player 2 wins 3 times and
+/// Player 3 wins 2 times".
+fn describe_wins(wins: &[BigUint]) -> String {
+    let descriptions: Vec<String> = wins
+        .iter()
+        .enumerate()
+        .map(|(i, count)| format!("Player {} wins {} times", i + 1, count))
+        .collect();
+
+    match descriptions.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        _ => descriptions.concat(),
     }
-
-    (game.p1_wins, game.p2_wins)
 }
 
 fn main() {
     let input_file = fs::read_to_string(INPUT_FILENAME).expect("Error reading input file");
 
-    let (p1_start, p2_start) = parse_input(&input_file);
-    let wins = play_game(p1_start, p2_start);
-    println!(
-        "Player 1 wins {} times and Player 2 wins {} times",
-        wins.0, wins.1
-    );
+    let wins = play_game(&parse_input(&input_file));
+    println!("{}", describe_wins(&wins));
     println!(
         "The challenge answer is the larger of these numbers, which is: {}",
-        u64::max(wins.0, wins.1)
+        wins.iter().max().unwrap()
     );
 }
 
@@ -229,24 +215,154 @@ mod tests {
 Player 1 starting position: 4
 Player 2 starting position: 8";
 
+    /// The parameters of `GameRules::new`, used to count the wins by branching on every
+    /// individual roll of the die, without memoising, to check `GameRules::count_wins` against.
+    struct BruteForce {
+        die_faces: u32,
+        rolls_per_turn: u32,
+        board_size: Position,
+        win_score: Score,
+    }
+
+    impl BruteForce {
+        fn count_wins(&self, starting_positions: &[Position]) -> Vec<u64> {
+            let mut wins = vec![0; starting_positions.len()];
+            let mut state = starting_positions.iter().map(|&p| (p, 0)).collect();
+            self.roll(&mut state, 0, 0, 0, &mut wins);
+            wins
+        }
+
+        fn roll(
+            &self,
+            state: &mut GameState,
+            player: usize,
+            roll: u32,
+            moved: Position,
+            wins: &mut [u64],
+        ) {
+            if roll < self.rolls_per_turn {
+                for face in 1..=self.die_faces {
+                    self.roll(state, player, roll + 1, moved + face, wins);
+                }
+                return;
+            }
+
+            let (position, score) = state[player];
+            let new_position = (position - 1 + moved) % self.board_size + 1;
+            if score + new_position >= self.win_score {
+                wins[player] += 1;
+                return;
+            }
+
+            state[player] = (new_position, score + new_position);
+            self.roll(state, (player + 1) % state.len(), 0, 0, wins);
+            state[player] = (position, score);
+        }
+    }
+
+    fn to_strings(wins: &[BigUint]) -> Vec<String> {
+        wins.iter().map(BigUint::to_string).collect()
+    }
+
     #[test]
     fn parse_test_input() {
-        let (p1_start, p2_start) = parse_input(TEST_INPUT);
+        assert_eq!(parse_input(TEST_INPUT), vec![4, 8]);
+        assert_eq!(
+            parse_input("Player 1 starting position: 1\nPlayer 2 starting position: 2\nPlayer 3 starting position: 3\n"),
+            vec![1, 2, 3]
+        );
+    }
 
-        assert_eq!(p1_start, 4);
-        assert_eq!(p2_start, 8);
+    #[test]
+    #[should_panic]
+    fn parse_misnumbered_players() {
+        parse_input("Player 1 starting position: 1\nPlayer 3 starting position: 2");
+    }
+
+    #[test]
+    fn test_roll_distribution() {
+        assert_eq!(
+            GameRules::new(3, 3, 10, 21).unwrap().roll_distribution,
+            vec![(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)]
+        );
+        assert_eq!(
+            GameRules::new(4, 2, 10, 21).unwrap().roll_distribution,
+            vec![(2, 1), (3, 2), (4, 3), (5, 4), (6, 3), (7, 2), (8, 1)]
+        );
+        assert_eq!(
+            GameRules::new(6, 1, 10, 21).unwrap().roll_distribution,
+            (1..=6).map(|face| (face, 1)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert!(GameRules::new(0, 3, 10, 21).is_err());
+        assert!(GameRules::new(3, 0, 10, 21).is_err());
+        assert!(GameRules::new(3, 3, 0, 21).is_err());
+        assert!(GameRules::new(3, 3, 10, 0).is_err());
+        assert!(GameRules::new(1000, 1000, 10, 21).is_err());
+
+        let rules = GameRules::new(3, 3, 10, 21).unwrap();
+        assert!(rules.count_wins(&[]).is_err());
+        assert!(rules.count_wins(&[4, 0]).is_err());
+        assert!(rules.count_wins(&[4, 11]).is_err());
     }
 
     #[test]
     fn test_play_game() {
-        let (p1_start, p2_start) = parse_input(TEST_INPUT);
-        let wins = play_game(p1_start, p2_start);
-        println!(
-            "Player 1 wins {} times and Player 2 wins {} times",
-            wins.0, wins.1
+        let wins = play_game(&parse_input(TEST_INPUT));
+
+        assert_eq!(
+            to_strings(&wins),
+            vec!["444356092776315", "341960390180808"]
         );
+        assert_eq!(
+            describe_wins(&wins),
+            "Player 1 wins 444356092776315 times and Player 2 wins 341960390180808 times"
+        );
+    }
 
-        assert_eq!(wins.0, 444356092776315);
-        assert_eq!(wins.1, 341960390180808);
+    #[test]
+    fn test_variants_match_brute_force() {
+        for (die_faces, rolls_per_turn, board_size, win_score, starts) in [
+            (3, 3, 10, 8, vec![4, 8]),
+            (4, 1, 10, 12, vec![1, 5, 9]),
+            (2, 2, 7, 10, vec![3, 3, 6, 2]),
+            (1, 1, 10, 21, vec![10, 1]),
+            (6, 1, 5, 9, vec![2]),
+        ] {
+            let expected = BruteForce {
+                die_faces,
+                rolls_per_turn,
+                board_size,
+                win_score,
+            }
+            .count_wins(&starts);
+
+            let wins = GameRules::new(die_faces, rolls_per_turn, board_size, win_score)
+                .unwrap()
+                .count_wins(&starts)
+                .unwrap();
+            assert_eq!(
+                to_strings(&wins),
+                expected.iter().map(u64::to_string).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_variants_beyond_128_bits() {
+        let wins = GameRules::new(6, 3, 10, 10)
+            .unwrap()
+            .count_wins(&[4, 8, 1])
+            .unwrap();
+
+        assert_eq!(
+            describe_wins(&wins),
+            "Player 1 wins 53628747007856099962258054755032439352544 times, \
+             Player 2 wins 4986090344731999078808681765765531308018 times and \
+             Player 3 wins 16320810498734725867229778078835181579044 times"
+        );
     }
 }
@@ -7,288 +7,101 @@
 //! create an internal representation of the directories and files. The files have an associated
 //! size. Determines the size of each directory by summing all files in the directory and all
 //! sub-directories. The challenge answer is the size of the smallest directory that's deletion
-//! will bring the free space on the disk over a constant.
+//! will bring the free space on the disk over a constant. Other disk sizes and required free space
+//! can be given on the command line, and the filesystem can be explored with a mini shell.
 
-use std::fmt::{self, Display};
+use advent_of_code_rust::filesystem::{DirectoryToDelete, FileSize, FileSystem, Shell};
+use advent_of_code_rust::CommandLine;
 use std::fs;
-
-type FileSize = u64;
-type NodeId = usize;
+use std::io::{self, BufRead, Write};
 
 const INPUT_FILENAME: &str = "2022_day07_input.txt";
-const ROOT_NODE_ID: NodeId = 0;
 const CHALLENGE_TOTAL_SPACE: FileSize = 70_000_000;
 const CHALLENGE_REQUIRED_SPACE: FileSize = 30_000_000;
 
-#[derive(Clone, Debug, PartialEq)]
-enum Node {
-    Directory {
-        name: String,
-        parent: NodeId,
-        children: Vec<NodeId>,
-    },
-    File {
-        name: String,
-        parent: NodeId,
-        file_size: FileSize,
-    },
-}
-
-/// A `Tree` contains all the nodes in this directory hierarchy. It is created with a root
-/// directory named "/". It is special in that its parent is itself. All nodes are referenced by
-/// their index in the `t` vector, referred to as the `NodeId`. The root node has a NodeId of 0.
-//
-// Implementation note: although links between nodes could be implemented with borrows (e.g.,
-// &Node), this is complex in Rust and offers poor performance. The latter is because Nodes packed
-// into a vector will be close in memory, whereas nodes independently stored in heap memory may be
-// placed further apart.
-#[derive(Debug, PartialEq)]
-struct Tree {
-    t: Vec<Node>,
-}
-
-impl Tree {
-    /// Creates a new `Tree` that is prepopulated with an empty root directory.
-    fn new() -> Self {
-        Self {
-            t: vec![Node::Directory {
-                name: "/".to_string(),
-                parent: ROOT_NODE_ID,
-                children: Vec::new(),
-            }],
-        }
-    }
-
-    /// Creates a new directory node and adds it to the end of the list of nodes maintained in
-    /// `Tree`. `name` should not have leading or trailing whitespace.
-    fn add_directory_node(&mut self, name: &str, parent: NodeId) -> NodeId {
-        let new_node_id = self.t.len();
-        self.t.push(Node::Directory {
-            name: name.to_string(),
-            parent,
-            children: Vec::new(),
-        });
-
-        match &mut self.t[parent] {
-            Node::Directory {
-                name: _,
-                parent: _,
-                children,
-            } => {
-                children.push(new_node_id);
-            }
-            _ => {
-                panic!("Fatal error - the parent of a node was not a Directory object, which should never happen");
-            }
-        }
-
-        new_node_id
-    }
-
-    /// Creates a new file node and adds it to the end of the list of nodes maintained in `Tree`.
-    fn add_file_node(&mut self, name: &str, parent: NodeId, file_size: FileSize) -> NodeId {
-        let new_node_id = self.t.len();
-        self.t.push(Node::File {
-            name: name.to_string(),
-            parent,
-            file_size,
-        });
-
-        match &mut self.t[parent] {
-            Node::Directory {
-                name: _,
-                parent: _,
-                children,
-            } => {
-                children.push(new_node_id);
-            }
-            _ => {
-                panic!("Fatal error - the parent of a node was not a Directory object, which should never happen");
-            }
-        }
-
-        new_node_id
-    }
-}
-
-/// Displays this `Tree` in the same format used by the challenge.
-impl Display for Tree {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fn recurse(
-            tree: &Tree,
-            f: &mut fmt::Formatter<'_>,
-            current_node_idx: NodeId,
-            depth: usize,
-        ) -> fmt::Result {
-            match &tree.t[current_node_idx] {
-                Node::Directory {
-                    name,
-                    parent: _,
-                    children,
-                } => {
-                    writeln!(f, "{0:>1$} {name} (dir)", "-", 2 * depth + 1)?;
-
-                    for child in children.iter() {
-                        recurse(tree, f, *child, depth + 1)?
-                    }
-                    Ok(())
-                }
-                Node::File {
-                    name,
-                    parent: _,
-                    file_size,
-                } => {
-                    writeln!(
-                        f,
-                        "{0:>1$} {name} (file, size={file_size})",
-                        "-",
-                        2 * depth + 1
-                    )
-                }
-            }
-        }
-
-        recurse(self, f, 0, 0)
-    }
-}
-
-/// Handle a 'cd' command. `dir_name` can be:
-///     "/" to return the `NodeId` of the root directory
-///     ".." to return the `NodeId` of the `current_dir_id`'s parent
-///     a sub-directory name to return its `NodeId`
-///
-/// If a sub-directory is specified that does not exist it is created.
+/// Returns the size of the smallest directory that can be freed to bring the free space on a disk
+/// of `disk_size` up to at least `required_space`, or `None` if there is already enough free
+/// space and nothing needs deleting.
 ///
 /// # Panics
 ///
-/// Panics if `dir_name` is empty or if `current_dir_id` is not a `Directory` node.
-fn do_cd(tree: &mut Tree, current_dir_id: NodeId, dir_name: &str) -> NodeId {
-    assert!(
-        !dir_name.is_empty(),
-        "cd must be called with a directory name"
-    );
-
-    match dir_name {
-        "/" => ROOT_NODE_ID,
-        ".." => match tree.t[current_dir_id] {
-            Node::Directory { parent, .. } => parent,
-            _ => {
-                panic!("Internal error: do_cd was called with a non-directory node");
-            }
-        },
-        _ => match &tree.t[current_dir_id] {
-            Node::Directory { children, .. } => {
-                for &c in children {
-                    if let Node::Directory { name, .. } = &tree.t[c] {
-                        if name == dir_name {
-                            return c;
-                        }
-                    }
-                }
-                tree.add_directory_node(dir_name, current_dir_id)
-            }
-            _ => {
-                panic!("Internal error: do_cd was called with a non-directory node");
-            }
-        },
-    }
-}
-
-/// Calculates the size of each directory in `tree`. A directory's size is the total of all the
-/// files it contains directly and indirectly (i.e., via sub-directories). Returns a vector that
-/// uses the same indexes as the `NodeId`'s in `tree` and which contains the size of each
-/// directory in `tree`. For example, the size of the directory with NodeId 2 in `tree` can be
-/// found in index 2 of the result.
-fn determine_directory_sizes(tree: &Tree) -> Vec<Option<FileSize>> {
-    let node_count = tree.t.len();
-    let mut dir_sizes = vec![None; node_count];
-
-    fn recurse(tree: &Tree, dir_sizes: &mut Vec<Option<FileSize>>, current_dir_id: NodeId) {
-        match &tree.t[current_dir_id] {
-            Node::Directory { children, .. } => {
-                let mut dir_size = 0;
-
-                for c in children {
-                    match &tree.t[*c] {
-                        Node::Directory { .. } => {
-                            if dir_sizes[*c].is_none() {
-                                recurse(tree, dir_sizes, *c);
-                            }
-                            dir_size += dir_sizes[*c].unwrap();
-                        }
-                        Node::File { file_size, .. } => {
-                            dir_size += file_size;
-                        }
-                    }
-                }
-                dir_sizes[current_dir_id] = Some(dir_size);
-            }
-            _ => {
-                panic!("Internal error: determine_directory_sizes internal function was called with a non-directory node");
-            }
+/// Panics if no directory is large enough.
+fn challenge_answer(
+    filesystem: &FileSystem,
+    disk_size: FileSize,
+    required_space: FileSize,
+) -> Option<FileSize> {
+    match filesystem.smallest_directory_to_free(disk_size, required_space) {
+        DirectoryToDelete::NotNeeded => None,
+        DirectoryToDelete::Smallest(dir_id) => Some(filesystem.size(dir_id)),
+        DirectoryToDelete::NoneLargeEnough => {
+            panic!("No single directory can be deleted to free the required space")
         }
     }
-
-    recurse(tree, &mut dir_sizes, ROOT_NODE_ID);
-
-    dir_sizes
 }
 
-/// Returns the size of the smallest directory that can be freed to bring the disk's free space
-/// over the minimum required by the challenge (`CHALLENGE_REQUIRED_SPACE`).
-fn challenge_answer(tree: &Tree) -> FileSize {
-    let dir_sizes = determine_directory_sizes(tree);
-    let current_unused_space = CHALLENGE_TOTAL_SPACE - dir_sizes[0].unwrap();
-    let need_to_free_another = CHALLENGE_REQUIRED_SPACE - current_unused_space;
-
-    dir_sizes
-        .iter()
-        .map(|ds| ds.unwrap_or(0))
-        .filter(|ds| ds >= &need_to_free_another)
-        .min()
-        .unwrap()
-}
-
-/// Takes a string containing the entire input file and converts it into a tree which is then
-/// returned. Each line of input must be one of:
-///     $ cd <directory_name>
-///     $ ls
-///     dir <directory_name>
-///     <file_size> <file_name>
-///
-/// # Panics
-///
-/// Panics if the input is malformed.
-fn parse_input(input: &str) -> Tree {
-    let mut tree = Tree::new();
-    let mut cwd = ROOT_NODE_ID; // current working directory
+/// Reads commands from standard input and runs them in a `Shell` over `filesystem` until the
+/// input ends or "exit" is entered.
+fn run_shell(filesystem: &FileSystem) {
+    let mut shell = Shell::new(filesystem);
+    let stdin = io::stdin();
+
+    loop {
+        print!("{}$ ", filesystem.path(shell.cwd()));
+        io::stdout()
+            .flush()
+            .expect("Error writing to standard output");
+
+        let mut line = String::new();
+        if stdin
+            .lock()
+            .read_line(&mut line)
+            .expect("Error reading command")
+            == 0
+        {
+            println!();
+            break;
+        }
+        if line.trim() == "exit" {
+            break;
+        }
 
-    for line in input.lines() {
-        if !line.is_empty() {
-            if line.starts_with("$ cd ") {
-                let dir_name = line.strip_prefix("$ cd ").unwrap().trim();
-                cwd = do_cd(&mut tree, cwd, dir_name);
-            } else if line.starts_with("dir ") {
-                let dir_name = line.strip_prefix("dir ").unwrap().trim();
-                _ = do_cd(&mut tree, cwd, dir_name);
-            } else if line.starts_with("$ ls") {
-                // No action required.
-            } else {
-                let (file_size_str, file_name) = line.split_once(' ').unwrap();
-                let file_size = file_size_str.parse().unwrap();
-                _ = tree.add_file_node(file_name, cwd, file_size);
-            }
+        match shell.execute(&line) {
+            Ok(output) => print!("{}", output),
+            Err(e) => eprintln!("{}", e),
         }
     }
-
-    tree
 }
 
+/// Prints the size of the directory to delete to make room for the update. "--disk-size <size>"
+/// and "--required-space <size>" replace the disk size and free space given in the challenge, and
+/// "--shell" instead starts an interactive shell to explore the filesystem.
 fn main() {
+    let command_line = CommandLine::from_env(&["--shell"], &["--disk-size", "--required-space"])
+        .unwrap_or_else(|e| panic!("{}", e));
+
     let input = fs::read_to_string(INPUT_FILENAME).expect("Error reading input file");
-    let tree = parse_input(&input);
+    let filesystem = FileSystem::from_terminal_log(&input)
+        .unwrap_or_else(|e| panic!("Error in input file: {}", e));
 
-    println!("The challenge answer is {}", challenge_answer(&tree),);
+    if command_line.flag("--shell") {
+        run_shell(&filesystem);
+        return;
+    }
+
+    let disk_size = command_line
+        .parse("--disk-size", "disk size")
+        .unwrap_or_else(|e| panic!("{}", e))
+        .unwrap_or(CHALLENGE_TOTAL_SPACE);
+    let required_space = command_line
+        .parse("--required-space", "required space")
+        .unwrap_or_else(|e| panic!("{}", e))
+        .unwrap_or(CHALLENGE_REQUIRED_SPACE);
+
+    match challenge_answer(&filesystem, disk_size, required_space) {
+        Some(answer) => println!("The challenge answer is {}", answer),
+        None => println!("There is already enough free space, so nothing needs deleting"),
+    }
 }
 
 // Test data based on examples on the challenge page.
@@ -322,127 +135,22 @@ $ ls
 7214296 k
 ";
 
-    const EXPECTED_OUTPUT: &str = "\
-- / (dir)
-  - a (dir)
-    - e (dir)
-      - i (file, size=584)
-    - f (file, size=29116)
-    - g (file, size=2557)
-    - h.lst (file, size=62596)
-  - b.txt (file, size=14848514)
-  - c.dat (file, size=8504156)
-  - d (dir)
-    - j (file, size=4060174)
-    - d.log (file, size=8033020)
-    - d.ext (file, size=5626152)
-    - k (file, size=7214296)
-";
-
     #[test]
-    fn test_do_cd() {
-        let mut tree = Tree::new();
-        assert_eq!(do_cd(&mut tree, ROOT_NODE_ID, "subdir1"), 1);
-
-        assert_eq!(
-            tree.t.get(1),
-            Some(&Node::Directory {
-                name: "subdir1".to_string(),
-                parent: ROOT_NODE_ID,
-                children: Vec::new()
-            })
-        );
-
-        assert_eq!(do_cd(&mut tree, 1, "subdir2"), 2);
-        assert_eq!(
-            tree.t.get(2),
-            Some(&Node::Directory {
-                name: "subdir2".to_string(),
-                parent: 1,
-                children: Vec::new()
-            })
-        );
+    fn test_challenge_answer() {
+        let filesystem = FileSystem::from_terminal_log(TEST_INPUT).unwrap();
 
-        assert_eq!(do_cd(&mut tree, ROOT_NODE_ID, "subdir3"), 3);
         assert_eq!(
-            tree.t.get(3),
-            Some(&Node::Directory {
-                name: "subdir3".to_string(),
-                parent: ROOT_NODE_ID,
-                children: Vec::new()
-            })
+            challenge_answer(&filesystem, CHALLENGE_TOTAL_SPACE, CHALLENGE_REQUIRED_SPACE),
+            Some(24933642)
         );
-
-        if let Node::Directory { children, .. } = &tree.t[ROOT_NODE_ID] {
-            assert_eq!(children, &vec![1, 3]);
-        } else {
-            panic!("Error: the root directory must be a directory type, but isn't.");
-        }
-
-        if let Node::Directory { children, .. } = &tree.t[1] {
-            assert_eq!(children, &vec![2]);
-        } else {
-            panic!("Error: 'subdir2' directory must be a directory type, but isn't.");
-        }
-
-        assert_eq!(do_cd(&mut tree, ROOT_NODE_ID, "/"), ROOT_NODE_ID);
-        assert_eq!(do_cd(&mut tree, 1, "/"), ROOT_NODE_ID);
-        assert_eq!(do_cd(&mut tree, 2, "/"), ROOT_NODE_ID);
-        assert_eq!(do_cd(&mut tree, 3, "/"), ROOT_NODE_ID);
-
-        assert_eq!(do_cd(&mut tree, ROOT_NODE_ID, ".."), ROOT_NODE_ID);
-        assert_eq!(do_cd(&mut tree, 1, ".."), ROOT_NODE_ID);
-        assert_eq!(do_cd(&mut tree, 2, ".."), 1);
-        assert_eq!(do_cd(&mut tree, 3, ".."), ROOT_NODE_ID);
-
-        assert_eq!(do_cd(&mut tree, ROOT_NODE_ID, "subdir1"), 1);
-        assert_eq!(do_cd(&mut tree, 1, "subdir2"), 2);
-        assert_eq!(do_cd(&mut tree, ROOT_NODE_ID, "subdir3"), 3);
+        assert_eq!(challenge_answer(&filesystem, 48_400_000, 19_000), Some(584));
+        assert_eq!(challenge_answer(&filesystem, 100_000_000, 30_000_000), None);
     }
 
     #[test]
     #[should_panic]
-    fn test_do_cd_with_bad_dir_name() {
-        let mut tree = Tree::new();
-        do_cd(&mut tree, ROOT_NODE_ID, "");
-    }
-
-    #[test]
-    fn test_parse_input() {
-        let tree = parse_input(TEST_INPUT);
-
-        assert_eq!(tree.to_string(), EXPECTED_OUTPUT);
-    }
-
-    #[test]
-    fn test_determine_directory_sizes() {
-        let tree = parse_input(TEST_INPUT);
-
-        assert_eq!(
-            determine_directory_sizes(&tree),
-            vec![
-                Some(48381165), // Dir '/'
-                Some(94853),    // Dir 'a'
-                None,
-                None,
-                Some(24933642), // Dir 'd'
-                Some(584),      // Dir 'e'
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-            ]
-        );
-    }
-
-    #[test]
-    fn test_challenge_answer() {
-        let tree = parse_input(TEST_INPUT);
-
-        assert_eq!(challenge_answer(&tree), 24933642);
+    fn test_challenge_answer_none_large_enough() {
+        let filesystem = FileSystem::from_terminal_log(TEST_INPUT).unwrap();
+        challenge_answer(&filesystem, 1_000, 60_000_000);
     }
 }
@@ -7,87 +7,20 @@
 //! described in the challenge. Sum the indexes of correctly ordered pairs to generate the
 //! challenge answer.
 
+use advent_of_code_rust::nested_list::NestedList;
+use std::cmp::Ordering;
 use std::fs;
 
 const INPUT_FILENAME: &str = "2022_day13_input.txt";
 
 type Int = u8;
-type Pairs = Vec<(ListElement, ListElement)>;
+type Packet = NestedList<Int>;
+type Pairs = Vec<(Packet, Packet)>;
 
-/// A `ListElement` contains either an individual number or a `Vec` of zero or more `ListElement`s.
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
-enum ListElement {
-    Integer(Int),
-    List(Vec<ListElement>),
-}
-
-impl ListElement {
-    /// Convert the passed string into `ListElement`s.
-    fn parse_str(input: &str) -> Self {
-        let input_chars: Vec<char> = input.chars().collect();
-        assert_eq!(input_chars[0], '[');
-
-        let slice = &mut &input_chars[1..];
-
-        Self::parse_element_recurse(slice)
-    }
-
-    /// Internal function that parses a slice of `char`s representing the input string into a
-    /// `ListElement` representation. The slice passed is modified to keep track of the input that
-    /// has been processed so far.
-    ///
-    /// # Panics
-    ///
-    /// Panics if the input contains an invalid character or is malformed in certain ways. However,
-    /// many malformed inputs are accepted if the problems are not too bad, e.g., ",," is treated
-    /// as ",".
-    fn parse_element_recurse(ic: &mut &[char]) -> Self {
-        let mut elements = Vec::new();
-
-        loop {
-            match ic[0] {
-                ']' => {
-                    *ic = &ic[1..];
-                    break;
-                }
-                '[' => {
-                    *ic = &ic[1..];
-                    let sublist = ListElement::parse_element_recurse(ic);
-                    elements.push(sublist);
-                }
-                '0'..='9' => {
-                    let mut char_digits = Vec::new();
-
-                    while ic[0].is_ascii_digit() {
-                        char_digits.push(ic[0]);
-                        *ic = &ic[1..];
-                    }
-
-                    let int_tmp = char_digits.iter().collect::<String>().parse().unwrap();
-
-                    elements.push(ListElement::Integer(int_tmp));
-                }
-                ',' => {
-                    *ic = &ic[1..];
-                }
-                _ => {
-                    panic!("Unrecognized character '{}' in input", ic[0]);
-                }
-            }
-
-            if ic.is_empty() {
-                panic!("The input contains unbalanced start and end list tags");
-            }
-        }
-
-        ListElement::List(elements)
-    }
-}
-
-/// Parses the input as sets of 3 lines. The first and second each contain a `ListElement`, which
-/// the challenge refers to as "Left" and "Right". The third is a blank line.
+/// Parses the input as sets of 3 lines. The first and second each contain a `Packet`, which the
+/// challenge refers to as "Left" and "Right". The third is a blank line.
 ///
-/// Returns a `Pairs` object which is a `Vec` of pairs of `ListElement`s.
+/// Returns a `Pairs` object which is a `Vec` of pairs of `Packet`s.
 ///
 /// # Panics
 ///
@@ -100,10 +33,10 @@ fn parse_input(input: &str) -> Pairs {
     for (line_number, line) in input.lines().enumerate() {
         match line_number % 3 {
             0 => {
-                left = Some(ListElement::parse_str(line));
+                left = Some(line.parse().unwrap());
             }
             1 => {
-                pairs.push((left.unwrap(), ListElement::parse_str(line)));
+                pairs.push((left.unwrap(), line.parse().unwrap()));
                 left = None;
             }
             2 => {
@@ -119,57 +52,15 @@ fn parse_input(input: &str) -> Pairs {
     pairs
 }
 
-/// Compares the ordering of the 'left' and 'right' `ListElement`s passed, as per the challenge
-/// rules. Returns 'Some(true)' if ordering is correct, 'Some(false)' if incorrect, and `None` if
-/// the two parameters passed are identical.
-fn is_order_correct(left: &ListElement, right: &ListElement) -> Option<bool> {
-    if let ListElement::Integer(left_integer) = left {
-        if let ListElement::Integer(right_integer) = right {
-            if left_integer != right_integer {
-                return Some(left_integer < right_integer);
-            } else {
-                return None;
-            }
-        }
-    }
-
-    // At least one of 'left' or 'right' is an `ElementList`, but both need to be treated as if
-    // they are `ElementList`s so they can be compared, as described the challenge rules. This is
-    // done by converting an `Integer` into a new `Vec` with it as the only element.
-    let left_elements = match left {
-        ListElement::Integer(int) => {
-            vec![ListElement::Integer(*int)]
-        }
-        ListElement::List(list) => list.clone(),
-    };
-
-    let right_elements = match right {
-        ListElement::Integer(int) => {
-            vec![ListElement::Integer(*int)]
-        }
-        ListElement::List(list) => list.clone(),
-    };
-
-    let left_length = left_elements.len();
-    let right_length = right_elements.len();
-    let shortest = usize::min(left_length, right_length);
-
-    for index in 0..shortest {
-        let pair_ordering_correct = is_order_correct(&left_elements[index], &right_elements[index]);
-        if pair_ordering_correct.is_some() {
-            return pair_ordering_correct;
-        }
-    }
-
-    // If the lists are the same length, all data passed is identical, so return `None` to
-    // indicate this.
-    if left_length == right_length {
-        return None;
+/// Compares the ordering of the 'left' and 'right' `Packet`s passed, as per the challenge rules.
+/// Returns 'Some(true)' if ordering is correct, 'Some(false)' if incorrect, and `None` if the two
+/// parameters passed are equal.
+fn is_order_correct(left: &Packet, right: &Packet) -> Option<bool> {
+    match left.cmp(right) {
+        Ordering::Less => Some(true),
+        Ordering::Greater => Some(false),
+        Ordering::Equal => None,
     }
-
-    // As per the challenge rules, the pairs are ordered correctly if the 'left' list is shorter,
-    // and are not ordered correctly otherwise.
-    Some(left_length < right_length)
 }
 
 /// Iterates through all pairs of packets passed to determine which pairs are in the correct order.
@@ -232,201 +123,16 @@ mod tests {
 [1,[2,[3,[4,[5,6,0]]]],8,9]
 ";
 
-    #[test]
-    fn test_parse_str_0() {
-        assert_eq!(
-            ListElement::parse_str("[11,0]"),
-            ListElement::List(vec![ListElement::Integer(11), ListElement::Integer(0),])
-        );
-    }
-
-    #[test]
-    fn test_parse_str_1() {
-        assert_eq!(
-            ListElement::parse_str("[[1],[2,3,4]]"),
-            ListElement::List(vec![
-                ListElement::List(vec![ListElement::Integer(1),]),
-                ListElement::List(vec![
-                    ListElement::Integer(2),
-                    ListElement::Integer(3),
-                    ListElement::Integer(4),
-                ]),
-            ]),
-        );
-    }
-
-    #[test]
-    #[should_panic]
-    fn test_parse_str_bad_char() {
-        ListElement::parse_str("[9,6,[2],a,5]");
-    }
-
-    #[test]
-    #[should_panic]
-    fn test_parse_str_unbalanced1() {
-        ListElement::parse_str("[9,6,[2]");
-    }
-
     #[test]
     fn test_parse_input() {
-        let result = parse_input(TEST_INPUT);
-
-        assert_eq!(
-            result[0].0,
-            ListElement::List(vec![
-                ListElement::Integer(1),
-                ListElement::Integer(1),
-                ListElement::Integer(3),
-                ListElement::Integer(1),
-                ListElement::Integer(1),
-            ]),
-        );
-
-        assert_eq!(
-            result[0].1,
-            ListElement::List(vec![
-                ListElement::Integer(1),
-                ListElement::Integer(1),
-                ListElement::Integer(5),
-                ListElement::Integer(1),
-                ListElement::Integer(1),
-            ]),
-        );
-
-        assert_eq!(
-            result[1].0,
-            ListElement::List(vec![
-                ListElement::List(vec![ListElement::Integer(1),]),
-                ListElement::List(vec![
-                    ListElement::Integer(2),
-                    ListElement::Integer(3),
-                    ListElement::Integer(4),
-                ]),
-            ]),
-        );
-
-        assert_eq!(
-            result[1].1,
-            ListElement::List(vec![
-                ListElement::List(vec![ListElement::Integer(1),]),
-                ListElement::Integer(4),
-            ]),
-        );
-
-        assert_eq!(
-            result[2].0,
-            ListElement::List(vec![ListElement::Integer(9),]),
-        );
-
-        assert_eq!(
-            result[2].1,
-            ListElement::List(vec![ListElement::List(vec![
-                ListElement::Integer(8),
-                ListElement::Integer(7),
-                ListElement::Integer(6),
-            ]),]),
-        );
-
-        assert_eq!(
-            result[3].0,
-            ListElement::List(vec![
-                ListElement::List(vec![ListElement::Integer(4), ListElement::Integer(4),]),
-                ListElement::Integer(4),
-                ListElement::Integer(4),
-            ]),
-        );
-
-        assert_eq!(
-            result[3].1,
-            ListElement::List(vec![
-                ListElement::List(vec![ListElement::Integer(4), ListElement::Integer(4),]),
-                ListElement::Integer(4),
-                ListElement::Integer(4),
-                ListElement::Integer(4),
-            ]),
-        );
-
-        assert_eq!(
-            result[4].0,
-            ListElement::List(vec![
-                ListElement::Integer(7),
-                ListElement::Integer(7),
-                ListElement::Integer(7),
-                ListElement::Integer(7),
-            ]),
-        );
-
-        assert_eq!(
-            result[4].1,
-            ListElement::List(vec![
-                ListElement::Integer(7),
-                ListElement::Integer(7),
-                ListElement::Integer(7),
-            ]),
-        );
-
-        assert_eq!(result[5].0, ListElement::List(vec![]),);
-
-        assert_eq!(
-            result[5].1,
-            ListElement::List(vec![ListElement::Integer(3),]),
-        );
-
-        assert_eq!(
-            result[6].0,
-            ListElement::List(vec![ListElement::List(vec![ListElement::List(vec![]),]),]),
-        );
-
-        assert_eq!(
-            result[6].1,
-            ListElement::List(vec![ListElement::List(vec![]),]),
-        );
-
-        assert_eq!(
-            result[7].0,
-            ListElement::List(vec![
-                ListElement::Integer(1),
-                ListElement::List(vec![
-                    ListElement::Integer(2),
-                    ListElement::List(vec![
-                        ListElement::Integer(3),
-                        ListElement::List(vec![
-                            ListElement::Integer(4),
-                            ListElement::List(vec![
-                                ListElement::Integer(5),
-                                ListElement::Integer(6),
-                                ListElement::Integer(7),
-                            ]),
-                        ]),
-                    ]),
-                ]),
-                ListElement::Integer(8),
-                ListElement::Integer(9),
-            ]),
-        );
+        let pairs = parse_input(TEST_INPUT);
+        let mut expected_lines = TEST_INPUT.lines().filter(|line| !line.is_empty());
 
-        assert_eq!(
-            result[7].1,
-            ListElement::List(vec![
-                ListElement::Integer(1),
-                ListElement::List(vec![
-                    ListElement::Integer(2),
-                    ListElement::List(vec![
-                        ListElement::Integer(3),
-                        ListElement::List(vec![
-                            ListElement::Integer(4),
-                            ListElement::List(vec![
-                                ListElement::Integer(5),
-                                ListElement::Integer(6),
-                                ListElement::Integer(0),
-                            ]),
-                        ]),
-                    ]),
-                ]),
-                ListElement::Integer(8),
-                ListElement::Integer(9),
-            ]),
-        );
+        assert_eq!(pairs.len(), 8);
+        for (left, right) in pairs {
+            assert_eq!(left.to_string(), expected_lines.next().unwrap());
+            assert_eq!(right.to_string(), expected_lines.next().unwrap());
+        }
     }
 
     #[test]
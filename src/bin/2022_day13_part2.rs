@@ -5,179 +5,50 @@
 //!
 //! Sort an input file of packets based on ordering rules described in the challenge.
 
-use std::cmp::Ordering;
+use advent_of_code_rust::nested_list::NestedList;
 use std::fs;
 
 const INPUT_FILENAME: &str = "2022_day13_input.txt";
 
 type Int = u8;
+type Packet = NestedList<Int>;
 
-/// A `ListElement` contains either an individual number or a `Vec` of zero or more `ListElement`s.
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
-enum ListElement {
-    Integer(Int),
-    List(Vec<ListElement>),
-}
-
-impl ListElement {
-    /// Convert the passed string into `ListElement`s.
-    fn parse_str(input: &str) -> Self {
-        let input_chars: Vec<char> = input.chars().collect();
-        assert_eq!(input_chars[0], '[');
-
-        let slice = &mut &input_chars[1..];
-
-        Self::parse_element_recurse(slice)
-    }
-
-    /// Internal function that parses a slice of `char`s representing the input string into a
-    /// `ListElement` representation. The slice passed is modified to keep track of the input that
-    /// has been processed so far.
-    ///
-    /// # Panics
-    ///
-    /// Panics if the input contains an invalid character or is malformed in certain ways. However,
-    /// many malformed inputs are accepted if the problems are not too bad, e.g., ",," is treated
-    /// as ",".
-    fn parse_element_recurse(ic: &mut &[char]) -> Self {
-        let mut elements = Vec::new();
-
-        loop {
-            match ic[0] {
-                ']' => {
-                    *ic = &ic[1..];
-                    break;
-                }
-                '[' => {
-                    *ic = &ic[1..];
-                    let sublist = ListElement::parse_element_recurse(ic);
-                    elements.push(sublist);
-                }
-                '0'..='9' => {
-                    let mut char_digits = Vec::new();
-
-                    while ic[0].is_ascii_digit() {
-                        char_digits.push(ic[0]);
-                        *ic = &ic[1..];
-                    }
-
-                    let int_tmp = char_digits.iter().collect::<String>().parse().unwrap();
-
-                    elements.push(ListElement::Integer(int_tmp));
-                }
-                ',' => {
-                    *ic = &ic[1..];
-                }
-                _ => {
-                    panic!("Unrecognized character '{}' in input", ic[0]);
-                }
-            }
-
-            if ic.is_empty() {
-                panic!("The input contains unbalanced start and end list tags");
-            }
-        }
-
-        ListElement::List(elements)
-    }
-}
-
-/// Parses the input and returns its `ListElement`s representation in a `Vec`.
+/// Parses the input and returns its `Packet`s in a `Vec`.
 ///
 /// # Panics
 ///
 /// Panics if the input is malformed.
-fn parse_input(input: &str) -> Vec<ListElement> {
-    let mut list_elements = Vec::new();
-
-    for line in input.lines() {
-        if !line.is_empty() {
-            list_elements.push(ListElement::parse_str(line));
-        }
-    }
-
-    list_elements
+fn parse_input(input: &str) -> Vec<Packet> {
+    input
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.parse().unwrap())
+        .collect()
 }
 
-/// Compares the ordering of the two 'left' and 'right' `ListElement`s passed, as per the challenge
-/// rules.
-fn compare_packets(left: &ListElement, right: &ListElement) -> Ordering {
-    if let ListElement::Integer(left_integer) = left {
-        if let ListElement::Integer(right_integer) = right {
-            return left_integer.cmp(right_integer);
-        }
-    }
-
-    // At least one of 'left' or 'right' is an `ElementList`, but both need to be treated as if
-    // they are `ElementList`s so they can be compared, as described the challenge rules. This is
-    // done by converting an `Integer` into a new `Vec` with it as the only element.
-    let left_elements = match left {
-        ListElement::Integer(int) => {
-            vec![ListElement::Integer(*int)]
-        }
-        ListElement::List(list) => list.clone(),
-    };
-
-    let right_elements = match right {
-        ListElement::Integer(int) => {
-            vec![ListElement::Integer(*int)]
-        }
-        ListElement::List(list) => list.clone(),
-    };
-
-    let left_length = left_elements.len();
-    let right_length = right_elements.len();
-    let shortest = usize::min(left_length, right_length);
-
-    for index in 0..shortest {
-        let pair_ordering = compare_packets(&left_elements[index], &right_elements[index]);
-        if pair_ordering != Ordering::Equal {
-            return pair_ordering;
-        }
-    }
-
-    // If the lists are the same length, all data passed is identical, so return `None` to
-    // indicate this.
-    if left_length == right_length {
-        return Ordering::Equal;
-    }
-
-    // As per the challenge rules, the pairs are ordered correctly if the 'left' list is shorter,
-    // and are not ordered correctly otherwise.
-    if left_length < right_length {
-        Ordering::Less
-    } else {
-        Ordering::Greater
-    }
+/// Returns the divider packet containing only `value`, i.e., "[[value]]".
+fn divider_packet(value: Int) -> Packet {
+    NestedList::List(vec![NestedList::List(vec![NestedList::Integer(value)])])
 }
 
-/// Append the two divider packets required by the challenge to the `Vec` of `ListElement`s
-/// passed.
-fn add_divider_packets(packets: &mut Vec<ListElement>) {
-    packets.push(ListElement::List(vec![ListElement::List(vec![
-        ListElement::Integer(2),
-    ])]));
-
-    packets.push(ListElement::List(vec![ListElement::List(vec![
-        ListElement::Integer(6),
-    ])]));
+/// Append the two divider packets required by the challenge to the `Vec` of `Packet`s passed.
+fn add_divider_packets(packets: &mut Vec<Packet>) {
+    packets.push(divider_packet(2));
+    packets.push(divider_packet(6));
 }
 
 /// Sort all packets based on the ordering defined in the challenge.
-fn sort_packets(packets: &mut [ListElement]) {
-    packets.sort_unstable_by(compare_packets);
+fn sort_packets(packets: &mut [Packet]) {
+    packets.sort_unstable();
 }
 
-/// Returns the index of `packet` in `packets`, or `None` if it is not found. The first index is 0,
-/// which is the Rust standard, so the caller may need to add one to be consistent with the
-/// challenge.
-fn find_packet(packet: &ListElement, packets: &[ListElement]) -> Option<usize> {
-    for (index, list_element) in packets.iter().enumerate() {
-        if compare_packets(packet, list_element) == Ordering::Equal {
-            return Some(index);
-        }
-    }
-    None
+/// Returns the index of `packet` in `packets`, or `None` if it is not found. `packets` must
+/// already be sorted. If several packets are equal to `packet`, e.g., "[2]" and "[[2]]", the
+/// index of the first is returned. The first index is 0, which is the Rust standard, so the
+/// caller may need to add one to be consistent with the challenge.
+fn find_packet(packet: &Packet, packets: &[Packet]) -> Option<usize> {
+    let index = packets.partition_point(|p| p < packet);
+    (packets.get(index) == Some(packet)).then_some(index)
 }
 
 /// Adds the divider packets to the `Vec` of packets passed, sorts all packets, finds the indexes
@@ -188,21 +59,12 @@ fn find_packet(packet: &ListElement, packets: &[ListElement]) -> Option<usize> {
 /// Panics if any of the pairs are identical.
 //
 // The challenge numbers indexes starting at 1 rather than 0, but this is taken into account.
-fn do_challenge(packets: &mut Vec<ListElement>) -> usize {
+fn do_challenge(packets: &mut Vec<Packet>) -> usize {
     add_divider_packets(packets);
     sort_packets(packets);
 
-    let first = find_packet(
-        &ListElement::List(vec![ListElement::List(vec![ListElement::Integer(2)])]),
-        packets,
-    )
-    .unwrap();
-
-    let second = find_packet(
-        &ListElement::List(vec![ListElement::List(vec![ListElement::Integer(6)])]),
-        packets,
-    )
-    .unwrap();
+    let first = find_packet(&divider_packet(2), packets).unwrap();
+    let second = find_packet(&divider_packet(6), packets).unwrap();
 
     (first + 1) * (second + 1)
 }
@@ -244,217 +106,198 @@ mod tests {
 [1,[2,[3,[4,[5,6,0]]]],8,9]
 ";
 
-    #[test]
-    fn test_parse_str_0() {
-        assert_eq!(
-            ListElement::parse_str("[11,0]"),
-            ListElement::List(vec![ListElement::Integer(11), ListElement::Integer(0),])
-        );
-    }
-
-    #[test]
-    fn test_parse_str_1() {
-        assert_eq!(
-            ListElement::parse_str("[[1],[2,3,4]]"),
-            ListElement::List(vec![
-                ListElement::List(vec![ListElement::Integer(1),]),
-                ListElement::List(vec![
-                    ListElement::Integer(2),
-                    ListElement::Integer(3),
-                    ListElement::Integer(4),
-                ]),
-            ]),
-        );
-    }
-
-    #[test]
-    #[should_panic]
-    fn test_parse_str_bad_char() {
-        ListElement::parse_str("[9,6,[2],a,5]");
+    /// Asserts that `actual` has the same structure as `expected`. `assert_eq!` is not used, as
+    /// packets with different structures, such as "[9]" and "[[9]]", compare as equal.
+    fn assert_identical(actual: &Packet, expected: &Packet) {
+        assert_eq!(actual.to_string(), expected.to_string());
     }
 
-    #[test]
-    #[should_panic]
-    fn test_parse_str_unbalanced1() {
-        ListElement::parse_str("[9,6,[2]");
+    fn assert_all_identical(actual: &[Packet], expected: &[Packet]) {
+        let to_strings =
+            |packets: &[Packet]| -> Vec<String> { packets.iter().map(|p| p.to_string()).collect() };
+        assert_eq!(to_strings(actual), to_strings(expected));
     }
 
     #[test]
     fn test_parse_input() {
         let result = parse_input(TEST_INPUT);
 
-        assert_eq!(
-            result[0],
-            ListElement::List(vec![
-                ListElement::Integer(1),
-                ListElement::Integer(1),
-                ListElement::Integer(3),
-                ListElement::Integer(1),
-                ListElement::Integer(1),
+        assert_identical(
+            &result[0],
+            &NestedList::List(vec![
+                NestedList::Integer(1),
+                NestedList::Integer(1),
+                NestedList::Integer(3),
+                NestedList::Integer(1),
+                NestedList::Integer(1),
             ]),
         );
 
-        assert_eq!(
-            result[1],
-            ListElement::List(vec![
-                ListElement::Integer(1),
-                ListElement::Integer(1),
-                ListElement::Integer(5),
-                ListElement::Integer(1),
-                ListElement::Integer(1),
+        assert_identical(
+            &result[1],
+            &NestedList::List(vec![
+                NestedList::Integer(1),
+                NestedList::Integer(1),
+                NestedList::Integer(5),
+                NestedList::Integer(1),
+                NestedList::Integer(1),
             ]),
         );
 
-        assert_eq!(
-            result[2],
-            ListElement::List(vec![
-                ListElement::List(vec![ListElement::Integer(1),]),
-                ListElement::List(vec![
-                    ListElement::Integer(2),
-                    ListElement::Integer(3),
-                    ListElement::Integer(4),
+        assert_identical(
+            &result[2],
+            &NestedList::List(vec![
+                NestedList::List(vec![NestedList::Integer(1)]),
+                NestedList::List(vec![
+                    NestedList::Integer(2),
+                    NestedList::Integer(3),
+                    NestedList::Integer(4),
                 ]),
             ]),
         );
 
-        assert_eq!(
-            result[3],
-            ListElement::List(vec![
-                ListElement::List(vec![ListElement::Integer(1),]),
-                ListElement::Integer(4),
+        assert_identical(
+            &result[3],
+            &NestedList::List(vec![
+                NestedList::List(vec![NestedList::Integer(1)]),
+                NestedList::Integer(4),
             ]),
         );
 
-        assert_eq!(result[4], ListElement::List(vec![ListElement::Integer(9),]),);
+        assert_identical(&result[4], &NestedList::List(vec![NestedList::Integer(9)]));
 
-        assert_eq!(
-            result[5],
-            ListElement::List(vec![ListElement::List(vec![
-                ListElement::Integer(8),
-                ListElement::Integer(7),
-                ListElement::Integer(6),
-            ]),]),
+        assert_identical(
+            &result[5],
+            &NestedList::List(vec![NestedList::List(vec![
+                NestedList::Integer(8),
+                NestedList::Integer(7),
+                NestedList::Integer(6),
+            ])]),
         );
 
-        assert_eq!(
-            result[6],
-            ListElement::List(vec![
-                ListElement::List(vec![ListElement::Integer(4), ListElement::Integer(4),]),
-                ListElement::Integer(4),
-                ListElement::Integer(4),
+        assert_identical(
+            &result[6],
+            &NestedList::List(vec![
+                NestedList::List(vec![NestedList::Integer(4), NestedList::Integer(4)]),
+                NestedList::Integer(4),
+                NestedList::Integer(4),
             ]),
         );
 
-        assert_eq!(
-            result[7],
-            ListElement::List(vec![
-                ListElement::List(vec![ListElement::Integer(4), ListElement::Integer(4),]),
-                ListElement::Integer(4),
-                ListElement::Integer(4),
-                ListElement::Integer(4),
+        assert_identical(
+            &result[7],
+            &NestedList::List(vec![
+                NestedList::List(vec![NestedList::Integer(4), NestedList::Integer(4)]),
+                NestedList::Integer(4),
+                NestedList::Integer(4),
+                NestedList::Integer(4),
             ]),
         );
 
-        assert_eq!(
-            result[8],
-            ListElement::List(vec![
-                ListElement::Integer(7),
-                ListElement::Integer(7),
-                ListElement::Integer(7),
-                ListElement::Integer(7),
+        assert_identical(
+            &result[8],
+            &NestedList::List(vec![
+                NestedList::Integer(7),
+                NestedList::Integer(7),
+                NestedList::Integer(7),
+                NestedList::Integer(7),
             ]),
         );
 
-        assert_eq!(
-            result[9],
-            ListElement::List(vec![
-                ListElement::Integer(7),
-                ListElement::Integer(7),
-                ListElement::Integer(7),
+        assert_identical(
+            &result[9],
+            &NestedList::List(vec![
+                NestedList::Integer(7),
+                NestedList::Integer(7),
+                NestedList::Integer(7),
             ]),
         );
 
-        assert_eq!(result[10], ListElement::List(vec![]),);
+        assert_identical(&result[10], &NestedList::List(vec![]));
 
-        assert_eq!(
-            result[11],
-            ListElement::List(vec![ListElement::Integer(3),]),
-        );
+        assert_identical(&result[11], &NestedList::List(vec![NestedList::Integer(3)]));
 
-        assert_eq!(
-            result[12],
-            ListElement::List(vec![ListElement::List(vec![ListElement::List(vec![]),]),]),
+        assert_identical(
+            &result[12],
+            &NestedList::List(vec![NestedList::List(vec![NestedList::List(vec![])])]),
         );
 
-        assert_eq!(
-            result[13],
-            ListElement::List(vec![ListElement::List(vec![]),]),
+        assert_identical(
+            &result[13],
+            &NestedList::List(vec![NestedList::List(vec![])]),
         );
 
-        assert_eq!(
-            result[14],
-            ListElement::List(vec![
-                ListElement::Integer(1),
-                ListElement::List(vec![
-                    ListElement::Integer(2),
-                    ListElement::List(vec![
-                        ListElement::Integer(3),
-                        ListElement::List(vec![
-                            ListElement::Integer(4),
-                            ListElement::List(vec![
-                                ListElement::Integer(5),
-                                ListElement::Integer(6),
-                                ListElement::Integer(7),
+        assert_identical(
+            &result[14],
+            &NestedList::List(vec![
+                NestedList::Integer(1),
+                NestedList::List(vec![
+                    NestedList::Integer(2),
+                    NestedList::List(vec![
+                        NestedList::Integer(3),
+                        NestedList::List(vec![
+                            NestedList::Integer(4),
+                            NestedList::List(vec![
+                                NestedList::Integer(5),
+                                NestedList::Integer(6),
+                                NestedList::Integer(7),
                             ]),
                         ]),
                     ]),
                 ]),
-                ListElement::Integer(8),
-                ListElement::Integer(9),
+                NestedList::Integer(8),
+                NestedList::Integer(9),
             ]),
         );
 
-        assert_eq!(
-            result[15],
-            ListElement::List(vec![
-                ListElement::Integer(1),
-                ListElement::List(vec![
-                    ListElement::Integer(2),
-                    ListElement::List(vec![
-                        ListElement::Integer(3),
-                        ListElement::List(vec![
-                            ListElement::Integer(4),
-                            ListElement::List(vec![
-                                ListElement::Integer(5),
-                                ListElement::Integer(6),
-                                ListElement::Integer(0),
+        assert_identical(
+            &result[15],
+            &NestedList::List(vec![
+                NestedList::Integer(1),
+                NestedList::List(vec![
+                    NestedList::Integer(2),
+                    NestedList::List(vec![
+                        NestedList::Integer(3),
+                        NestedList::List(vec![
+                            NestedList::Integer(4),
+                            NestedList::List(vec![
+                                NestedList::Integer(5),
+                                NestedList::Integer(6),
+                                NestedList::Integer(0),
                             ]),
                         ]),
                     ]),
                 ]),
-                ListElement::Integer(8),
-                ListElement::Integer(9),
+                NestedList::Integer(8),
+                NestedList::Integer(9),
             ]),
         );
     }
 
     #[test]
-    fn check_compare_packets() {
-        let packets = parse_input(TEST_INPUT);
-        assert_eq!(compare_packets(&packets[0], &packets[1]), Ordering::Less);
-        assert_eq!(compare_packets(&packets[2], &packets[3]), Ordering::Less);
-        assert_eq!(compare_packets(&packets[4], &packets[5]), Ordering::Greater);
-        assert_eq!(compare_packets(&packets[6], &packets[7]), Ordering::Less);
-        assert_eq!(compare_packets(&packets[8], &packets[9]), Ordering::Greater);
-        assert_eq!(compare_packets(&packets[10], &packets[11]), Ordering::Less);
-        assert_eq!(
-            compare_packets(&packets[12], &packets[13]),
-            Ordering::Greater
-        );
-        assert_eq!(
-            compare_packets(&packets[14], &packets[15]),
-            Ordering::Greater
-        );
+    fn test_find_packet() {
+        let mut packets = parse_input(TEST_INPUT);
+        sort_packets(&mut packets);
+
+        assert_eq!(find_packet(&"[]".parse().unwrap(), &packets), Some(0));
+        assert_eq!(find_packet(&"[9]".parse().unwrap(), &packets), Some(15));
+        assert_eq!(find_packet(&divider_packet(2), &packets), None);
+    }
+
+    #[test]
+    fn test_find_packet_equal_to_others() {
+        let mut packets = parse_input("[[[2]]]\n[1]\n[2]\n[[2]]\n[3]\n");
+        sort_packets(&mut packets);
+
+        assert_eq!(find_packet(&divider_packet(2), &packets), Some(1));
+        assert_eq!(find_packet(&"2".parse().unwrap(), &packets), Some(1));
+        assert_eq!(find_packet(&"[3]".parse().unwrap(), &packets), Some(4));
+    }
+
+    #[test]
+    fn test_do_challenge_with_packet_equal_to_divider() {
+        let mut packets = parse_input("[1]\n[2]\n[[[2]]]\n[3]\n");
+        assert_eq!(do_challenge(&mut packets), 2 * 6);
     }
 
     #[test]
@@ -464,14 +307,14 @@ mod tests {
         add_divider_packets(&mut packets);
         assert_eq!(packets.len(), 18);
 
-        assert_eq!(
-            packets[16],
-            ListElement::List(vec![ListElement::List(vec![ListElement::Integer(2),]),])
+        assert_identical(
+            &packets[16],
+            &NestedList::List(vec![NestedList::List(vec![NestedList::Integer(2)])]),
         );
 
-        assert_eq!(
-            packets[17],
-            ListElement::List(vec![ListElement::List(vec![ListElement::Integer(6),]),])
+        assert_identical(
+            &packets[17],
+            &NestedList::List(vec![NestedList::List(vec![NestedList::Integer(6)])]),
         );
     }
 
@@ -480,106 +323,106 @@ mod tests {
         let mut packets = parse_input(TEST_INPUT);
         sort_packets(&mut packets);
 
-        assert_eq!(
-            packets,
-            vec![
-                ListElement::List(vec![]),
-                ListElement::List(vec![ListElement::List(vec![]),]),
-                ListElement::List(vec![ListElement::List(vec![ListElement::List(vec![]),]),]),
-                ListElement::List(vec![
-                    ListElement::Integer(1),
-                    ListElement::Integer(1),
-                    ListElement::Integer(3),
-                    ListElement::Integer(1),
-                    ListElement::Integer(1),
+        assert_all_identical(
+            &packets,
+            &[
+                NestedList::List(vec![]),
+                NestedList::List(vec![NestedList::List(vec![])]),
+                NestedList::List(vec![NestedList::List(vec![NestedList::List(vec![])])]),
+                NestedList::List(vec![
+                    NestedList::Integer(1),
+                    NestedList::Integer(1),
+                    NestedList::Integer(3),
+                    NestedList::Integer(1),
+                    NestedList::Integer(1),
                 ]),
-                ListElement::List(vec![
-                    ListElement::Integer(1),
-                    ListElement::Integer(1),
-                    ListElement::Integer(5),
-                    ListElement::Integer(1),
-                    ListElement::Integer(1),
+                NestedList::List(vec![
+                    NestedList::Integer(1),
+                    NestedList::Integer(1),
+                    NestedList::Integer(5),
+                    NestedList::Integer(1),
+                    NestedList::Integer(1),
                 ]),
-                ListElement::List(vec![
-                    ListElement::List(vec![ListElement::Integer(1),]),
-                    ListElement::List(vec![
-                        ListElement::Integer(2),
-                        ListElement::Integer(3),
-                        ListElement::Integer(4),
+                NestedList::List(vec![
+                    NestedList::List(vec![NestedList::Integer(1)]),
+                    NestedList::List(vec![
+                        NestedList::Integer(2),
+                        NestedList::Integer(3),
+                        NestedList::Integer(4),
                     ]),
                 ]),
-                ListElement::List(vec![
-                    ListElement::Integer(1),
-                    ListElement::List(vec![
-                        ListElement::Integer(2),
-                        ListElement::List(vec![
-                            ListElement::Integer(3),
-                            ListElement::List(vec![
-                                ListElement::Integer(4),
-                                ListElement::List(vec![
-                                    ListElement::Integer(5),
-                                    ListElement::Integer(6),
-                                    ListElement::Integer(0),
+                NestedList::List(vec![
+                    NestedList::Integer(1),
+                    NestedList::List(vec![
+                        NestedList::Integer(2),
+                        NestedList::List(vec![
+                            NestedList::Integer(3),
+                            NestedList::List(vec![
+                                NestedList::Integer(4),
+                                NestedList::List(vec![
+                                    NestedList::Integer(5),
+                                    NestedList::Integer(6),
+                                    NestedList::Integer(0),
                                 ]),
                             ]),
                         ]),
                     ]),
-                    ListElement::Integer(8),
-                    ListElement::Integer(9),
+                    NestedList::Integer(8),
+                    NestedList::Integer(9),
                 ]),
-                ListElement::List(vec![
-                    ListElement::Integer(1),
-                    ListElement::List(vec![
-                        ListElement::Integer(2),
-                        ListElement::List(vec![
-                            ListElement::Integer(3),
-                            ListElement::List(vec![
-                                ListElement::Integer(4),
-                                ListElement::List(vec![
-                                    ListElement::Integer(5),
-                                    ListElement::Integer(6),
-                                    ListElement::Integer(7),
+                NestedList::List(vec![
+                    NestedList::Integer(1),
+                    NestedList::List(vec![
+                        NestedList::Integer(2),
+                        NestedList::List(vec![
+                            NestedList::Integer(3),
+                            NestedList::List(vec![
+                                NestedList::Integer(4),
+                                NestedList::List(vec![
+                                    NestedList::Integer(5),
+                                    NestedList::Integer(6),
+                                    NestedList::Integer(7),
                                 ]),
                             ]),
                         ]),
                     ]),
-                    ListElement::Integer(8),
-                    ListElement::Integer(9),
+                    NestedList::Integer(8),
+                    NestedList::Integer(9),
                 ]),
-                ListElement::List(vec![
-                    ListElement::List(vec![ListElement::Integer(1),]),
-                    ListElement::Integer(4),
+                NestedList::List(vec![
+                    NestedList::List(vec![NestedList::Integer(1)]),
+                    NestedList::Integer(4),
                 ]),
-                ListElement::List(vec![ListElement::Integer(3),]),
-                ListElement::List(vec![
-                    ListElement::List(vec![ListElement::Integer(4), ListElement::Integer(4),]),
-                    ListElement::Integer(4),
-                    ListElement::Integer(4),
+                NestedList::List(vec![NestedList::Integer(3)]),
+                NestedList::List(vec![
+                    NestedList::List(vec![NestedList::Integer(4), NestedList::Integer(4)]),
+                    NestedList::Integer(4),
+                    NestedList::Integer(4),
                 ]),
-                ListElement::List(vec![
-                    ListElement::List(vec![ListElement::Integer(4), ListElement::Integer(4),]),
-                    ListElement::Integer(4),
-                    ListElement::Integer(4),
-                    ListElement::Integer(4),
+                NestedList::List(vec![
+                    NestedList::List(vec![NestedList::Integer(4), NestedList::Integer(4)]),
+                    NestedList::Integer(4),
+                    NestedList::Integer(4),
+                    NestedList::Integer(4),
                 ]),
-                ListElement::List(vec![
-                    ListElement::Integer(7),
-                    ListElement::Integer(7),
-                    ListElement::Integer(7),
+                NestedList::List(vec![
+                    NestedList::Integer(7),
+                    NestedList::Integer(7),
+                    NestedList::Integer(7),
                 ]),
-                ListElement::List(vec![
-                    ListElement::Integer(7),
-                    ListElement::Integer(7),
-                    ListElement::Integer(7),
-                    ListElement::Integer(7),
+                NestedList::List(vec![
+                    NestedList::Integer(7),
+                    NestedList::Integer(7),
+                    NestedList::Integer(7),
+                    NestedList::Integer(7),
                 ]),
-                ListElement::List(vec![ListElement::List(vec![
-                    ListElement::Integer(8),
-                    ListElement::Integer(7),
-                    ListElement::Integer(6),
-                ]),]),
-                ListElement::List(vec![ListElement::Integer(9),]),
-            ]
+                NestedList::List(vec![NestedList::List(vec![
+                    NestedList::Integer(8),
+                    NestedList::Integer(7),
+                    NestedList::Integer(6),
+                ])]),
+                NestedList::List(vec![NestedList::Integer(9)]),
+            ],
         );
     }
 
@@ -0,0 +1,473 @@
+//! Ranking hands of Camel Cards, as described in Advent of Code 2023 Day 7.
+//! https://adventofcode.com/2023/day/7
+//!
+//! Each hand is ranked first by its type, such as a full house or two pair, then by comparing its
+//! cards one at a time, in the order they were dealt, by their strength. `Rules` holds the order
+//! of strength of the cards, and which cards are wildcards. A wildcard counts as whichever card
+//! makes the strongest type of hand, but is still compared by its own strength. Part 1 of the
+//! challenge has no wildcards, and part 2 makes 'J' a wildcard that is the weakest card.
+//!
+//! The type of a hand comes from the sizes of its groups of matching cards, largest first. For a
+//! hand of five cards, comparing these sizes in turn gives the order of types in the challenge, so
+//! the same comparison ranks hands of any size. Adding every wildcard to the largest group of
+//! other cards gives the largest first group, so is always the strongest substitution.
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// The order of strength of the cards in part 1 of the challenge, from weakest to strongest.
+pub const STANDARD_ORDER: &str = "23456789TJQKA";
+/// The order of strength of the cards in part 2 of the challenge, where 'J' is a wildcard.
+pub const JOKER_ORDER: &str = "J23456789TQKA";
+pub const JOKER: &str = "J";
+
+pub type Card = char;
+pub type Bid = u64;
+
+/// The type of a hand, as the sizes of its groups of matching cards, largest first. Types are
+/// ordered from weakest to strongest.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct HandType {
+    groups: Vec<usize>,
+}
+
+impl HandType {
+    pub fn groups(&self) -> &[usize] {
+        &self.groups
+    }
+}
+
+/// Displays the name of the type used in the challenge, e.g., "Full house", or otherwise the
+/// size of the group of matching cards, e.g., "6 of a kind", or the sizes of the groups, e.g.,
+/// "Groups of 3 and 3".
+impl fmt::Display for HandType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let matched: Vec<usize> = self
+            .groups
+            .iter()
+            .copied()
+            .filter(|&size| size > 1)
+            .collect();
+
+        let name = match matched.as_slice() {
+            [] => "High card",
+            [2] => "One pair",
+            [2, 2] => "Two pair",
+            [3] => "Three of a kind",
+            [3, 2] => "Full house",
+            [4] => "Four of a kind",
+            [5] => "Five of a kind",
+            [size] => return write!(f, "{} of a kind", size),
+            [rest @ .., last] => {
+                let sizes: Vec<String> = rest.iter().map(usize::to_string).collect();
+                return write!(f, "Groups of {} and {}", sizes.join(", "), last);
+            }
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The wildcards in a hand, in the order they first appear, and the card they all count as.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Substitution {
+    pub wildcards: Vec<Card>,
+    pub card: Card,
+}
+
+/// A hand of cards with its bid, ranked under some `Rules`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Hand {
+    pub cards: Vec<Card>,
+    pub bid: Bid,
+    pub hand_type: HandType,
+    /// What the wildcards in the hand count as, or `None` if there are no wildcards.
+    pub substitution: Option<Substitution>,
+    /// The strength of each card, where the weakest card has strength 0.
+    strengths: Vec<usize>,
+}
+
+impl Hand {
+    /// Returns the key that orders hands from weakest to strongest.
+    pub fn rank_key(&self) -> (&HandType, &[usize]) {
+        (&self.hand_type, &self.strengths)
+    }
+
+    /// Returns an explanation of the hand's type, including what any wildcards count as, e.g.,
+    /// "KTJJT: Four of a kind, with J as T".
+    pub fn explain(&self) -> String {
+        let cards: String = self.cards.iter().collect();
+
+        match &self.substitution {
+            None => format!("{}: {}", cards, self.hand_type),
+            Some(substitution) => {
+                let wildcards: Vec<String> =
+                    substitution.wildcards.iter().map(Card::to_string).collect();
+                format!(
+                    "{}: {}, with {} as {}",
+                    cards,
+                    self.hand_type,
+                    wildcards.join(" and "),
+                    substitution.card
+                )
+            }
+        }
+    }
+}
+
+/// The order of strength of the cards, and which are wildcards.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rules {
+    /// The cards from weakest to strongest.
+    order: Vec<Card>,
+    wildcards: Vec<Card>,
+}
+
+impl Rules {
+    /// Returns rules where the cards in `order` go from weakest to strongest, and the cards in
+    /// `wildcards` count as whichever card makes the strongest hand.
+    ///
+    /// Returns an error if a card appears twice in `order`, or a wildcard is not in `order`.
+    pub fn new(order: &str, wildcards: &str) -> Result<Self, String> {
+        let order: Vec<Card> = order.chars().collect();
+        for (i, card) in order.iter().enumerate() {
+            if order[..i].contains(card) {
+                return Err(format!("The card '{}' appears twice in the order", card));
+            }
+        }
+
+        let wildcards: Vec<Card> = wildcards.chars().collect();
+        if let Some(card) = wildcards.iter().find(|card| !order.contains(card)) {
+            return Err(format!("The wildcard '{}' is not in the order", card));
+        }
+
+        Ok(Self { order, wildcards })
+    }
+
+    /// Returns the rules of part 1 of the challenge.
+    pub fn standard() -> Self {
+        Self::new(STANDARD_ORDER, "").unwrap()
+    }
+
+    /// Returns the rules of part 2 of the challenge.
+    pub fn jokers() -> Self {
+        Self::new(JOKER_ORDER, JOKER).unwrap()
+    }
+
+    fn strength(&self, card: Card) -> Result<usize, String> {
+        self.order
+            .iter()
+            .position(|&c| c == card)
+            .ok_or_else(|| format!("Unrecognized card '{}'", card))
+    }
+
+    /// Returns the strongest type of hand `cards` can make, and what the wildcards count as, if
+    /// there are any. Where there is a choice of card, the strongest is used. If every card is
+    /// a wildcard, they count as the strongest card that is not one.
+    fn classify(&self, cards: &[Card]) -> (HandType, Option<Substitution>) {
+        let mut counts: HashMap<Card, usize> = HashMap::new();
+        let mut wildcards: Vec<Card> = Vec::new();
+        for &card in cards {
+            if self.wildcards.contains(&card) {
+                wildcards.push(card);
+            } else {
+                *counts.entry(card).or_default() += 1;
+            }
+        }
+
+        let mut groups: Vec<(usize, Card)> = counts.into_iter().map(|(c, n)| (n, c)).collect();
+        // Largest groups first, then strongest cards first.
+        groups.sort_by_key(|&(size, card)| {
+            std::cmp::Reverse((size, self.strength(card).unwrap_or(0)))
+        });
+
+        let mut sizes: Vec<usize> = groups.iter().map(|&(size, _)| size).collect();
+        match sizes.first_mut() {
+            Some(largest) => *largest += wildcards.len(),
+            None if !wildcards.is_empty() => sizes.push(wildcards.len()),
+            None => {}
+        }
+
+        let substitution = (!wildcards.is_empty()).then(|| {
+            let card = match groups.first() {
+                Some(&(_, card)) => card,
+                None => self
+                    .order
+                    .iter()
+                    .rev()
+                    .find(|card| !self.wildcards.contains(card))
+                    .copied()
+                    .unwrap_or(wildcards[0]),
+            };
+            let mut distinct = Vec::new();
+            for wildcard in wildcards {
+                if !distinct.contains(&wildcard) {
+                    distinct.push(wildcard);
+                }
+            }
+            Substitution {
+                wildcards: distinct,
+                card,
+            }
+        });
+
+        (HandType { groups: sizes }, substitution)
+    }
+
+    /// Parses a hand of any number of cards followed by its bid, e.g., "32T3K 765".
+    pub fn parse_hand(&self, s: &str) -> Result<Hand, String> {
+        let (cards, bid) = s
+            .trim()
+            .split_once(' ')
+            .ok_or_else(|| format!("Expected cards and a bid, not '{}'", s))?;
+
+        let cards: Vec<Card> = cards.chars().collect();
+        if cards.is_empty() {
+            return Err(format!("The hand '{}' has no cards", s));
+        }
+        let strengths = cards
+            .iter()
+            .map(|&card| self.strength(card))
+            .collect::<Result<_, _>>()?;
+        let (hand_type, substitution) = self.classify(&cards);
+
+        Ok(Hand {
+            cards,
+            bid: bid
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid bid in '{}'", s))?,
+            hand_type,
+            substitution,
+            strengths,
+        })
+    }
+
+    /// Parses one hand per line. See `parse_hand`.
+    pub fn parse_hands(&self, input: &str) -> Result<Vec<Hand>, String> {
+        input
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| self.parse_hand(line))
+            .collect()
+    }
+}
+
+/// Sorts `hands` from weakest to strongest.
+pub fn sort_hands(hands: &mut [Hand]) {
+    hands.sort_by(|a, b| a.rank_key().cmp(&b.rank_key()));
+}
+
+/// Returns the total winnings of `hands`, as the sum of each hand's bid multiplied by its rank,
+/// where the weakest hand has rank 1.
+pub fn total_winnings(hands: &[Hand]) -> Bid {
+    let mut hands = hands.to_vec();
+    sort_hands(&mut hands);
+
+    hands
+        .iter()
+        .zip(1..)
+        .map(|(hand, rank)| hand.bid * rank)
+        .sum()
+}
+
+/// Options for ranking hands, given on the command line.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RunOptions {
+    /// The order of strength of the cards, from weakest to strongest, if not the challenge's.
+    pub order: Option<String>,
+    /// The wildcards, if not the challenge's.
+    pub wildcards: Option<String>,
+    /// Whether to explain the type of each hand.
+    pub explain: bool,
+}
+
+impl RunOptions {
+    /// Parses the command line arguments `args`, which may contain "--order <cards>",
+    /// "--wild <cards>" and "--explain". An empty string of wildcards means there are none.
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut options = Self {
+            order: None,
+            wildcards: None,
+            explain: false,
+        };
+
+        let mut args = args.iter();
+        while let Some(option) = args.next() {
+            if option == "--explain" {
+                options.explain = true;
+                continue;
+            }
+
+            let value = args
+                .next()
+                .ok_or_else(|| format!("Missing value for option '{}'", option))?;
+
+            match option.as_str() {
+                "--order" => options.order = Some(value.clone()),
+                "--wild" => options.wildcards = Some(value.clone()),
+                _ => return Err(format!("Unknown option '{}'", option)),
+            }
+        }
+
+        Ok(options)
+    }
+
+    /// Returns the rules set by these options, using `default_order` and `default_wildcards`
+    /// for any that are not set.
+    pub fn rules(&self, default_order: &str, default_wildcards: &str) -> Result<Rules, String> {
+        Rules::new(
+            self.order.as_deref().unwrap_or(default_order),
+            self.wildcards.as_deref().unwrap_or(default_wildcards),
+        )
+    }
+}
+
+// Test data based on examples on the challenge page.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "\
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483
+";
+
+    fn explanations(rules: &Rules) -> Vec<String> {
+        rules
+            .parse_hands(TEST_INPUT)
+            .unwrap()
+            .iter()
+            .map(Hand::explain)
+            .collect()
+    }
+
+    #[test]
+    fn test_standard_rules() {
+        let rules = Rules::standard();
+        let hands = rules.parse_hands(TEST_INPUT).unwrap();
+
+        assert_eq!(
+            explanations(&rules),
+            [
+                "32T3K: One pair",
+                "T55J5: Three of a kind",
+                "KK677: Two pair",
+                "KTJJT: Two pair",
+                "QQQJA: Three of a kind",
+            ]
+        );
+        assert!(hands[2].rank_key() > hands[3].rank_key());
+        assert_eq!(total_winnings(&hands), 6440);
+    }
+
+    #[test]
+    fn test_joker_rules() {
+        let rules = Rules::jokers();
+        let hands = rules.parse_hands(TEST_INPUT).unwrap();
+
+        assert_eq!(
+            explanations(&rules),
+            [
+                "32T3K: One pair",
+                "T55J5: Four of a kind, with J as 5",
+                "KK677: Two pair",
+                "KTJJT: Four of a kind, with J as T",
+                "QQQJA: Four of a kind, with J as Q",
+            ]
+        );
+        assert!(hands[1].rank_key() < hands[4].rank_key());
+        assert!(hands[4].rank_key() < hands[3].rank_key());
+        assert_eq!(total_winnings(&hands), 5905);
+    }
+
+    #[test]
+    fn test_hand_types() {
+        let rules = Rules::jokers();
+        let hand_type = |cards: &str| rules.parse_hand(&format!("{} 1", cards)).unwrap().hand_type;
+
+        let ordered: Vec<HandType> = [
+            "23456", "2345J", "22334", "2J234", "J2323", "22JJ3", "JJJJ2",
+        ]
+        .iter()
+        .map(|cards| hand_type(cards))
+        .collect();
+        assert!(ordered.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(
+            ordered.iter().map(HandType::to_string).collect::<Vec<_>>(),
+            [
+                "High card",
+                "One pair",
+                "Two pair",
+                "Three of a kind",
+                "Full house",
+                "Four of a kind",
+                "Five of a kind",
+            ]
+        );
+        assert_eq!(hand_type("JJJ23"), hand_type("22JJ3"));
+        assert_eq!(hand_type("JJJJJ"), hand_type("JJJJ2"));
+        assert_eq!(
+            rules.parse_hand("JJJJJ 1").unwrap().explain(),
+            "JJJJJ: Five of a kind, with J as A"
+        );
+    }
+
+    #[test]
+    fn test_other_hand_sizes() {
+        let rules = Rules::standard();
+        let hand_type = |cards: &str| rules.parse_hand(&format!("{} 1", cards)).unwrap().hand_type;
+
+        assert_eq!(hand_type("222333").groups(), [3, 3]);
+        assert_eq!(hand_type("222333").to_string(), "Groups of 3 and 3");
+        assert_eq!(hand_type("223344").to_string(), "Groups of 2, 2 and 2");
+        assert_eq!(hand_type("AK").to_string(), "High card");
+        assert_eq!(hand_type("222222").to_string(), "6 of a kind");
+        assert_eq!(hand_type("2222223").to_string(), "6 of a kind");
+        assert!(hand_type("222333") > hand_type("222334"));
+        assert!(hand_type("2222") > hand_type("AAAK"));
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        // Twos are high and wild, and any hand is as strong as it can be with them.
+        let rules = Rules::new("3456789TJQKA2", "2").unwrap();
+        let hand = rules.parse_hand("2K2QK 5").unwrap();
+        assert_eq!(hand.explain(), "2K2QK: Four of a kind, with 2 as K");
+        assert!(rules.parse_hand("AAAAK 1").unwrap().rank_key() < hand.rank_key());
+
+        let rules = Rules::new(STANDARD_ORDER, "JQ").unwrap();
+        assert_eq!(
+            rules.parse_hand("QJ9J8 1").unwrap().explain(),
+            "QJ9J8: Four of a kind, with Q and J as 9"
+        );
+
+        assert!(Rules::new("23452", "").is_err());
+        assert!(Rules::new("2345", "J").is_err());
+        assert!(Rules::standard().parse_hand("32T3X 1").is_err());
+        assert!(Rules::standard().parse_hand("32T3K").is_err());
+        assert!(Rules::standard().parse_hand("32T3K bid").is_err());
+    }
+
+    #[test]
+    fn test_run_options() {
+        let args = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+
+        let options = RunOptions::from_args(&args("--wild Q --explain")).unwrap();
+        assert!(options.explain);
+        assert_eq!(
+            options.rules(STANDARD_ORDER, ""),
+            Rules::new(STANDARD_ORDER, "Q")
+        );
+        assert_eq!(
+            RunOptions::from_args(&[])
+                .unwrap()
+                .rules(JOKER_ORDER, JOKER),
+            Ok(Rules::jokers())
+        );
+        assert!(RunOptions::from_args(&args("--order")).is_err());
+        assert!(RunOptions::from_args(&args("--jokers")).is_err());
+    }
+}
@@ -0,0 +1,434 @@
+//! Paths through a cave system, as described in Advent of Code 2021 Day 12.
+//! https://adventofcode.com/2021/day/12
+//!
+//! The caves are connected by passages that can be followed in either direction. Big caves,
+//! whose names are upper case, can be visited any number of times, but how often a small cave
+//! may be visited is decided by a `RevisitPolicy`. Every path begins at the "start" cave, which
+//! is never re-entered, and finishes at the "end" cave.
+//!
+//! A policy decides from the number of times each small cave has been visited, so the number of
+//! ways to finish a path depends only on the current cave and those visit counts. Counting paths
+//! stores the count for each combination, so the paths themselves are never built unless they
+//! are listed.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+const INPUT_TOKEN_SEPARATOR: char = '-';
+const START_CAVE: &str = "start";
+const END_CAVE: &str = "end";
+
+pub type CaveId = usize;
+pub type Visits = u32;
+
+/// Decides whether a path may enter a small cave.
+pub trait RevisitPolicy {
+    /// Returns whether a path may enter small cave `cave`, where `visits[c]` is the number of
+    /// times the path has already visited small cave `c`.
+    ///
+    /// Every small cave must eventually be refused, or there are infinitely many paths.
+    fn may_enter(&self, cave: CaveId, visits: &[Visits]) -> bool;
+}
+
+/// Any closure with the signature of `RevisitPolicy::may_enter` is a policy.
+impl<F: Fn(CaveId, &[Visits]) -> bool> RevisitPolicy for F {
+    fn may_enter(&self, cave: CaveId, visits: &[Visits]) -> bool {
+        self(cave, visits)
+    }
+}
+
+/// Each small cave may be visited at most once.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct NeverRevisit;
+
+impl RevisitPolicy for NeverRevisit {
+    fn may_enter(&self, cave: CaveId, visits: &[Visits]) -> bool {
+        visits[cave] == 0
+    }
+}
+
+/// A single small cave may be visited twice, and every other small cave at most once.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OneSmallCaveTwice;
+
+impl RevisitPolicy for OneSmallCaveTwice {
+    fn may_enter(&self, cave: CaveId, visits: &[Visits]) -> bool {
+        visits[cave] == 0 || (visits[cave] == 1 && visits.iter().all(|&v| v < 2))
+    }
+}
+
+/// Each small cave may be visited at most the number of times held.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MaxVisits(pub Visits);
+
+impl RevisitPolicy for MaxVisits {
+    fn may_enter(&self, cave: CaveId, visits: &[Visits]) -> bool {
+        visits[cave] < self.0
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CaveSystem {
+    names: Vec<String>,
+    ids: HashMap<String, CaveId>,
+    /// `connections[c]` lists the caves that cave `c` has passages to.
+    connections: Vec<Vec<CaveId>>,
+    start: CaveId,
+    end: CaveId,
+}
+
+impl FromStr for CaveSystem {
+    type Err = String;
+
+    /// Parses one passage per line, e.g., "start-A".
+    ///
+    /// Returns an error if a passage is malformed, there is no "start" or "end" cave, or two big
+    /// caves are connected, as a path could then move between them forever.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut names: Vec<String> = Vec::new();
+        let mut ids = HashMap::new();
+        let mut connections: Vec<Vec<CaveId>> = Vec::new();
+
+        let mut add_cave = |name: &str| -> CaveId {
+            *ids.entry(name.to_string()).or_insert_with(|| {
+                names.push(name.to_string());
+                connections.push(Vec::new());
+                names.len() - 1
+            })
+        };
+
+        let mut passages = Vec::new();
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (from, to) = line
+                .split_once(INPUT_TOKEN_SEPARATOR)
+                .filter(|(from, to)| !from.is_empty() && !to.is_empty())
+                .ok_or_else(|| format!("Malformed passage: {}", line))?;
+            passages.push((add_cave(from), add_cave(to)));
+        }
+
+        for (from, to) in passages {
+            if is_big(&names[from]) && is_big(&names[to]) {
+                return Err(format!(
+                    "Big caves '{}' and '{}' are connected, so there are infinitely many paths",
+                    names[from], names[to]
+                ));
+            }
+            connections[from].push(to);
+            connections[to].push(from);
+        }
+
+        let start = *ids
+            .get(START_CAVE)
+            .ok_or_else(|| format!("There is no '{}' cave", START_CAVE))?;
+        let end = *ids
+            .get(END_CAVE)
+            .ok_or_else(|| format!("There is no '{}' cave", END_CAVE))?;
+
+        Ok(Self {
+            names,
+            ids,
+            connections,
+            start,
+            end,
+        })
+    }
+}
+
+fn is_big(name: &str) -> bool {
+    name.chars().all(|c| c.is_uppercase())
+}
+
+impl CaveSystem {
+    pub fn cave_id(&self, name: &str) -> Option<CaveId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, cave: CaveId) -> &str {
+        &self.names[cave]
+    }
+
+    pub fn is_big(&self, cave: CaveId) -> bool {
+        is_big(&self.names[cave])
+    }
+
+    /// Returns the number of caves, whose `CaveId`s run from zero up to one less than this.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Returns the visit counts at the start of every path, where only "start" has been visited.
+    fn initial_visits(&self) -> Vec<Visits> {
+        let mut visits = vec![0; self.names.len()];
+        visits[self.start] = 1;
+        visits
+    }
+
+    /// Returns whether a path with small cave visit counts `visits` may move into `cave`.
+    fn may_enter(&self, cave: CaveId, visits: &[Visits], policy: &impl RevisitPolicy) -> bool {
+        cave != self.start && (self.is_big(cave) || policy.may_enter(cave, visits))
+    }
+
+    /// Calls `f` with each cave a path with small cave visit counts `visits` may move into from
+    /// `cave`, with `visits` updated for the move.
+    fn for_each_move(
+        &self,
+        cave: CaveId,
+        visits: &mut Vec<Visits>,
+        policy: &impl RevisitPolicy,
+        mut f: impl FnMut(CaveId, &mut Vec<Visits>),
+    ) {
+        for &next in &self.connections[cave] {
+            if !self.may_enter(next, visits, policy) {
+                continue;
+            }
+
+            let small = !self.is_big(next);
+            if small {
+                visits[next] += 1;
+            }
+            f(next, visits);
+            if small {
+                visits[next] -= 1;
+            }
+        }
+    }
+
+    /// Returns the number of paths from "start" to "end" that `policy` allows.
+    pub fn count_paths(&self, policy: &impl RevisitPolicy) -> u64 {
+        let mut memo = HashMap::new();
+        self.count_paths_from(self.start, &mut self.initial_visits(), policy, &mut memo)
+    }
+
+    /// Returns the number of ways to finish a path at `cave` with small cave visit counts
+    /// `visits`, using and updating the counts already found in `memo`.
+    fn count_paths_from(
+        &self,
+        cave: CaveId,
+        visits: &mut Vec<Visits>,
+        policy: &impl RevisitPolicy,
+        memo: &mut HashMap<(CaveId, Vec<Visits>), u64>,
+    ) -> u64 {
+        if cave == self.end {
+            return 1;
+        }
+        if let Some(&count) = memo.get(&(cave, visits.clone())) {
+            return count;
+        }
+
+        let mut count = 0;
+        let key = (cave, visits.clone());
+        self.for_each_move(cave, visits, policy, |next, visits| {
+            count += self.count_paths_from(next, visits, policy, memo);
+        });
+
+        memo.insert(key, count);
+        count
+    }
+
+    /// Returns every path from "start" to "end" that `policy` allows, each as a comma-separated
+    /// list of cave names, e.g., "start,A,b,end", sorted alphabetically.
+    pub fn paths(&self, policy: &impl RevisitPolicy) -> Vec<String> {
+        let mut paths = Vec::new();
+        self.paths_from(
+            &mut vec![self.start],
+            &mut self.initial_visits(),
+            policy,
+            &mut paths,
+        );
+
+        paths.sort_unstable();
+        paths
+    }
+
+    /// Adds every way to finish `path`, whose small cave visit counts are `visits`, to `paths`.
+    fn paths_from(
+        &self,
+        path: &mut Vec<CaveId>,
+        visits: &mut Vec<Visits>,
+        policy: &impl RevisitPolicy,
+        paths: &mut Vec<String>,
+    ) {
+        let cave = *path.last().expect("A path always contains the start cave");
+        if cave == self.end {
+            let names: Vec<&str> = path.iter().map(|&c| self.name(c)).collect();
+            paths.push(names.join(","));
+            return;
+        }
+
+        self.for_each_move(cave, visits, policy, |next, visits| {
+            path.push(next);
+            self.paths_from(path, visits, policy, paths);
+            path.pop();
+        });
+    }
+}
+
+// Test data based on examples on the challenge page.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT_1: &str = "\
+start-A
+start-b
+A-c
+A-b
+b-d
+A-end
+b-end";
+
+    const TEST_INPUT_2: &str = "\
+dc-end
+HN-start
+start-kj
+dc-start
+dc-HN
+LN-dc
+HN-end
+kj-sa
+kj-HN
+kj-dc";
+
+    const TEST_INPUT_3: &str = "\
+fs-end
+he-DX
+fs-he
+start-DX
+pj-DX
+end-zg
+zg-sl
+zg-pj
+pj-he
+RW-he
+fs-DX
+pj-RW
+zg-RW
+start-pj
+he-WI
+zg-he
+pj-fs
+start-RW";
+
+    #[test]
+    fn test_parse() {
+        let caves: CaveSystem = TEST_INPUT_1.parse().unwrap();
+
+        assert_eq!(caves.len(), 6);
+        let a = caves.cave_id("A").unwrap();
+        assert_eq!(caves.name(a), "A");
+        assert!(caves.is_big(a));
+        assert!(!caves.is_big(caves.cave_id("b").unwrap()));
+        assert_eq!(caves.cave_id("B"), None);
+
+        assert!("start-A\nA-".parse::<CaveSystem>().is_err());
+        assert!("start-A\nA end".parse::<CaveSystem>().is_err());
+        assert!("start-A\nA-b".parse::<CaveSystem>().is_err());
+        assert!("A-b\nA-end".parse::<CaveSystem>().is_err());
+        assert!("start-A\nA-BB\nA-end".parse::<CaveSystem>().is_err());
+    }
+
+    #[test]
+    fn test_never_revisit() {
+        let caves: CaveSystem = TEST_INPUT_1.parse().unwrap();
+        assert_eq!(
+            caves.paths(&NeverRevisit),
+            vec![
+                "start,A,b,A,c,A,end",
+                "start,A,b,A,end",
+                "start,A,b,end",
+                "start,A,c,A,b,A,end",
+                "start,A,c,A,b,end",
+                "start,A,c,A,end",
+                "start,A,end",
+                "start,b,A,c,A,end",
+                "start,b,A,end",
+                "start,b,end",
+            ]
+        );
+        assert_eq!(caves.count_paths(&NeverRevisit), 10);
+
+        let caves: CaveSystem = TEST_INPUT_2.parse().unwrap();
+        assert_eq!(
+            caves.paths(&NeverRevisit),
+            vec![
+                "start,HN,dc,HN,end",
+                "start,HN,dc,HN,kj,HN,end",
+                "start,HN,dc,end",
+                "start,HN,dc,kj,HN,end",
+                "start,HN,end",
+                "start,HN,kj,HN,dc,HN,end",
+                "start,HN,kj,HN,dc,end",
+                "start,HN,kj,HN,end",
+                "start,HN,kj,dc,HN,end",
+                "start,HN,kj,dc,end",
+                "start,dc,HN,end",
+                "start,dc,HN,kj,HN,end",
+                "start,dc,end",
+                "start,dc,kj,HN,end",
+                "start,kj,HN,dc,HN,end",
+                "start,kj,HN,dc,end",
+                "start,kj,HN,end",
+                "start,kj,dc,HN,end",
+                "start,kj,dc,end",
+            ]
+        );
+        assert_eq!(caves.count_paths(&NeverRevisit), 19);
+
+        let caves: CaveSystem = TEST_INPUT_3.parse().unwrap();
+        assert_eq!(caves.count_paths(&NeverRevisit), 226);
+    }
+
+    #[test]
+    fn test_one_small_cave_twice() {
+        let caves: CaveSystem = TEST_INPUT_1.parse().unwrap();
+        let paths = caves.paths(&OneSmallCaveTwice);
+        assert_eq!(paths.len(), 36);
+        assert!(paths.contains(&"start,A,b,d,b,A,c,A,end".to_string()));
+        assert!(!paths.contains(&"start,A,b,A,b,A,c,A,c,A,end".to_string()));
+        assert_eq!(caves.count_paths(&OneSmallCaveTwice), 36);
+
+        let caves: CaveSystem = TEST_INPUT_2.parse().unwrap();
+        assert_eq!(caves.count_paths(&OneSmallCaveTwice), 103);
+
+        let caves: CaveSystem = TEST_INPUT_3.parse().unwrap();
+        assert_eq!(caves.count_paths(&OneSmallCaveTwice), 3509);
+        assert_eq!(caves.paths(&OneSmallCaveTwice).len(), 3509);
+    }
+
+    #[test]
+    fn test_max_visits() {
+        let caves: CaveSystem = TEST_INPUT_1.parse().unwrap();
+
+        assert_eq!(
+            caves.count_paths(&MaxVisits(1)),
+            caves.count_paths(&NeverRevisit)
+        );
+        assert_eq!(caves.count_paths(&MaxVisits(0)), 0);
+        assert_eq!(caves.count_paths(&MaxVisits(2)), 54);
+        assert_eq!(caves.paths(&MaxVisits(2)).len(), 54);
+    }
+
+    #[test]
+    fn test_custom_policy() {
+        let caves: CaveSystem = TEST_INPUT_1.parse().unwrap();
+        let c = caves.cave_id("c").unwrap();
+
+        // Never visit cave "c", and other small caves at most once.
+        let policy = |cave: CaveId, visits: &[Visits]| cave != c && visits[cave] == 0;
+        assert_eq!(
+            caves.paths(&policy),
+            vec![
+                "start,A,b,A,end",
+                "start,A,b,end",
+                "start,A,end",
+                "start,b,A,end",
+                "start,b,end",
+            ]
+        );
+        assert_eq!(caves.count_paths(&policy), 5);
+    }
+}
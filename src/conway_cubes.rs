@@ -0,0 +1,593 @@
+//! Conway Cubes in any number of dimensions, as described in Advent of Code 2020 Day 17.
+//! https://adventofcode.com/2020/day/17
+//!
+//! A grid of cubes extends infinitely in `D` dimensions. Each cube is either active or inactive,
+//! and all cubes update simultaneously in each cycle based on how many of their neighbors are
+//! active. The challenge rules are that an active cube stays active if it has 2 or 3 active
+//! neighbors, and an inactive cube becomes active if it has exactly 3, but other birth and
+//! survival counts can be used.
+//!
+//! The starting state is a 2D slice in which every coordinate other than `x` and `y` is 0. As the
+//! rules treat every neighbor equally, the grid remains symmetric about 0 in each of these other
+//! dimensions, and swapping the values of any two of them gives another cube in the same state.
+//! This is exploited by only storing cubes whose extra coordinates are non-negative and in
+//! ascending order, which makes 5D and 6D grids practical.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+const STATE_ACTIVE: char = '#';
+const STATE_INACTIVE: char = '.';
+
+/// Names used for the coordinates of dimensions beyond `x` and `y` when displaying a grid.
+const EXTRA_DIMENSION_NAMES: [&str; 6] = ["z", "w", "v", "u", "t", "s"];
+
+pub type Position<const D: usize> = [i32; D];
+
+/// The number of active neighbors that cause an inactive cube to become active (`birth`), and
+/// that allow an active cube to stay active (`survival`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rules {
+    birth: Vec<usize>,
+    survival: Vec<usize>,
+}
+
+impl Rules {
+    /// Returns new `Rules` with the given counts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `birth` contains 0, as every inactive cube in the infinite grid would then
+    /// become active.
+    pub fn new(birth: &[usize], survival: &[usize]) -> Self {
+        assert!(
+            !birth.contains(&0),
+            "Cubes cannot become active with no active neighbors"
+        );
+
+        Self {
+            birth: birth.to_vec(),
+            survival: survival.to_vec(),
+        }
+    }
+
+    fn next_state(&self, active: bool, active_neighbors: usize) -> bool {
+        if active {
+            self.survival.contains(&active_neighbors)
+        } else {
+            self.birth.contains(&active_neighbors)
+        }
+    }
+}
+
+/// The challenge rules, which are written as "B3/S23" in the notation parsed by `from_str`.
+impl Default for Rules {
+    fn default() -> Self {
+        Self::new(&[3], &[2, 3])
+    }
+}
+
+/// Parses rules written in the common "B3/S23" notation, where the digits following 'B' are the
+/// birth counts and those following 'S' are the survival counts. Counts of 10 or more can be
+/// given by separating them with commas, e.g., "B3,12/S2,3".
+impl FromStr for Rules {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        fn parse_counts(counts: &str) -> Result<Vec<usize>, String> {
+            let parse_one = |c: &str| {
+                c.parse()
+                    .map_err(|_| format!("Invalid neighbor count '{}'", c))
+            };
+
+            if counts.contains(',') {
+                counts.split(',').map(parse_one).collect()
+            } else {
+                counts.chars().map(|c| parse_one(&c.to_string())).collect()
+            }
+        }
+
+        let (birth, survival) = s
+            .trim()
+            .split_once('/')
+            .and_then(|(b, s)| Some((b.strip_prefix('B')?, s.strip_prefix('S')?)))
+            .ok_or_else(|| format!("Rules '{}' are not in the form 'B3/S23'", s))?;
+
+        let birth = parse_counts(birth)?;
+        if birth.contains(&0) {
+            return Err("Cubes cannot become active with no active neighbors".to_string());
+        }
+
+        Ok(Self {
+            birth,
+            survival: parse_counts(survival)?,
+        })
+    }
+}
+
+/// A grid of cubes in `D` dimensions. Only the active cubes whose extra coordinates are in the
+/// canonical form described in the module documentation are stored.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CubeGrid<const D: usize> {
+    active_cubes: HashSet<Position<D>>,
+    rules: Rules,
+    neighbor_offsets: Vec<Position<D>>,
+}
+
+impl<const D: usize> CubeGrid<D> {
+    /// Creates a new `CubeGrid` from a string representing a 2D grid of cube states, where '#'
+    /// is an active cube and '.' is inactive. The top-left character is at `x` = 0 and `y` = 0,
+    /// and all other coordinates are 0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `D` is less than 2.
+    pub fn new(slice: &str, rules: Rules) -> Result<Self, String> {
+        assert!(D >= 2, "A CubeGrid must have at least 2 dimensions");
+
+        let mut active_cubes = HashSet::new();
+
+        for (y, line) in slice.lines().filter(|line| !line.is_empty()).enumerate() {
+            for (x, c) in line.trim().chars().enumerate() {
+                match c {
+                    STATE_ACTIVE => {
+                        let mut p = [0; D];
+                        p[0] = x as i32;
+                        p[1] = y as i32;
+                        active_cubes.insert(p);
+                    }
+                    STATE_INACTIVE => {}
+                    _ => return Err(format!("Unrecognized cube state '{}'", c)),
+                }
+            }
+        }
+
+        Ok(Self {
+            active_cubes,
+            rules,
+            neighbor_offsets: neighbor_offsets(),
+        })
+    }
+
+    /// Returns the number of active cubes in the entire grid.
+    pub fn active_count(&self) -> usize {
+        self.active_cubes.iter().map(orbit_size).sum()
+    }
+
+    /// Returns whether the cube at `p` is active.
+    pub fn is_active(&self, p: &Position<D>) -> bool {
+        self.active_cubes.contains(&canonical(*p))
+    }
+
+    /// Returns every active cube in the entire grid, in no particular order.
+    pub fn active_cubes(&self) -> HashSet<Position<D>> {
+        self.active_cubes.iter().flat_map(orbit).collect()
+    }
+
+    /// Updates the state of every cube once, based on the rules.
+    //
+    // Implementation note: each cube in the grid is in the same state as every other cube in its
+    // "orbit", i.e., all those with the same canonical form. By symmetry, the number of active
+    // neighbors of canonical cube `c` that lie in the orbit of canonical cube `a` is:
+    //     |orbit(a)| * (neighbors of `a` lying in the orbit of `c`) / |orbit(c)|
+    // so the neighbors of each stored active cube are visited once, adding the size of its orbit
+    // to the tally of each neighbor's canonical form, and each tally is divided at the end.
+    pub fn cycle_once(&mut self) {
+        let mut weighted_counts: HashMap<Position<D>, usize> = HashMap::new();
+
+        for a in &self.active_cubes {
+            let weight = orbit_size(a);
+
+            for offset in &self.neighbor_offsets {
+                let mut n = *a;
+                for (coordinate, delta) in n.iter_mut().zip(offset) {
+                    *coordinate += delta;
+                }
+
+                *weighted_counts.entry(canonical(n)).or_default() += weight;
+            }
+        }
+
+        let mut new_state: HashSet<Position<D>> = weighted_counts
+            .iter()
+            .filter(|(c, total)| {
+                self.rules
+                    .next_state(self.active_cubes.contains(*c), *total / orbit_size(c))
+            })
+            .map(|(c, _)| *c)
+            .collect();
+
+        // Active cubes with no active neighbors are not visited above.
+        if self.rules.survival.contains(&0) {
+            new_state.extend(
+                self.active_cubes
+                    .iter()
+                    .filter(|c| !weighted_counts.contains_key(*c)),
+            );
+        }
+
+        self.active_cubes = new_state;
+    }
+
+    /// Updates the state of every cube `rounds` times.
+    pub fn cycle(&mut self, rounds: usize) {
+        for _ in 0..rounds {
+            self.cycle_once();
+        }
+    }
+}
+
+/// Displays each 2D slice of the grid that falls within the box bounding all active cubes, in
+/// the same format as the examples in the challenge.
+impl<const D: usize> fmt::Display for CubeGrid<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cubes = self.active_cubes();
+        if cubes.is_empty() {
+            return Ok(());
+        }
+
+        let mut min = [i32::MAX; D];
+        let mut max = [i32::MIN; D];
+        for p in &cubes {
+            for d in 0..D {
+                min[d] = min[d].min(p[d]);
+                max[d] = max[d].max(p[d]);
+            }
+        }
+
+        // Iterate over the extra coordinates like an odometer, with the first extra dimension
+        // changing fastest, as in the challenge examples.
+        let mut slice = min;
+        loop {
+            if D > 2 {
+                let header: Vec<String> = (2..D)
+                    .map(|d| format!("{}={}", dimension_name(d), slice[d]))
+                    .collect();
+                writeln!(f, "{}", header.join(", "))?;
+            }
+
+            for y in min[1]..=max[1] {
+                let row: String = (min[0]..=max[0])
+                    .map(|x| {
+                        slice[0] = x;
+                        slice[1] = y;
+                        if cubes.contains(&slice) {
+                            STATE_ACTIVE
+                        } else {
+                            STATE_INACTIVE
+                        }
+                    })
+                    .collect();
+                writeln!(f, "{}", row)?;
+            }
+
+            let Some(d) = (2..D).find(|&d| slice[d] < max[d]) else {
+                return Ok(());
+            };
+            slice[d] += 1;
+            slice[2..d].copy_from_slice(&min[2..d]);
+            writeln!(f)?;
+        }
+    }
+}
+
+fn dimension_name(d: usize) -> String {
+    EXTRA_DIMENSION_NAMES
+        .get(d - 2)
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| format!("d{}", d))
+}
+
+/// Returns the offsets from a cube to all `3^D - 1` of its neighbors.
+fn neighbor_offsets<const D: usize>() -> Vec<Position<D>> {
+    let mut offsets = vec![[0; D]];
+
+    for d in 0..D {
+        offsets = offsets
+            .iter()
+            .flat_map(|o| {
+                [-1, 0, 1].map(|delta| {
+                    let mut new_offset = *o;
+                    new_offset[d] = delta;
+                    new_offset
+                })
+            })
+            .collect();
+    }
+
+    offsets.retain(|o| o.iter().any(|&delta| delta != 0));
+    offsets
+}
+
+/// Returns the canonical form of `p`, in which the extra coordinates (all but `x` and `y`) are
+/// replaced with their absolute values and sorted into ascending order.
+fn canonical<const D: usize>(mut p: Position<D>) -> Position<D> {
+    if D > 2 {
+        for coordinate in &mut p[2..] {
+            *coordinate = coordinate.abs();
+        }
+        p[2..].sort_unstable();
+    }
+    p
+}
+
+/// Returns the number of cubes whose canonical form is `p`, which must be canonical. This is the
+/// number of distinct orderings of the extra coordinates, multiplied by 2 for every non-zero extra
+/// coordinate as its sign can be flipped.
+fn orbit_size<const D: usize>(p: &Position<D>) -> usize {
+    if D <= 2 {
+        return 1;
+    }
+
+    let extra = &p[2..];
+    let mut orderings = factorial(extra.len());
+    let mut run_length = 1;
+    for i in 1..=extra.len() {
+        if i < extra.len() && extra[i] == extra[i - 1] {
+            run_length += 1;
+        } else {
+            orderings /= factorial(run_length);
+            run_length = 1;
+        }
+    }
+
+    orderings << extra.iter().filter(|&&c| c != 0).count()
+}
+
+fn factorial(n: usize) -> usize {
+    (1..=n).product()
+}
+
+/// Returns every cube whose canonical form is `p`.
+fn orbit<const D: usize>(p: &Position<D>) -> HashSet<Position<D>> {
+    let mut result = HashSet::from([*p]);
+
+    for d in 2..D {
+        let mut next = HashSet::new();
+        for q in &result {
+            // Move each extra coordinate into position `d` in turn, with either sign.
+            for e in d..D {
+                let mut swapped = *q;
+                swapped.swap(d, e);
+                next.insert(swapped);
+                swapped[d] = -swapped[d];
+                next.insert(swapped);
+            }
+        }
+        result = next;
+    }
+
+    result
+}
+
+// Test data based on examples on the challenge page.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "\
+.#.
+..#
+###";
+
+    const TEST_3D_ROUND_1: &str = "\
+z=-1
+#..
+..#
+.#.
+
+z=0
+#.#
+.##
+.#.
+
+z=1
+#..
+..#
+.#.
+";
+
+    const TEST_3D_ROUND_2: &str = "\
+z=-2
+.....
+.....
+..#..
+.....
+.....
+
+z=-1
+..#..
+.#..#
+....#
+.#...
+.....
+
+z=0
+##...
+##...
+#....
+....#
+.###.
+
+z=1
+..#..
+.#..#
+....#
+.#...
+.....
+
+z=2
+.....
+.....
+..#..
+.....
+.....
+";
+
+    const TEST_4D_ROUND_1: &str = "\
+z=-1, w=-1
+#..
+..#
+.#.
+
+z=0, w=-1
+#..
+..#
+.#.
+
+z=1, w=-1
+#..
+..#
+.#.
+
+z=-1, w=0
+#..
+..#
+.#.
+
+z=0, w=0
+#.#
+.##
+.#.
+
+z=1, w=0
+#..
+..#
+.#.
+
+z=-1, w=1
+#..
+..#
+.#.
+
+z=0, w=1
+#..
+..#
+.#.
+
+z=1, w=1
+#..
+..#
+.#.
+";
+
+    #[test]
+    fn test_new() {
+        let grid = CubeGrid::<3>::new(TEST_INPUT, Rules::default()).unwrap();
+
+        assert_eq!(grid.active_count(), 5);
+        assert_eq!(
+            grid.active_cubes(),
+            HashSet::from([[1, 0, 0], [2, 1, 0], [0, 2, 0], [1, 2, 0], [2, 2, 0]])
+        );
+        assert_eq!(grid.to_string(), "z=0\n.#.\n..#\n###\n");
+        assert!(CubeGrid::<3>::new("..x", Rules::default()).is_err());
+    }
+
+    #[test]
+    fn test_2d_display() {
+        let grid = CubeGrid::<2>::new(TEST_INPUT, Rules::default()).unwrap();
+        assert_eq!(grid.to_string(), ".#.\n..#\n###\n");
+    }
+
+    #[test]
+    fn test_3d_cycles() {
+        let mut grid = CubeGrid::<3>::new(TEST_INPUT, Rules::default()).unwrap();
+
+        grid.cycle_once();
+        assert_eq!(grid.to_string(), TEST_3D_ROUND_1);
+        assert_eq!(grid.active_count(), 11);
+        assert!(grid.is_active(&[0, 1, -1]));
+        assert!(!grid.is_active(&[1, 1, -1]));
+
+        grid.cycle_once();
+        assert_eq!(grid.to_string(), TEST_3D_ROUND_2);
+        assert_eq!(grid.active_count(), 21);
+
+        grid.cycle(4);
+        assert_eq!(grid.active_count(), 112);
+    }
+
+    #[test]
+    fn test_4d_cycles() {
+        let mut grid = CubeGrid::<4>::new(TEST_INPUT, Rules::default()).unwrap();
+
+        grid.cycle_once();
+        assert_eq!(grid.to_string(), TEST_4D_ROUND_1);
+        assert_eq!(grid.active_count(), 29);
+
+        grid.cycle_once();
+        assert_eq!(grid.active_count(), 60);
+
+        grid.cycle(4);
+        assert_eq!(grid.active_count(), 848);
+    }
+
+    #[test]
+    fn test_5d_and_6d_cycles() {
+        let mut grid = CubeGrid::<5>::new(TEST_INPUT, Rules::default()).unwrap();
+        grid.cycle(6);
+        assert_eq!(grid.active_count(), 5760);
+
+        let mut grid = CubeGrid::<6>::new(TEST_INPUT, Rules::default()).unwrap();
+        grid.cycle(6);
+        assert_eq!(grid.active_count(), 35936);
+    }
+
+    #[test]
+    fn test_active_count_matches_active_cubes() {
+        let mut grid = CubeGrid::<5>::new(TEST_INPUT, Rules::default()).unwrap();
+        grid.cycle(3);
+        assert_eq!(grid.active_count(), grid.active_cubes().len());
+    }
+
+    #[test]
+    fn test_orbit_size() {
+        assert_eq!(orbit_size(&[0, 0]), 1);
+        assert_eq!(orbit_size(&[0, 0, 0]), 1);
+        assert_eq!(orbit_size(&[0, 0, 2]), 2);
+        assert_eq!(orbit_size(&[0, 0, 0, 1]), 4);
+        assert_eq!(orbit_size(&[0, 0, 1, 1]), 4);
+        assert_eq!(orbit_size(&[0, 0, 1, 2]), 8);
+        assert_eq!(orbit_size(&[0, 0, 0, 1, 1]), 12);
+
+        for p in [[5, 6, 0, 1, 1], [0, 0, 1, 2, 3], [0, 0, 0, 0, 4]] {
+            assert_eq!(orbit_size(&p), orbit(&p).len());
+            assert!(orbit(&p).iter().all(|q| canonical(*q) == p));
+        }
+    }
+
+    #[test]
+    fn test_neighbor_offsets() {
+        assert_eq!(neighbor_offsets::<2>().len(), 8);
+        assert_eq!(neighbor_offsets::<3>().len(), 26);
+        assert_eq!(neighbor_offsets::<4>().len(), 80);
+    }
+
+    #[test]
+    fn test_rules() {
+        assert_eq!("B3/S23".parse::<Rules>(), Ok(Rules::default()));
+        assert_eq!(
+            "B3,12/S2,3".parse::<Rules>(),
+            Ok(Rules::new(&[3, 12], &[2, 3]))
+        );
+        assert!("B03/S23".parse::<Rules>().is_err());
+        assert!("3/23".parse::<Rules>().is_err());
+        assert!("Bx/S23".parse::<Rules>().is_err());
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        // With "B3/S" no cube survives, so a 2D blinker vanishes after one cycle apart from the
+        // cubes that are born.
+        let mut grid = CubeGrid::<2>::new("...\n###\n...", "B3/S".parse().unwrap()).unwrap();
+        grid.cycle_once();
+        assert_eq!(grid.to_string(), "#\n.\n#\n");
+
+        // With survival on 0 neighbors, an isolated cube survives.
+        let mut grid = CubeGrid::<3>::new("#", "B3/S0".parse().unwrap()).unwrap();
+        grid.cycle(2);
+        assert_eq!(grid.active_count(), 1);
+    }
+}
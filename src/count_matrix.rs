@@ -0,0 +1,258 @@
+//! Square matrices of non-negative counts, for challenges where a population changes in the same
+//! linear way every step.
+//!
+//! If a vector holds how many there are of each kind of thing, and entry `(i, j)` of the matrix
+//! is how many things of kind `i` each thing of kind `j` becomes after one step, then the counts
+//! after `n` steps are the matrix raised to the power `n` applied to the starting counts. Raising
+//! the matrix to a power by repeated squaring needs a number of multiplications proportional to
+//! the logarithm of `n`, so even huge step counts are quick.
+//!
+//! Counts like these grow exponentially, so entries are held as `BigUint`s. Exact counts after
+//! billions of steps would have billions of digits, so `CountMode::Modulo` keeps every entry
+//! reduced modulo some number instead.
+
+use crate::big_uint::BigUint;
+
+/// Whether counts are exact, or reduced modulo a number.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CountMode {
+    Exact,
+    Modulo(u64),
+}
+
+impl CountMode {
+    /// Returns `value` reduced as this mode requires.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the modulus is zero.
+    pub fn reduce(self, value: BigUint) -> BigUint {
+        match self {
+            Self::Exact => value,
+            Self::Modulo(modulus) => value.rem_u64(modulus).into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CountMatrix {
+    size: usize,
+    /// The entries in row-major order.
+    entries: Vec<BigUint>,
+}
+
+impl CountMatrix {
+    /// Returns a `size` by `size` matrix with every entry zero.
+    pub fn zero(size: usize) -> Self {
+        Self {
+            size,
+            entries: vec![BigUint::zero(); size * size],
+        }
+    }
+
+    /// Returns a `size` by `size` matrix with ones on the diagonal and zeros elsewhere.
+    pub fn identity(size: usize) -> Self {
+        let mut matrix = Self::zero(size);
+        for i in 0..size {
+            matrix.add(i, i, 1);
+        }
+        matrix
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> &BigUint {
+        &self.entries[row * self.size + column]
+    }
+
+    /// Adds `count` to the entry at `row` and `column`.
+    pub fn add(&mut self, row: usize, column: usize, count: u64) {
+        let entry = &mut self.entries[row * self.size + column];
+        *entry = &*entry + &BigUint::from(count);
+    }
+
+    /// Returns the product of `self` and `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrices are different sizes, or if `mode` has a modulus of zero.
+    pub fn multiply(&self, other: &Self, mode: CountMode) -> Self {
+        assert_eq!(
+            self.size, other.size,
+            "Cannot multiply matrices of different sizes"
+        );
+
+        let size = self.size;
+        let mut product = Self::zero(size);
+
+        match mode {
+            // Reduced entries fit in a `u64`, so their products are summed in a `u128` rather
+            // than allocating a `BigUint` for each.
+            CountMode::Modulo(modulus) => {
+                let modulus = u128::from(modulus);
+                let self_entries = self.reduced_entries(mode);
+                let other_entries = other.reduced_entries(mode);
+
+                for row in 0..size {
+                    for column in 0..size {
+                        let mut sum = 0;
+                        for k in 0..size {
+                            let a = self_entries[row * size + k];
+                            if a != 0 {
+                                sum = (sum + a * other_entries[k * size + column]) % modulus;
+                            }
+                        }
+                        product.entries[row * size + column] = BigUint::from(sum as u64);
+                    }
+                }
+            }
+            CountMode::Exact => {
+                for row in 0..size {
+                    for k in 0..size {
+                        let a = self.get(row, k);
+                        if a.is_zero() {
+                            continue;
+                        }
+                        for column in 0..size {
+                            let b = other.get(k, column);
+                            if !b.is_zero() {
+                                let entry = &mut product.entries[row * size + column];
+                                *entry = &*entry + &(a * b);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        product
+    }
+
+    /// Returns `self` raised to the power `exponent`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mode` has a modulus of zero.
+    pub fn power(&self, mut exponent: u64, mode: CountMode) -> Self {
+        let mut result = Self::identity(self.size);
+        let mut square = self.clone();
+
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.multiply(&square, mode);
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                square = square.multiply(&square, mode);
+            }
+        }
+
+        if let CountMode::Modulo(_) = mode {
+            // Reduces the identity matrix too, in case the modulus is 1.
+            result.entries = result
+                .entries
+                .into_iter()
+                .map(|entry| mode.reduce(entry))
+                .collect();
+        }
+        result
+    }
+
+    /// Returns the result of multiplying the column vector `counts` by `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `counts` is not the same length as the matrix size, or if `mode` has a modulus of
+    /// zero.
+    pub fn apply(&self, counts: &[BigUint], mode: CountMode) -> Vec<BigUint> {
+        assert_eq!(
+            counts.len(),
+            self.size,
+            "The number of counts must match the matrix size"
+        );
+
+        (0..self.size)
+            .map(|row| {
+                let sum = counts
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, count)| !count.is_zero())
+                    .fold(BigUint::zero(), |sum, (column, count)| {
+                        sum + self.get(row, column) * count
+                    });
+                mode.reduce(sum)
+            })
+            .collect()
+    }
+
+    /// Returns the entries reduced by the `CountMode::Modulo` passed, as `u128`s.
+    fn reduced_entries(&self, mode: CountMode) -> Vec<u128> {
+        self.entries
+            .iter()
+            .map(|entry| {
+                let reduced = mode.reduce(entry.clone());
+                u128::from(reduced.to_u64().expect("Reduced entries fit in 64 bits"))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a 2 by 2 matrix that generates Fibonacci numbers.
+    fn fibonacci() -> CountMatrix {
+        let mut matrix = CountMatrix::zero(2);
+        matrix.add(0, 0, 1);
+        matrix.add(0, 1, 1);
+        matrix.add(1, 0, 1);
+        matrix
+    }
+
+    #[test]
+    fn test_power() {
+        let matrix = fibonacci();
+
+        assert_eq!(matrix.power(0, CountMode::Exact), CountMatrix::identity(2));
+        assert_eq!(matrix.power(1, CountMode::Exact), matrix);
+        assert_eq!(
+            matrix.power(10, CountMode::Exact).get(0, 1),
+            &BigUint::from(55)
+        );
+        assert_eq!(
+            matrix.power(200, CountMode::Exact).get(0, 1).to_string(),
+            "280571172992510140037611932413038677189525"
+        );
+    }
+
+    #[test]
+    fn test_power_modulo() {
+        let matrix = fibonacci();
+        let mode = CountMode::Modulo(1_000_000_007);
+
+        assert_eq!(matrix.power(200, mode).get(0, 1).to_string(), "349361645");
+        assert_eq!(
+            matrix.power(1_000_000_000_000, mode).get(0, 1).to_string(),
+            "730695249"
+        );
+        assert_eq!(matrix.power(0, CountMode::Modulo(1)), CountMatrix::zero(2));
+    }
+
+    #[test]
+    fn test_apply() {
+        let matrix = fibonacci().power(10, CountMode::Exact);
+        let counts = [BigUint::from(2), BigUint::from(1)];
+
+        assert_eq!(
+            matrix.apply(&counts, CountMode::Exact),
+            vec![BigUint::from(233), BigUint::from(144)]
+        );
+        assert_eq!(
+            matrix.apply(&counts, CountMode::Modulo(100)),
+            vec![BigUint::from(33), BigUint::from(44)]
+        );
+    }
+}
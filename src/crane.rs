@@ -0,0 +1,510 @@
+//! Stacks of crates rearranged by a crane, as described in Advent of Code 2022 Day 5.
+//! https://adventofcode.com/2022/day/5
+//!
+//! Each move lifts a number of crates from the top of one stack and puts them on top of another.
+//! How the crates end up ordered depends on the model of crane. The CrateMover 9000 in part 1 of
+//! the challenge moves one crate at a time, reversing their order, and the CrateMover 9001 in part
+//! 2 moves them all at once, keeping their order. A `CraneModel` describes any such behaviour.
+
+use std::fmt;
+use std::str::FromStr;
+
+const INPUT_TOKEN_SECTION_SEPARATOR: &str = "\n\n";
+/// The width of each stack in the drawing of the stacks, excluding the space between stacks.
+const DRAWING_STACK_WIDTH: usize = 3;
+
+pub type Crate = char;
+pub type Stack = Vec<Crate>;
+
+/// How a crane rearranges crates when moving them between stacks.
+pub trait CraneModel {
+    /// Rearranges `lifted`, which holds the crates lifted from a stack from lowest to highest,
+    /// into the order they end up on the destination stack, from lowest to highest.
+    fn arrange(&self, lifted: &mut [Crate]);
+}
+
+impl<F: Fn(&mut [Crate])> CraneModel for F {
+    fn arrange(&self, lifted: &mut [Crate]) {
+        self(lifted)
+    }
+}
+
+/// Moves crates one at a time, so they end up in the reverse of their order.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CrateMover9000;
+
+impl CraneModel for CrateMover9000 {
+    fn arrange(&self, lifted: &mut [Crate]) {
+        lifted.reverse();
+    }
+}
+
+/// Moves all the crates at once, so they keep their order.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CrateMover9001;
+
+impl CraneModel for CrateMover9001 {
+    fn arrange(&self, _lifted: &mut [Crate]) {}
+}
+
+/// Moves crates in groups of up to `capacity` at a time, taking them from the top of the stack.
+/// Each group keeps its order, but the groups end up in the reverse of their order. A capacity of
+/// 1 behaves as `CrateMover9000`, and a capacity at least the size of every move behaves as
+/// `CrateMover9001`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BatchCrane {
+    pub capacity: usize,
+}
+
+impl CraneModel for BatchCrane {
+    fn arrange(&self, lifted: &mut [Crate]) {
+        let arranged: Vec<Crate> = lifted
+            .rchunks(self.capacity.max(1))
+            .flatten()
+            .copied()
+            .collect();
+        lifted.copy_from_slice(&arranged);
+    }
+}
+
+/// A request to move `count` crates from stack `from` to stack `to`. Stacks are numbered from 1,
+/// as in the challenge.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Move {
+    pub count: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl FromStr for Move {
+    type Err = String;
+
+    /// Parses a move of the form "move 1 from 2 to 1".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens: Vec<&str> = s.split_whitespace().collect();
+
+        if let ["move", count, "from", from, "to", to] = tokens.as_slice() {
+            if let (Ok(count), Ok(from), Ok(to)) = (count.parse(), from.parse(), to.parse()) {
+                return Ok(Self { count, from, to });
+            }
+        }
+        Err(format!("Malformed move '{}'", s))
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from, self.to)
+    }
+}
+
+/// Holds stacks of crates. Each stack begins at the crate at ground level.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Stacks {
+    stacks: Vec<Stack>,
+}
+
+impl FromStr for Stacks {
+    type Err = String;
+
+    /// Parses a drawing of the stacks, ending with a row numbering them. For example:
+    ///
+    /// ```text
+    ///     [D]
+    /// [N] [C]
+    /// [Z] [M] [P]
+    ///  1   2   3
+    /// ```
+    ///
+    /// Trailing spaces on each row may be omitted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines: Vec<&str> = s.lines().filter(|line| !line.trim().is_empty()).collect();
+
+        let numbers = lines.pop().ok_or("The drawing of the stacks is empty")?;
+        let count = numbers.split_whitespace().count();
+        for (i, number) in numbers.split_whitespace().enumerate() {
+            if number.parse() != Ok(i + 1) {
+                return Err(format!("Unexpected stack number '{}'", number));
+            }
+        }
+
+        let mut stacks = vec![Stack::new(); count];
+        for (level, line) in lines.iter().rev().enumerate() {
+            let chars: Vec<char> = line.chars().collect();
+
+            for (i, cell) in chars.chunks(DRAWING_STACK_WIDTH + 1).enumerate() {
+                match cell {
+                    [' ', ' ', ' ', ..] | [' ', ' '] | [' '] => {}
+                    ['[', c, ']', ..] if i < count => {
+                        if stacks[i].len() != level {
+                            return Err(format!("Crate '{}' is not resting on another", c));
+                        }
+                        stacks[i].push(*c);
+                    }
+                    _ => {
+                        return Err(format!(
+                            "Unrecognized stack drawing '{}'",
+                            cell.iter().collect::<String>()
+                        ))
+                    }
+                }
+            }
+        }
+
+        Ok(Self { stacks })
+    }
+}
+
+impl Stacks {
+    /// Returns the stacks, where the stack numbered 1 in the challenge has index 0.
+    pub fn stacks(&self) -> &[Stack] {
+        &self.stacks
+    }
+
+    /// Moves crates between stacks as requested by `m`, arranged by `crane`.
+    ///
+    /// Returns an error, leaving the stacks unchanged, if either stack does not exist or the
+    /// stack the crates are moved from does not hold enough of them.
+    pub fn apply(&mut self, m: &Move, crane: &impl CraneModel) -> Result<(), String> {
+        for stack in [m.from, m.to] {
+            if stack == 0 || stack > self.stacks.len() {
+                return Err(format!("'{}' refers to a missing stack {}", m, stack));
+            }
+        }
+
+        let from = &mut self.stacks[m.from - 1];
+        if from.len() < m.count {
+            return Err(format!(
+                "'{}' needs {} crates but stack {} only holds {}",
+                m,
+                m.count,
+                m.from,
+                from.len()
+            ));
+        }
+
+        let mut lifted = from.split_off(from.len() - m.count);
+        crane.arrange(&mut lifted);
+        self.stacks[m.to - 1].append(&mut lifted);
+        Ok(())
+    }
+
+    /// Applies every `Move` in `moves` in turn. See `apply`.
+    pub fn apply_all(&mut self, moves: &[Move], crane: &impl CraneModel) -> Result<(), String> {
+        self.apply_all_traced(moves, crane, |_, _| {})
+    }
+
+    /// As `apply_all`, but calls `trace` with each move and the stacks after it is made.
+    pub fn apply_all_traced(
+        &mut self,
+        moves: &[Move],
+        crane: &impl CraneModel,
+        mut trace: impl FnMut(&Move, &Self),
+    ) -> Result<(), String> {
+        for m in moves {
+            self.apply(m, crane)?;
+            trace(m, self);
+        }
+        Ok(())
+    }
+
+    /// Returns the crate on top of each stack, as required by the challenge. An empty stack is
+    /// shown as a space.
+    pub fn top_crates(&self) -> String {
+        self.stacks
+            .iter()
+            .map(|stack| *stack.last().unwrap_or(&' '))
+            .collect()
+    }
+}
+
+/// Displays the stacks as the drawing they are parsed from, including trailing spaces.
+impl fmt::Display for Stacks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let height = self.stacks.iter().map(Vec::len).max().unwrap_or(0);
+
+        for level in (0..height).rev() {
+            let row: Vec<String> = self
+                .stacks
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(c) => format!("[{}]", c),
+                    None => " ".repeat(DRAWING_STACK_WIDTH),
+                })
+                .collect();
+            writeln!(f, "{}", row.join(" "))?;
+        }
+
+        let numbers: Vec<String> = (1..=self.stacks.len())
+            .map(|n| format!("{:^width$}", n, width = DRAWING_STACK_WIDTH))
+            .collect();
+        writeln!(f, "{}", numbers.join(" "))
+    }
+}
+
+/// Returns the initial state of the stacks in `input` and the moves that follow it, separated by
+/// a blank line.
+pub fn parse_input(input: &str) -> Result<(Stacks, Vec<Move>), String> {
+    let (drawing, moves) = input
+        .split_once(INPUT_TOKEN_SECTION_SEPARATOR)
+        .ok_or("The input must have a drawing of the stacks, then a blank line, then moves")?;
+
+    let moves = moves
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::parse)
+        .collect::<Result<_, _>>()?;
+
+    Ok((drawing.parse()?, moves))
+}
+
+/// Options for rearranging the crates, given on the command line.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RunOptions {
+    /// The capacity of a `BatchCrane` to use instead of the challenge's crane, if any.
+    pub capacity: Option<usize>,
+    /// Whether to print the stacks after every move.
+    pub trace: bool,
+}
+
+impl RunOptions {
+    /// Parses the command line arguments `args`, which may contain "--capacity <crates>" and
+    /// "--trace".
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut options = Self {
+            capacity: None,
+            trace: false,
+        };
+
+        let mut args = args.iter();
+        while let Some(option) = args.next() {
+            match option.as_str() {
+                "--trace" => options.trace = true,
+                "--capacity" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("Missing value for option '{}'", option))?;
+                    options.capacity = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|&capacity| capacity > 0)
+                            .ok_or_else(|| format!("Invalid capacity '{}'", value))?,
+                    );
+                }
+                _ => return Err(format!("Unknown option '{}'", option)),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+// Test data based on examples on the challenge page.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = concat!(
+        "    [D]    \n",
+        "[N] [C]    \n",
+        "[Z] [M] [P]\n",
+        " 1   2   3 \n",
+        "\n",
+        "move 1 from 2 to 1\n",
+        "move 3 from 1 to 3\n",
+        "move 2 from 2 to 1\n",
+        "move 1 from 1 to 2\n",
+    );
+
+    const EXPECTED_TRACE_9000: [&str; 4] = [
+        concat!(
+            "[D]        \n",
+            "[N] [C]    \n",
+            "[Z] [M] [P]\n",
+            " 1   2   3 \n",
+        ),
+        concat!(
+            "        [Z]\n",
+            "        [N]\n",
+            "    [C] [D]\n",
+            "    [M] [P]\n",
+            " 1   2   3 \n",
+        ),
+        concat!(
+            "        [Z]\n",
+            "        [N]\n",
+            "[M]     [D]\n",
+            "[C]     [P]\n",
+            " 1   2   3 \n",
+        ),
+        concat!(
+            "        [Z]\n",
+            "        [N]\n",
+            "        [D]\n",
+            "[C] [M] [P]\n",
+            " 1   2   3 \n",
+        ),
+    ];
+
+    fn stacks(stacks: &[&str]) -> Vec<Stack> {
+        stacks.iter().map(|s| s.chars().collect()).collect()
+    }
+
+    #[test]
+    fn test_parse_input() {
+        let (stacks_parsed, moves) = parse_input(TEST_INPUT).unwrap();
+
+        assert_eq!(stacks_parsed.stacks(), stacks(&["ZN", "MCD", "P"]));
+        assert_eq!(
+            stacks_parsed.to_string(),
+            TEST_INPUT.split("\n\n").next().unwrap().to_string() + "\n"
+        );
+        assert_eq!(moves.len(), 4);
+        assert_eq!(
+            moves[1],
+            Move {
+                count: 3,
+                from: 1,
+                to: 3
+            }
+        );
+        assert_eq!(moves[1].to_string(), "move 3 from 1 to 3");
+
+        // Trailing spaces may be omitted.
+        assert_eq!(
+            "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3".parse(),
+            Ok(stacks_parsed)
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!("[N] [C]    \n 1   2   3".parse::<Stacks>().is_ok());
+        assert!("[N] [C]    \n 1   3   2".parse::<Stacks>().is_err());
+        assert!("[N] [C] [P]\n 1   2".parse::<Stacks>().is_err());
+        assert!("[N]        \n    [C]    \n 1   2"
+            .parse::<Stacks>()
+            .is_err());
+        assert!("[N] (C)\n 1   2".parse::<Stacks>().is_err());
+        assert!("move 1 from 2".parse::<Move>().is_err());
+        assert!("move one from 2 to 1".parse::<Move>().is_err());
+        assert!(parse_input("move 1 from 2 to 1").is_err());
+    }
+
+    #[test]
+    fn test_crate_mover_9000() {
+        let (mut stacks, moves) = parse_input(TEST_INPUT).unwrap();
+        let mut trace = Vec::new();
+
+        stacks
+            .apply_all_traced(&moves, &CrateMover9000, |_, stacks| {
+                trace.push(stacks.to_string())
+            })
+            .unwrap();
+        assert_eq!(trace, EXPECTED_TRACE_9000);
+        assert_eq!(stacks.top_crates(), "CMZ");
+    }
+
+    #[test]
+    fn test_crate_mover_9001() {
+        let (mut stacks_moved, moves) = parse_input(TEST_INPUT).unwrap();
+
+        stacks_moved
+            .apply_all(&moves[..2], &CrateMover9001)
+            .unwrap();
+        assert_eq!(stacks_moved.stacks(), stacks(&["", "MC", "PZND"]));
+        stacks_moved
+            .apply_all(&moves[2..], &CrateMover9001)
+            .unwrap();
+        assert_eq!(stacks_moved.top_crates(), "MCD");
+    }
+
+    #[test]
+    fn test_custom_cranes() {
+        let (initial, moves) = parse_input(TEST_INPUT).unwrap();
+        let run = |crane: &dyn Fn(&mut Stacks, &Move) -> Result<(), String>| {
+            let mut stacks = initial.clone();
+            for m in &moves {
+                crane(&mut stacks, m).unwrap();
+            }
+            stacks
+        };
+
+        let by_9000 = run(&|s, m| s.apply(m, &CrateMover9000));
+        let by_9001 = run(&|s, m| s.apply(m, &CrateMover9001));
+        assert_eq!(
+            run(&|s, m| s.apply(m, &BatchCrane { capacity: 1 })),
+            by_9000
+        );
+        assert_eq!(
+            run(&|s, m| s.apply(m, &BatchCrane { capacity: 3 })),
+            by_9001
+        );
+
+        // Moving "MCD" two at a time takes "CD" then "M".
+        let mut stacks_moved = initial.clone();
+        let m = Move {
+            count: 3,
+            from: 2,
+            to: 3,
+        };
+        stacks_moved.apply(&m, &BatchCrane { capacity: 2 }).unwrap();
+        assert_eq!(stacks_moved.stacks(), stacks(&["ZN", "", "PCDM"]));
+
+        // Any closure can act as a crane.
+        let mut stacks_moved = initial;
+        stacks_moved
+            .apply(&m, &|lifted: &mut [Crate]| lifted.sort_by(|a, b| b.cmp(a)))
+            .unwrap();
+        assert_eq!(stacks_moved.stacks(), stacks(&["ZN", "", "PMDC"]));
+    }
+
+    #[test]
+    fn test_invalid_moves() {
+        let (mut stacks_moved, _) = parse_input(TEST_INPUT).unwrap();
+        let initial = stacks_moved.clone();
+
+        for (count, from, to) in [(3, 1, 2), (1, 0, 2), (1, 2, 4)] {
+            assert!(stacks_moved
+                .apply(&Move { count, from, to }, &CrateMover9000)
+                .is_err());
+            assert_eq!(stacks_moved, initial);
+        }
+
+        stacks_moved
+            .apply(
+                &Move {
+                    count: 1,
+                    from: 3,
+                    to: 1,
+                },
+                &CrateMover9000,
+            )
+            .unwrap();
+        assert_eq!(stacks_moved.top_crates(), "PD ");
+    }
+
+    #[test]
+    fn test_run_options() {
+        let args = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+
+        assert_eq!(
+            RunOptions::from_args(&[]),
+            Ok(RunOptions {
+                capacity: None,
+                trace: false
+            })
+        );
+        assert_eq!(
+            RunOptions::from_args(&args("--trace --capacity 4")),
+            Ok(RunOptions {
+                capacity: Some(4),
+                trace: true
+            })
+        );
+        assert!(RunOptions::from_args(&args("--capacity 0")).is_err());
+        assert!(RunOptions::from_args(&args("--capacity")).is_err());
+        assert!(RunOptions::from_args(&args("--crane 9001")).is_err());
+    }
+}
@@ -0,0 +1,382 @@
+//! The crab's cup game, as described in Advent of Code 2020 Day 23.
+//! https://adventofcode.com/2020/day/23
+//!
+//! Cups labelled from 1 upwards are arranged in a circle. Each move, the cups immediately
+//! clockwise of the current cup are picked up, and placed immediately clockwise of the
+//! destination cup, which is the cup with the highest label lower than the current cup's that was
+//! not picked up, wrapping around to the highest label if needed. The current cup then becomes
+//! the cup clockwise of it.
+//!
+//! As cups are only ever moved to immediately after another cup, the circle is held as a
+//! successor array, where `next[c]` is the label of the cup clockwise of cup `c`. Each move then
+//! takes a fixed number of steps however many cups there are.
+
+use std::fmt;
+
+pub type Cup = u32;
+
+/// The picked up cups and the destination cup of one move of a `CupRing` game.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Move {
+    pub picked_up: Vec<Cup>,
+    pub destination: Cup,
+}
+
+/// The state of a game.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CupRing {
+    /// `next[c]` is the cup clockwise of cup `c`. `next[0]` is unused, as there is no cup 0.
+    next: Vec<Cup>,
+    current: Cup,
+    pick_up: usize,
+    moves_made: usize,
+}
+
+/// Returns the cup labels in the first line of `input`, one digit per cup, e.g., "389125467".
+pub fn parse_labels(input: &str) -> Result<Vec<Cup>, String> {
+    input
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            c.to_digit(10)
+                .ok_or_else(|| format!("'{}' is not a cup label", c))
+        })
+        .collect()
+}
+
+impl CupRing {
+    /// Returns a game with the cups `labels` in clockwise order, followed by the cups labelled
+    /// from `labels.len() + 1` up to `total_cups`, in ascending order. The first cup is the
+    /// current cup, and each move picks up `pick_up` cups.
+    ///
+    /// Returns an error if `labels` is not an ordering of the labels 1 to `labels.len()`, if
+    /// `total_cups` is smaller than `labels.len()`, or if there are not enough cups to pick up
+    /// `pick_up` cups and still have a destination cup.
+    pub fn new(labels: &[Cup], total_cups: usize, pick_up: usize) -> Result<Self, String> {
+        if labels.is_empty() {
+            return Err("There must be at least one labelled cup".to_string());
+        }
+        if total_cups < labels.len() || Cup::try_from(total_cups).is_err() {
+            return Err(format!(
+                "Cannot make a game of {} cups from {} labelled cups",
+                total_cups,
+                labels.len()
+            ));
+        }
+        if pick_up + 2 > total_cups {
+            return Err(format!(
+                "Picking up {} cups needs at least {} cups in the game",
+                pick_up,
+                pick_up + 2
+            ));
+        }
+
+        let mut seen = vec![false; labels.len() + 1];
+        for &label in labels {
+            match seen.get_mut(label as usize) {
+                Some(seen) if label != 0 && !*seen => *seen = true,
+                _ => {
+                    return Err(format!(
+                        "The labels must be 1 to {} in any order, but {} is a duplicate or \
+                         out of range",
+                        labels.len(),
+                        label
+                    ))
+                }
+            }
+        }
+
+        let order = labels
+            .iter()
+            .copied()
+            .chain(labels.len() as Cup + 1..=total_cups as Cup);
+        let mut next = vec![0; total_cups + 1];
+        let mut previous = labels[0];
+        for cup in order.skip(1) {
+            next[previous as usize] = cup;
+            previous = cup;
+        }
+        next[previous as usize] = labels[0];
+
+        Ok(Self {
+            next,
+            current: labels[0],
+            pick_up,
+            moves_made: 0,
+        })
+    }
+
+    pub fn total_cups(&self) -> usize {
+        self.next.len() - 1
+    }
+
+    pub fn current(&self) -> Cup {
+        self.current
+    }
+
+    pub fn moves_made(&self) -> usize {
+        self.moves_made
+    }
+
+    /// Performs a single move, returning the cups picked up and the destination cup.
+    pub fn perform_move(&mut self) -> Move {
+        let picked_up = self.cups_after(self.current).take(self.pick_up).collect();
+        let destination = self.move_cups();
+        Move {
+            picked_up,
+            destination,
+        }
+    }
+
+    /// Performs `moves` moves.
+    pub fn play(&mut self, moves: usize) {
+        for _ in 0..moves {
+            self.move_cups();
+        }
+    }
+
+    /// Performs a single move, returning the destination cup. The picked up cups are checked by
+    /// following `next` from the current cup, so that a move does not allocate.
+    fn move_cups(&mut self) -> Cup {
+        let first = self.next[self.current as usize];
+        let mut last = self.current;
+        for _ in 0..self.pick_up {
+            last = self.next[last as usize];
+        }
+
+        let is_picked_up = |cup: Cup| {
+            let mut picked = self.current;
+            (0..self.pick_up).any(|_| {
+                picked = self.next[picked as usize];
+                picked == cup
+            })
+        };
+        let highest = self.total_cups() as Cup;
+        let mut destination = self.current;
+        loop {
+            destination = if destination == 1 {
+                highest
+            } else {
+                destination - 1
+            };
+            if !is_picked_up(destination) {
+                break;
+            }
+        }
+
+        if self.pick_up > 0 {
+            self.next[self.current as usize] = self.next[last as usize];
+            self.next[last as usize] = self.next[destination as usize];
+            self.next[destination as usize] = first;
+        }
+
+        self.current = self.next[self.current as usize];
+        self.moves_made += 1;
+        destination
+    }
+
+    /// Performs `moves` moves, returning a description of each in the format of the worked
+    /// example on the challenge page, followed by the final state.
+    pub fn play_verbose(&mut self, moves: usize) -> String {
+        let mut description = String::new();
+
+        for move_number in 1..=moves {
+            let cups = self.to_string();
+            let Move {
+                picked_up,
+                destination,
+            } = self.perform_move();
+            let picked_up: Vec<String> = picked_up.iter().map(Cup::to_string).collect();
+
+            description += &format!(
+                "-- move {} --\ncups: {}\npick up: {}\ndestination: {}\n\n",
+                move_number,
+                cups,
+                picked_up.join(", "),
+                destination
+            );
+        }
+
+        description + &format!("-- final --\ncups: {}\n", self)
+    }
+
+    /// Returns the cups in clockwise order starting with the cup clockwise of `cup`, and ending
+    /// before `cup`.
+    pub fn cups_after(&self, cup: Cup) -> impl Iterator<Item = Cup> + '_ {
+        let mut next = self.next[cup as usize];
+        (1..self.total_cups()).map(move |_| {
+            let result = next;
+            next = self.next[next as usize];
+            result
+        })
+    }
+
+    /// Returns the labels of the cups after cup 1 concatenated, as in the answer to part 1 of the
+    /// challenge.
+    pub fn labels_after_one(&self) -> String {
+        self.cups_after(1).map(|cup| cup.to_string()).collect()
+    }
+}
+
+/// Lists the cups clockwise with the current cup in parentheses. As in the challenge's worked
+/// example, the list is rotated so that the current cup moves one place to the right after each
+/// move.
+impl fmt::Display for CupRing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let total = self.total_cups();
+        let mut cups = Vec::with_capacity(total);
+        cups.push(self.current);
+        cups.extend(self.cups_after(self.current));
+        cups.rotate_right(self.moves_made % total);
+
+        for cup in cups {
+            if cup == self.current {
+                write!(f, "({})", cup)?;
+            } else {
+                write!(f, " {} ", cup)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Test data based on examples on the challenge page.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "389125467";
+
+    fn test_ring(total_cups: usize) -> CupRing {
+        CupRing::new(&parse_labels(TEST_INPUT).unwrap(), total_cups, 3).unwrap()
+    }
+
+    #[test]
+    fn test_new() {
+        let ring = test_ring(9);
+        assert_eq!(ring.total_cups(), 9);
+        assert_eq!(ring.current(), 3);
+        assert_eq!(
+            ring.cups_after(3).collect::<Vec<_>>(),
+            [8, 9, 1, 2, 5, 4, 6, 7]
+        );
+
+        let ring = test_ring(12);
+        assert_eq!(
+            ring.cups_after(7).collect::<Vec<_>>(),
+            [10, 11, 12, 3, 8, 9, 1, 2, 5, 4, 6]
+        );
+    }
+
+    #[test]
+    fn test_new_errors() {
+        assert!(parse_labels("38a").is_err());
+        assert!(CupRing::new(&[], 9, 3).is_err());
+        assert!(CupRing::new(&[3, 1, 2], 2, 0).is_err());
+        assert!(CupRing::new(&[3, 1, 1], 3, 1).is_err());
+        assert!(CupRing::new(&[3, 1, 4], 4, 1).is_err());
+        assert!(CupRing::new(&[0, 1, 2], 3, 1).is_err());
+        assert!(CupRing::new(&[3, 1, 2], 4, 3).is_err());
+        assert!(CupRing::new(&[3, 1, 2], 5, 3).is_ok());
+    }
+
+    #[test]
+    fn test_perform_move() {
+        let mut ring = test_ring(9);
+
+        let expected = [
+            ([8, 9, 1], 2, "3 (2) 8  9  1  5  4  6  7 "),
+            ([8, 9, 1], 7, " 3  2 (5) 4  6  7  8  9  1 "),
+            ([4, 6, 7], 3, " 7  2  5 (8) 9  1  3  4  6 "),
+            ([9, 1, 3], 7, " 3  2  5  8 (4) 6  7  9  1 "),
+            ([6, 7, 9], 3, " 9  2  5  8  4 (1) 3  6  7 "),
+            ([3, 6, 7], 9, " 7  2  5  8  4  1 (9) 3  6 "),
+            ([3, 6, 7], 8, " 8  3  6  7  4  1  9 (2) 5 "),
+            ([5, 8, 3], 1, " 7  4  1  5  8  3  9  2 (6)"),
+            ([7, 4, 1], 5, "(5) 7  4  1  8  3  9  2  6 "),
+            ([7, 4, 1], 3, " 5 (8) 3  7  4  1  9  2  6 "),
+        ];
+
+        for (picked_up, destination, cups) in expected {
+            assert_eq!(
+                ring.perform_move(),
+                Move {
+                    picked_up: picked_up.to_vec(),
+                    destination
+                }
+            );
+            assert_eq!(ring.to_string().trim_start(), cups.trim_start());
+        }
+        assert_eq!(ring.moves_made(), 10);
+        assert_eq!(ring.labels_after_one(), "92658374");
+    }
+
+    #[test]
+    fn test_play_verbose() {
+        let mut ring = test_ring(9);
+        let description = ring.play_verbose(2);
+
+        assert_eq!(
+            description,
+            [
+                "-- move 1 --",
+                "cups: (3) 8  9  1  2  5  4  6  7 ",
+                "pick up: 8, 9, 1",
+                "destination: 2",
+                "",
+                "-- move 2 --",
+                "cups:  3 (2) 8  9  1  5  4  6  7 ",
+                "pick up: 8, 9, 1",
+                "destination: 7",
+                "",
+                "-- final --",
+                "cups:  3  2 (5) 4  6  7  8  9  1 ",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_play() {
+        let mut ring = test_ring(9);
+        ring.play(100);
+        assert_eq!(ring.labels_after_one(), "67384529");
+    }
+
+    #[test]
+    fn test_pick_up_count() {
+        let mut ring = CupRing::new(&parse_labels(TEST_INPUT).unwrap(), 9, 1).unwrap();
+        assert_eq!(
+            ring.perform_move(),
+            Move {
+                picked_up: vec![8],
+                destination: 2
+            }
+        );
+        assert_eq!(
+            ring.cups_after(3).collect::<Vec<_>>(),
+            [9, 1, 2, 8, 5, 4, 6, 7]
+        );
+
+        let mut ring = CupRing::new(&parse_labels(TEST_INPUT).unwrap(), 9, 0).unwrap();
+        ring.play(5);
+        assert_eq!(
+            ring.cups_after(3).collect::<Vec<_>>(),
+            [8, 9, 1, 2, 5, 4, 6, 7]
+        );
+        assert_eq!(ring.current(), 5);
+    }
+
+    #[test]
+    fn test_million_cups() {
+        let mut ring = test_ring(1_000_000);
+        ring.play(10_000_000);
+
+        let mut after_one = ring.cups_after(1);
+        assert_eq!(after_one.next(), Some(934001));
+        assert_eq!(after_one.next(), Some(159792));
+    }
+}
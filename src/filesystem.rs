@@ -0,0 +1,946 @@
+//! A model of the filesystem described by the terminal log in Advent of Code 2022 Day 07.
+//! https://adventofcode.com/2022/day/7
+//!
+//! The log contains Linux-style `cd` and `ls` commands and their output. It is used to
+//! reconstruct the directories and files seen, checking along the way that the log is
+//! consistent. The reconstructed `FileSystem` can then be queried directly, or via a `Shell`
+//! that supports a handful of familiar commands.
+
+use std::fmt::{self, Display};
+
+pub type FileSize = u64;
+pub type NodeId = usize;
+
+/// The `NodeId` of the root directory.
+pub const ROOT_NODE_ID: NodeId = 0;
+
+const HUMAN_SIZE_UNITS: [&str; 4] = ["K", "M", "G", "T"];
+
+/// The result of looking for a directory to delete in order to free space on a disk.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DirectoryToDelete {
+    /// There is already enough free space, so nothing needs deleting.
+    NotNeeded,
+    /// The `NodeId` of the smallest directory whose deletion frees enough space.
+    Smallest(NodeId),
+    /// Deleting any single directory, including the root, does not free enough space.
+    NoneLargeEnough,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node {
+    Directory {
+        name: String,
+        parent: NodeId,
+        children: Vec<NodeId>,
+    },
+    File {
+        name: String,
+        parent: NodeId,
+        file_size: FileSize,
+    },
+}
+
+impl Node {
+    pub fn name(&self) -> &str {
+        match self {
+            Node::Directory { name, .. } | Node::File { name, .. } => name,
+        }
+    }
+
+    pub fn parent(&self) -> NodeId {
+        match self {
+            Node::Directory { parent, .. } | Node::File { parent, .. } => *parent,
+        }
+    }
+
+    pub fn is_directory(&self) -> bool {
+        matches!(self, Node::Directory { .. })
+    }
+}
+
+/// A `FileSystem` contains all the nodes in a directory hierarchy. It is created with a root
+/// directory named "/". It is special in that its parent is itself. All nodes are referenced by
+/// their index in the `t` vector, referred to as the `NodeId`. The root node has a NodeId of 0.
+//
+// Implementation note: although links between nodes could be implemented with borrows (e.g.,
+// &Node), this is complex in Rust and offers poor performance. The latter is because Nodes packed
+// into a vector will be close in memory, whereas nodes independently stored in heap memory may be
+// placed further apart.
+#[derive(Debug, PartialEq)]
+pub struct FileSystem {
+    t: Vec<Node>,
+}
+
+impl Default for FileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileSystem {
+    /// Creates a new `FileSystem` that is prepopulated with an empty root directory.
+    pub fn new() -> Self {
+        Self {
+            t: vec![Node::Directory {
+                name: "/".to_string(),
+                parent: ROOT_NODE_ID,
+                children: Vec::new(),
+            }],
+        }
+    }
+
+    /// Takes a string containing an entire terminal log and converts it into a `FileSystem`.
+    /// Each line of input must be one of:
+    ///     $ cd <directory_name>
+    ///     $ ls
+    ///     dir <directory_name>
+    ///     <file_size> <file_name>
+    ///
+    /// Changing into a directory that has not been listed creates it. Returns an error if the
+    /// log is malformed or inconsistent, e.g., if the same file is listed twice with different
+    /// sizes, or the same name is listed as both a file and a directory.
+    pub fn from_terminal_log(input: &str) -> Result<Self, String> {
+        let mut fs = Self::new();
+        let mut cwd = ROOT_NODE_ID; // current working directory
+        let mut listing = false;
+
+        for (line_number, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let result = if let Some(command) = line.strip_prefix("$ ") {
+                listing = false;
+                match command.split_once(' ') {
+                    Some(("cd", dir_name)) => fs.log_cd(cwd, dir_name.trim()).map(|id| {
+                        cwd = id;
+                    }),
+                    None if command == "ls" => {
+                        listing = true;
+                        Ok(())
+                    }
+                    _ => Err(format!("Unrecognized command '{}'", command)),
+                }
+            } else if !listing {
+                Err("Output found that does not follow an 'ls' command".to_string())
+            } else if let Some(dir_name) = line.strip_prefix("dir ") {
+                fs.log_directory(cwd, dir_name.trim()).map(|_| ())
+            } else {
+                match line.split_once(' ') {
+                    Some((file_size_str, file_name)) => match file_size_str.parse() {
+                        Ok(file_size) => fs.log_file(cwd, file_name.trim(), file_size),
+                        Err(_) => Err(format!("Invalid file size '{}'", file_size_str)),
+                    },
+                    None => Err(format!("Unrecognized output '{}'", line)),
+                }
+            };
+
+            result.map_err(|e| format!("Line {}: {}", line_number + 1, e))?;
+        }
+
+        Ok(fs)
+    }
+
+    /// Returns the node with the given `NodeId`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` does not refer to a node in this `FileSystem`.
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.t[id]
+    }
+
+    /// Returns the `NodeId`s of the nodes contained in directory `id`, or an empty slice if `id`
+    /// is a file.
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        match &self.t[id] {
+            Node::Directory { children, .. } => children,
+            Node::File { .. } => &[],
+        }
+    }
+
+    /// Returns the `NodeId` of the node named `name` directly within directory `dir`, if any.
+    pub fn child_named(&self, dir: NodeId, name: &str) -> Option<NodeId> {
+        self.children(dir)
+            .iter()
+            .copied()
+            .find(|&c| self.t[c].name() == name)
+    }
+
+    /// Returns the absolute path of the node `id`, e.g., "/a/e".
+    pub fn path(&self, id: NodeId) -> String {
+        if id == ROOT_NODE_ID {
+            return "/".to_string();
+        }
+
+        let mut names = Vec::new();
+        let mut current = id;
+        while current != ROOT_NODE_ID {
+            names.push(self.t[current].name());
+            current = self.t[current].parent();
+        }
+
+        names
+            .iter()
+            .rev()
+            .map(|name| format!("/{}", name))
+            .collect()
+    }
+
+    /// Returns the `NodeId` of `path`, which may be absolute or relative to directory `cwd`.
+    /// Path components of "." and ".." have their usual meanings, and the parent of the root
+    /// directory is the root directory itself.
+    pub fn resolve(&self, cwd: NodeId, path: &str) -> Result<NodeId, String> {
+        let mut current = if path.starts_with('/') {
+            ROOT_NODE_ID
+        } else {
+            cwd
+        };
+
+        for component in path.split('/').filter(|c| !c.is_empty()) {
+            if !self.t[current].is_directory() {
+                return Err(format!("{}: Not a directory", self.path(current)));
+            }
+
+            current = match component {
+                "." => current,
+                ".." => self.t[current].parent(),
+                _ => self
+                    .child_named(current, component)
+                    .ok_or_else(|| format!("{}: No such file or directory", path))?,
+            };
+        }
+
+        Ok(current)
+    }
+
+    /// Calculates the size of each directory. A directory's size is the total of all the files it
+    /// contains directly and indirectly (i.e., via sub-directories). Returns a vector that uses the
+    /// same indexes as the `NodeId`s in this `FileSystem` and which contains the size of each
+    /// directory. For example, the size of the directory with NodeId 2 can be found in index 2 of
+    /// the result. Indexes of files contain `None`.
+    pub fn directory_sizes(&self) -> Vec<Option<FileSize>> {
+        let mut dir_sizes = vec![None; self.t.len()];
+        self.directory_sizes_recurse(&mut dir_sizes, ROOT_NODE_ID);
+        dir_sizes
+    }
+
+    /// Returns the size of node `id`. This is the size of the file, or the total size of all files
+    /// within the directory.
+    pub fn size(&self, id: NodeId) -> FileSize {
+        match &self.t[id] {
+            Node::File { file_size, .. } => *file_size,
+            Node::Directory { children, .. } => children.iter().map(|&c| self.size(c)).sum(),
+        }
+    }
+
+    /// Returns the total size of all files.
+    pub fn used_space(&self) -> FileSize {
+        self.size(ROOT_NODE_ID)
+    }
+
+    /// Finds the smallest directory whose deletion would leave at least `required_space` free on
+    /// a disk of `disk_size`. The result also distinguishes the cases where there is already
+    /// enough free space, and where deleting any single directory is not enough.
+    pub fn smallest_directory_to_free(
+        &self,
+        disk_size: FileSize,
+        required_space: FileSize,
+    ) -> DirectoryToDelete {
+        let unused_space = disk_size.saturating_sub(self.used_space());
+        if unused_space >= required_space {
+            return DirectoryToDelete::NotNeeded;
+        }
+        let need_to_free = required_space - unused_space;
+
+        self.directory_sizes()
+            .iter()
+            .enumerate()
+            .filter_map(|(id, ds)| ds.map(|size| (id, size)))
+            .filter(|&(_, size)| size >= need_to_free)
+            .min_by_key(|&(_, size)| size)
+            .map_or(DirectoryToDelete::NoneLargeEnough, |(id, _)| {
+                DirectoryToDelete::Smallest(id)
+            })
+    }
+
+    /// Returns the `NodeId`s of node `id` and every node below it, with each directory listed
+    /// before its contents.
+    pub fn descendants(&self, id: NodeId) -> Vec<NodeId> {
+        let mut result = vec![id];
+        for &c in self.children(id) {
+            result.append(&mut self.descendants(c));
+        }
+        result
+    }
+
+    /// Writes the subtree starting at node `id` in the same format used by the challenge.
+    pub fn write_tree(&self, f: &mut impl fmt::Write, id: NodeId) -> fmt::Result {
+        self.write_tree_recurse(f, id, 0)
+    }
+
+    fn write_tree_recurse(&self, f: &mut impl fmt::Write, id: NodeId, depth: usize) -> fmt::Result {
+        match &self.t[id] {
+            Node::Directory { name, children, .. } => {
+                writeln!(f, "{0:>1$} {name} (dir)", "-", 2 * depth + 1)?;
+
+                for child in children.iter() {
+                    self.write_tree_recurse(f, *child, depth + 1)?
+                }
+                Ok(())
+            }
+            Node::File {
+                name, file_size, ..
+            } => {
+                writeln!(
+                    f,
+                    "{0:>1$} {name} (file, size={file_size})",
+                    "-",
+                    2 * depth + 1
+                )
+            }
+        }
+    }
+
+    fn directory_sizes_recurse(&self, dir_sizes: &mut [Option<FileSize>], current_dir_id: NodeId) {
+        let mut dir_size = 0;
+
+        for &c in self.children(current_dir_id) {
+            match &self.t[c] {
+                Node::Directory { .. } => {
+                    self.directory_sizes_recurse(dir_sizes, c);
+                    dir_size += dir_sizes[c].unwrap();
+                }
+                Node::File { file_size, .. } => {
+                    dir_size += file_size;
+                }
+            }
+        }
+        dir_sizes[current_dir_id] = Some(dir_size);
+    }
+
+    /// Adds `node` to the end of the list of nodes and to its parent's children.
+    fn add_node(&mut self, node: Node) -> NodeId {
+        let new_node_id = self.t.len();
+        let parent = node.parent();
+        self.t.push(node);
+
+        match &mut self.t[parent] {
+            Node::Directory { children, .. } => {
+                children.push(new_node_id);
+            }
+            _ => {
+                panic!("Fatal error - the parent of a node was not a Directory object, which should never happen");
+            }
+        }
+
+        new_node_id
+    }
+
+    /// Handles a 'cd' command in the log. `dir_name` can be:
+    ///     "/" to return the `NodeId` of the root directory
+    ///     ".." to return the `NodeId` of the `cwd`'s parent
+    ///     a sub-directory name to return its `NodeId`
+    ///
+    /// If a sub-directory is specified that does not exist it is created.
+    fn log_cd(&mut self, cwd: NodeId, dir_name: &str) -> Result<NodeId, String> {
+        match dir_name {
+            "" => Err("cd must be called with a directory name".to_string()),
+            "/" => Ok(ROOT_NODE_ID),
+            ".." => Ok(self.t[cwd].parent()),
+            _ => self.log_directory(cwd, dir_name),
+        }
+    }
+
+    /// Handles a directory listed in the output of 'ls', returning its `NodeId`. It is created
+    /// if it has not been seen before.
+    fn log_directory(&mut self, cwd: NodeId, dir_name: &str) -> Result<NodeId, String> {
+        match self.child_named(cwd, dir_name) {
+            Some(id) if self.t[id].is_directory() => Ok(id),
+            Some(id) => Err(format!(
+                "'{}' was previously listed as a file",
+                self.path(id)
+            )),
+            None => Ok(self.add_node(Node::Directory {
+                name: dir_name.to_string(),
+                parent: cwd,
+                children: Vec::new(),
+            })),
+        }
+    }
+
+    /// Handles a file listed in the output of 'ls'. Listing a file that has been seen before is
+    /// only valid if its size is unchanged.
+    fn log_file(
+        &mut self,
+        cwd: NodeId,
+        file_name: &str,
+        file_size: FileSize,
+    ) -> Result<(), String> {
+        match self.child_named(cwd, file_name) {
+            Some(id) => match self.t[id] {
+                Node::File {
+                    file_size: previous_size,
+                    ..
+                } if previous_size == file_size => Ok(()),
+                Node::File {
+                    file_size: previous_size,
+                    ..
+                } => Err(format!(
+                    "'{}' is listed with size {} but was previously listed with size {}",
+                    self.path(id),
+                    file_size,
+                    previous_size
+                )),
+                Node::Directory { .. } => Err(format!(
+                    "'{}' was previously listed as a directory",
+                    self.path(id)
+                )),
+            },
+            None => {
+                self.add_node(Node::File {
+                    name: file_name.to_string(),
+                    parent: cwd,
+                    file_size,
+                });
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Displays this `FileSystem` in the same format used by the challenge.
+impl Display for FileSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_tree(f, ROOT_NODE_ID)
+    }
+}
+
+/// Returns `size` in the style of `du -h`, e.g., "584", "1.5K" or "93K". Sizes are rounded up,
+/// and use a single decimal place if this gives fewer than two digits before the decimal point.
+pub fn human_readable_size(size: FileSize) -> String {
+    if size < 1024 {
+        return size.to_string();
+    }
+
+    let mut divisor = 1024;
+    for unit in HUMAN_SIZE_UNITS {
+        // Sizes too large to multiply by 10 are far too large to need a decimal place.
+        if let Some(tenths) = size.checked_mul(10).map(|t| t.div_ceil(divisor)) {
+            if tenths < 100 {
+                return format!("{}.{}{}", tenths / 10, tenths % 10, unit);
+            }
+        }
+
+        let whole = size.div_ceil(divisor);
+        if whole < 1024 || unit == HUMAN_SIZE_UNITS[HUMAN_SIZE_UNITS.len() - 1] {
+            return format!("{}{}", whole, unit);
+        }
+        divisor *= 1024;
+    }
+
+    unreachable!()
+}
+
+/// Which nodes a `find` command matches, based on size.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SizeFilter {
+    Over(FileSize),
+    Under(FileSize),
+    Exactly(FileSize),
+}
+
+impl SizeFilter {
+    /// Parses a `find -size` argument, which is a size in bytes optionally followed by a unit of
+    /// 'k', 'M' or 'G'. A leading '+' matches larger sizes and a leading '-' matches smaller ones.
+    fn parse(arg: &str) -> Result<Self, String> {
+        let (constructor, digits): (fn(FileSize) -> Self, &str) =
+            if let Some(rest) = arg.strip_prefix('+') {
+                (SizeFilter::Over, rest)
+            } else if let Some(rest) = arg.strip_prefix('-') {
+                (SizeFilter::Under, rest)
+            } else {
+                (SizeFilter::Exactly, arg)
+            };
+
+        let (digits, multiplier) = match digits.chars().last() {
+            Some('k') => (&digits[..digits.len() - 1], 1024),
+            Some('M') => (&digits[..digits.len() - 1], 1024 * 1024),
+            Some('G') => (&digits[..digits.len() - 1], 1024 * 1024 * 1024),
+            _ => (digits, 1),
+        };
+
+        digits
+            .parse::<FileSize>()
+            .ok()
+            .and_then(|size| size.checked_mul(multiplier))
+            .map(constructor)
+            .ok_or_else(|| format!("find: invalid argument '{}' to -size", arg))
+    }
+
+    fn matches(&self, size: FileSize) -> bool {
+        match *self {
+            SizeFilter::Over(limit) => size > limit,
+            SizeFilter::Under(limit) => size < limit,
+            SizeFilter::Exactly(limit) => size == limit,
+        }
+    }
+}
+
+/// A mini shell for exploring a `FileSystem`. It tracks a current working directory and supports
+/// the following commands, which behave like simplified versions of their Linux namesakes:
+///     cd [path]
+///     pwd
+///     ls [path]
+///     du [-h] [path]
+///     find [path] [-type f|d] [-size [+|-]N[k|M|G]]
+///     tree [path]
+///
+/// Sizes are in bytes unless a unit is given, and the size of a directory is the total size of
+/// the files it contains.
+#[derive(Debug)]
+pub struct Shell<'a> {
+    fs: &'a FileSystem,
+    cwd: NodeId,
+}
+
+impl<'a> Shell<'a> {
+    /// Creates a new `Shell` for `fs` whose working directory is the root directory.
+    pub fn new(fs: &'a FileSystem) -> Self {
+        Self {
+            fs,
+            cwd: ROOT_NODE_ID,
+        }
+    }
+
+    /// Returns the `NodeId` of the current working directory.
+    pub fn cwd(&self) -> NodeId {
+        self.cwd
+    }
+
+    /// Runs `command_line` and returns its output, or an error message if it fails.
+    pub fn execute(&mut self, command_line: &str) -> Result<String, String> {
+        let mut words = command_line.split_whitespace();
+        let Some(command) = words.next() else {
+            return Ok(String::new());
+        };
+        let args: Vec<&str> = words.collect();
+
+        match command {
+            "cd" => self.cd(&args),
+            "pwd" => Ok(format!("{}\n", self.fs.path(self.cwd))),
+            "ls" => self.ls(&args),
+            "du" => self.du(&args),
+            "find" => self.find(&args),
+            "tree" => self.tree(&args),
+            _ => Err(format!("{}: command not found", command)),
+        }
+    }
+
+    /// Returns the `NodeId` of the optional single path in `args`, defaulting to the current
+    /// working directory.
+    fn single_path(&self, command: &str, args: &[&str]) -> Result<NodeId, String> {
+        match args {
+            [] => Ok(self.cwd),
+            [path] => self.fs.resolve(self.cwd, path),
+            _ => Err(format!("{}: too many arguments", command)),
+        }
+    }
+
+    fn cd(&mut self, args: &[&str]) -> Result<String, String> {
+        let target = match args {
+            [] => ROOT_NODE_ID,
+            _ => self.single_path("cd", args)?,
+        };
+
+        if !self.fs.node(target).is_directory() {
+            return Err(format!("cd: {}: Not a directory", self.fs.path(target)));
+        }
+
+        self.cwd = target;
+        Ok(String::new())
+    }
+
+    /// Lists the contents of a directory in the same format as the terminal log.
+    fn ls(&self, args: &[&str]) -> Result<String, String> {
+        let target = self.single_path("ls", args)?;
+
+        Ok(match self.fs.node(target) {
+            Node::File {
+                name, file_size, ..
+            } => format!("{} {}\n", file_size, name),
+            Node::Directory { children, .. } => children
+                .iter()
+                .map(|&c| match self.fs.node(c) {
+                    Node::Directory { name, .. } => format!("dir {}\n", name),
+                    Node::File {
+                        name, file_size, ..
+                    } => format!("{} {}\n", file_size, name),
+                })
+                .collect(),
+        })
+    }
+
+    /// Lists the size of each directory, with sub-directories listed before their parents. If
+    /// the path given is a file, only the size of that file is listed.
+    fn du(&self, args: &[&str]) -> Result<String, String> {
+        let (human, args) = match args {
+            ["-h", rest @ ..] => (true, rest),
+            _ => (false, args),
+        };
+        let target = self.single_path("du", args)?;
+
+        let mut output = String::new();
+        if self.fs.node(target).is_directory() {
+            let dir_sizes = self.fs.directory_sizes();
+            self.du_recurse(&mut output, &dir_sizes, target, human);
+        } else {
+            self.du_line(&mut output, self.fs.size(target), target, human);
+        }
+        Ok(output)
+    }
+
+    fn du_recurse(
+        &self,
+        output: &mut String,
+        dir_sizes: &[Option<FileSize>],
+        id: NodeId,
+        human: bool,
+    ) {
+        let Some(size) = dir_sizes[id] else {
+            return;
+        };
+
+        for &c in self.fs.children(id) {
+            self.du_recurse(output, dir_sizes, c, human);
+        }
+
+        self.du_line(output, size, id, human);
+    }
+
+    fn du_line(&self, output: &mut String, size: FileSize, id: NodeId, human: bool) {
+        let size_text = if human {
+            human_readable_size(size)
+        } else {
+            size.to_string()
+        };
+        output.push_str(&format!("{}\t{}\n", size_text, self.fs.path(id)));
+    }
+
+    /// Lists the paths of all nodes at or below a starting point that match the filters given.
+    fn find(&self, args: &[&str]) -> Result<String, String> {
+        let mut start = self.cwd;
+        let mut type_filter = None;
+        let mut size_filter = None;
+
+        let mut remaining = args;
+        if let [path, rest @ ..] = remaining {
+            if !path.starts_with('-') {
+                start = self.fs.resolve(self.cwd, path)?;
+                remaining = rest;
+            }
+        }
+
+        while !remaining.is_empty() {
+            match remaining {
+                ["-type", "f", rest @ ..] => {
+                    type_filter = Some(false);
+                    remaining = rest;
+                }
+                ["-type", "d", rest @ ..] => {
+                    type_filter = Some(true);
+                    remaining = rest;
+                }
+                ["-size", size, rest @ ..] => {
+                    size_filter = Some(SizeFilter::parse(size)?);
+                    remaining = rest;
+                }
+                [unknown, ..] => {
+                    return Err(format!(
+                        "find: unknown or incomplete predicate '{}'",
+                        unknown
+                    ))
+                }
+                [] => unreachable!(),
+            }
+        }
+
+        // Sizing each directory separately would walk its subtree again for every directory above.
+        let dir_sizes = self.fs.directory_sizes();
+        let size = |id: NodeId| dir_sizes[id].unwrap_or_else(|| self.fs.size(id));
+
+        Ok(self
+            .fs
+            .descendants(start)
+            .into_iter()
+            .filter(|&id| type_filter.is_none_or(|dir| self.fs.node(id).is_directory() == dir))
+            .filter(|&id| size_filter.is_none_or(|filter| filter.matches(size(id))))
+            .map(|id| format!("{}\n", self.fs.path(id)))
+            .collect())
+    }
+
+    fn tree(&self, args: &[&str]) -> Result<String, String> {
+        let target = self.single_path("tree", args)?;
+        let mut output = String::new();
+        self.fs
+            .write_tree(&mut output, target)
+            .map_err(|e| e.to_string())?;
+        Ok(output)
+    }
+}
+
+// Test data based on examples on the challenge page.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "\
+$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k
+";
+
+    const EXPECTED_OUTPUT: &str = "\
+- / (dir)
+  - a (dir)
+    - e (dir)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir)
+    - j (file, size=4060174)
+    - d.log (file, size=8033020)
+    - d.ext (file, size=5626152)
+    - k (file, size=7214296)
+";
+
+    fn test_fs() -> FileSystem {
+        FileSystem::from_terminal_log(TEST_INPUT).unwrap()
+    }
+
+    #[test]
+    fn test_from_terminal_log() {
+        assert_eq!(test_fs().to_string(), EXPECTED_OUTPUT);
+    }
+
+    #[test]
+    fn test_cd_creates_directories() {
+        let fs = FileSystem::from_terminal_log("$ cd subdir1\n$ cd subdir2\n$ cd /\n$ cd subdir3")
+            .unwrap();
+
+        assert_eq!(
+            fs.node(1),
+            &Node::Directory {
+                name: "subdir1".to_string(),
+                parent: ROOT_NODE_ID,
+                children: vec![2],
+            }
+        );
+        assert_eq!(fs.node(2).parent(), 1);
+        assert_eq!(fs.node(3).parent(), ROOT_NODE_ID);
+        assert_eq!(fs.children(ROOT_NODE_ID), &[1, 3]);
+    }
+
+    #[test]
+    fn test_repeated_listing_is_consistent() {
+        let input = format!("{}$ cd /\n$ ls\ndir a\n14848514 b.txt\n", TEST_INPUT);
+        assert_eq!(
+            FileSystem::from_terminal_log(&input).unwrap().to_string(),
+            EXPECTED_OUTPUT
+        );
+    }
+
+    #[test]
+    fn test_inconsistent_logs() {
+        assert!(FileSystem::from_terminal_log("$ ls\n100 a\n200 a\n")
+            .unwrap_err()
+            .contains("'/a' is listed with size 200 but was previously listed with size 100"));
+        assert!(FileSystem::from_terminal_log("$ ls\n100 a\ndir a\n").is_err());
+        assert!(FileSystem::from_terminal_log("$ ls\ndir a\n100 a\n").is_err());
+        assert!(FileSystem::from_terminal_log("$ ls\n100 a\n$ cd a\n").is_err());
+        assert!(FileSystem::from_terminal_log("100 a\n").is_err());
+        assert!(FileSystem::from_terminal_log("$ rm a\n").is_err());
+        assert!(FileSystem::from_terminal_log("$ ls\nabc a\n").is_err());
+    }
+
+    #[test]
+    fn test_directory_sizes() {
+        assert_eq!(
+            test_fs().directory_sizes(),
+            vec![
+                Some(48381165), // Dir '/'
+                Some(94853),    // Dir 'a'
+                None,
+                None,
+                Some(24933642), // Dir 'd'
+                Some(584),      // Dir 'e'
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_path_and_resolve() {
+        let fs = test_fs();
+        assert_eq!(fs.path(ROOT_NODE_ID), "/");
+        assert_eq!(fs.path(5), "/a/e");
+
+        assert_eq!(fs.resolve(ROOT_NODE_ID, "/a/e"), Ok(5));
+        assert_eq!(fs.resolve(5, ".."), Ok(1));
+        assert_eq!(
+            fs.resolve(5, "../../d/./k"),
+            Ok(fs.child_named(4, "k").unwrap())
+        );
+        assert_eq!(fs.resolve(ROOT_NODE_ID, ".."), Ok(ROOT_NODE_ID));
+        assert!(fs.resolve(ROOT_NODE_ID, "x").is_err());
+        assert!(fs.resolve(ROOT_NODE_ID, "b.txt/x").is_err());
+    }
+
+    #[test]
+    fn test_smallest_directory_to_free() {
+        let fs = test_fs();
+        assert_eq!(
+            fs.smallest_directory_to_free(70_000_000, 30_000_000),
+            DirectoryToDelete::Smallest(4)
+        );
+        assert_eq!(
+            fs.smallest_directory_to_free(48_400_000, 19_000),
+            DirectoryToDelete::Smallest(5)
+        );
+        assert_eq!(
+            fs.smallest_directory_to_free(100_000_000, 30_000_000),
+            DirectoryToDelete::NotNeeded
+        );
+        assert_eq!(
+            fs.smallest_directory_to_free(1_000, 60_000_000),
+            DirectoryToDelete::NoneLargeEnough
+        );
+    }
+
+    #[test]
+    fn test_human_readable_size() {
+        assert_eq!(human_readable_size(584), "584");
+        assert_eq!(human_readable_size(1024), "1.0K");
+        assert_eq!(human_readable_size(1536), "1.5K");
+        assert_eq!(human_readable_size(94853), "93K");
+        assert_eq!(human_readable_size(24933642), "24M");
+        assert_eq!(human_readable_size(48381165), "47M");
+        assert_eq!(human_readable_size(FileSize::MAX), "16777216T");
+    }
+
+    #[test]
+    fn test_shell_navigation() {
+        let fs = test_fs();
+        let mut shell = Shell::new(&fs);
+
+        assert_eq!(shell.execute("pwd"), Ok("/\n".to_string()));
+        assert_eq!(shell.execute("cd a/e"), Ok(String::new()));
+        assert_eq!(shell.execute("pwd"), Ok("/a/e\n".to_string()));
+        assert_eq!(shell.execute("cd .."), Ok(String::new()));
+        assert_eq!(shell.cwd(), 1);
+        assert!(shell.execute("cd f").is_err());
+        assert!(shell.execute("cd missing").is_err());
+        assert!(shell.execute("rm f").is_err());
+        assert_eq!(shell.execute("cd"), Ok(String::new()));
+        assert_eq!(shell.cwd(), ROOT_NODE_ID);
+    }
+
+    #[test]
+    fn test_shell_ls() {
+        let fs = test_fs();
+        let mut shell = Shell::new(&fs);
+
+        assert_eq!(
+            shell.execute("ls"),
+            Ok("dir a\n14848514 b.txt\n8504156 c.dat\ndir d\n".to_string())
+        );
+        assert_eq!(shell.execute("ls /a/e"), Ok("584 i\n".to_string()));
+        assert_eq!(shell.execute("ls d/k"), Ok("7214296 k\n".to_string()));
+    }
+
+    #[test]
+    fn test_shell_du() {
+        let fs = test_fs();
+        let mut shell = Shell::new(&fs);
+
+        assert_eq!(
+            shell.execute("du"),
+            Ok("584\t/a/e\n94853\t/a\n24933642\t/d\n48381165\t/\n".to_string())
+        );
+        assert_eq!(
+            shell.execute("du -h a"),
+            Ok("584\t/a/e\n93K\t/a\n".to_string())
+        );
+        assert_eq!(shell.execute("du a/f"), Ok("29116\t/a/f\n".to_string()));
+        assert_eq!(
+            shell.execute("du -h b.txt"),
+            Ok("15M\t/b.txt\n".to_string())
+        );
+    }
+
+    #[test]
+    fn test_shell_find() {
+        let fs = test_fs();
+        let mut shell = Shell::new(&fs);
+
+        assert_eq!(
+            shell.execute("find -type d -size -100000"),
+            Ok("/a\n/a/e\n".to_string())
+        );
+        assert_eq!(
+            shell.execute("find /a -size +10k"),
+            Ok("/a\n/a/f\n/a/h.lst\n".to_string())
+        );
+        assert_eq!(
+            shell.execute("find d -size 4060174"),
+            Ok("/d/j\n".to_string())
+        );
+        assert!(shell.execute("find -size x").is_err());
+        assert!(shell.execute("find -size 99999999999999999G").is_err());
+        assert!(shell.execute("find -type").is_err());
+    }
+
+    #[test]
+    fn test_shell_tree() {
+        let fs = test_fs();
+        let mut shell = Shell::new(&fs);
+
+        assert_eq!(shell.execute("tree"), Ok(EXPECTED_OUTPUT.to_string()));
+        assert_eq!(
+            shell.execute("tree /a/e"),
+            Ok("- e (dir)\n  - i (file, size=584)\n".to_string())
+        );
+    }
+}
@@ -0,0 +1,477 @@
+//! Coordinates for grids of hexagonal tiles, as used for the tile floor in Advent of Code 2020 Day
+//! 24. https://adventofcode.com/2020/day/24
+//!
+//! Positions are stored in axial form, i.e., as the `q` and `r` coordinates of a `Hex`. The cube
+//! form adds a third coordinate `s` chosen so that `q + r + s == 0`, which makes distances and
+//! rotations simple to calculate. The axial axes are the same whichever way the hexagons are laid
+//! out, and `Orientation` only affects the compass names of the six directions and the rendering:
+//!
+//! - with pointy-topped hexagons, `q` increases to the east and `r` increases to the south-east;
+//! - with flat-topped hexagons, `q` increases to the south-east and `r` increases to the south.
+//!
+//! For example, with pointy-topped hexagons, the path "esew" ends one tile south-east of where it
+//! started, at `q` = 0 and `r` = 1.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+
+const TILE_PRESENT: char = '#';
+const TILE_ABSENT: char = '.';
+
+/// The axial offsets of the six neighbours of a hexagon, in the order of `Direction`'s indexes.
+const NEIGHBOR_OFFSETS: [(i32, i32); 6] = [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+
+/// The compass names of the directions for each orientation, in the order of `Direction`'s
+/// indexes.
+const POINTY_TOP_NAMES: [&str; 6] = ["e", "ne", "nw", "w", "sw", "se"];
+const FLAT_TOP_NAMES: [&str; 6] = ["se", "ne", "n", "nw", "sw", "s"];
+
+/// How hexagons are laid out, which determines the compass names of the directions between them.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Orientation {
+    /// Hexagons have a vertex at the top, so neighbours are to the east, north-east, north-west,
+    /// west, south-west and south-east.
+    PointyTop,
+    /// Hexagons have an edge at the top, so neighbours are to the south-east, north-east, north,
+    /// north-west, south-west and south.
+    FlatTop,
+}
+
+impl Orientation {
+    /// Returns the compass names of the six directions, e.g., "ne", in the order of
+    /// `Direction::ALL`.
+    pub fn direction_names(self) -> [&'static str; 6] {
+        match self {
+            Orientation::PointyTop => POINTY_TOP_NAMES,
+            Orientation::FlatTop => FLAT_TOP_NAMES,
+        }
+    }
+
+    /// Returns the compass name of `direction`, e.g., "ne".
+    pub fn direction_name(self, direction: Direction) -> &'static str {
+        self.direction_names()[direction.0]
+    }
+
+    /// Parses a single direction given by its compass name, e.g., "ne".
+    pub fn parse_direction(self, s: &str) -> Result<Direction, String> {
+        self.direction_names()
+            .iter()
+            .position(|&name| name == s)
+            .map(Direction)
+            .ok_or_else(|| format!("'{}' is not a direction between {:?} hexagons", s, self))
+    }
+
+    /// Parses a path written as direction names with no delimiters, e.g., "esenee". The longest
+    /// name that matches is used, so with flat-topped hexagons "nw" is north-west rather than
+    /// north followed by west. This is unambiguous, as "e" and "w" are not flat-topped directions.
+    pub fn parse_path(self, s: &str) -> Result<Vec<Direction>, String> {
+        let names = self.direction_names();
+        let mut remaining = s.trim();
+        let mut path = Vec::new();
+
+        while !remaining.is_empty() {
+            let index = (0..names.len())
+                .filter(|&i| remaining.starts_with(names[i]))
+                .max_by_key(|&i| names[i].len())
+                .ok_or_else(|| format!("Unrecognized direction at the start of '{}'", remaining))?;
+
+            path.push(Direction(index));
+            remaining = &remaining[names[index].len()..];
+        }
+
+        Ok(path)
+    }
+
+    /// Returns the "doubled" coordinates of `hex` used when rendering, where `x` is the character
+    /// column and `y` is the line. Only positions where `x + y` is even hold a hexagon.
+    fn doubled_position(self, hex: &Hex) -> (i32, i32) {
+        match self {
+            Orientation::PointyTop => (2 * hex.q + hex.r, hex.r),
+            Orientation::FlatTop => (hex.q, 2 * hex.r + hex.q),
+        }
+    }
+
+    /// The inverse of `doubled_position`. `x + y` must be even.
+    fn hex_at_doubled(self, x: i32, y: i32) -> Hex {
+        match self {
+            Orientation::PointyTop => Hex::new((x - y) / 2, y),
+            Orientation::FlatTop => Hex::new(x, (y - x) / 2),
+        }
+    }
+}
+
+/// One of the six directions from a hexagon to its neighbours. Directions are indexed from 0 to 5,
+/// anticlockwise, and the compass name of each depends on the `Orientation`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Direction(usize);
+
+impl Direction {
+    pub const ALL: [Direction; 6] = [
+        Direction(0),
+        Direction(1),
+        Direction(2),
+        Direction(3),
+        Direction(4),
+        Direction(5),
+    ];
+
+    /// Returns the direction with the given index, which is taken modulo 6.
+    pub fn from_index(index: usize) -> Self {
+        Direction(index % 6)
+    }
+
+    pub fn index(self) -> usize {
+        self.0
+    }
+
+    /// Returns this direction rotated by `sixths` sixths of a turn. Positive values rotate
+    /// anticlockwise and negative values clockwise.
+    pub fn rotate(self, sixths: i32) -> Self {
+        Direction((self.0 as i32 + sixths).rem_euclid(6) as usize)
+    }
+
+    /// Returns the direction pointing the opposite way.
+    pub fn opposite(self) -> Self {
+        self.rotate(3)
+    }
+
+    /// Returns the offset of the neighbour in this direction.
+    pub fn offset(self) -> Hex {
+        let (q, r) = NEIGHBOR_OFFSETS[self.0];
+        Hex { q, r }
+    }
+}
+
+/// The position of a hexagon in axial form.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl Hex {
+    /// The reference hexagon, from which other positions are measured.
+    pub const ORIGIN: Hex = Hex { q: 0, r: 0 };
+
+    pub fn new(q: i32, r: i32) -> Self {
+        Self { q, r }
+    }
+
+    /// Creates a `Hex` from its cube form. Returns an error if the coordinates do not total 0.
+    pub fn from_cube(q: i32, r: i32, s: i32) -> Result<Self, String> {
+        if q + r + s != 0 {
+            return Err(format!(
+                "Cube coordinates ({}, {}, {}) do not total 0",
+                q, r, s
+            ));
+        }
+
+        Ok(Self { q, r })
+    }
+
+    /// Returns the third coordinate of the cube form.
+    pub fn s(&self) -> i32 {
+        -self.q - self.r
+    }
+
+    /// Returns the cube form of this position, `(q, r, s)`.
+    pub fn cube(&self) -> (i32, i32, i32) {
+        (self.q, self.r, self.s())
+    }
+
+    /// Returns the neighbour of this hexagon in `direction`.
+    pub fn neighbor(&self, direction: Direction) -> Self {
+        *self + direction.offset()
+    }
+
+    /// Returns an iterator over the six neighbours of this hexagon, in the order of
+    /// `Direction::ALL`.
+    pub fn neighbors(&self) -> impl Iterator<Item = Hex> + '_ {
+        Direction::ALL.iter().map(|&d| self.neighbor(d))
+    }
+
+    /// Returns the hexagon reached by following `path` from this one.
+    pub fn travel(&self, path: &[Direction]) -> Self {
+        path.iter().fold(*self, |hex, &d| hex.neighbor(d))
+    }
+
+    /// Returns the number of steps between this hexagon and `other`.
+    pub fn distance(&self, other: &Hex) -> u32 {
+        let (q, r, s) = (*self - *other).cube();
+        (q.unsigned_abs() + r.unsigned_abs() + s.unsigned_abs()) / 2
+    }
+
+    /// Returns the hexagons exactly `radius` steps from this one. The ring starts at the hexagon
+    /// `radius` steps in `Direction` 4 and continues anticlockwise. A radius of 0 returns only this
+    /// hexagon.
+    pub fn ring(&self, radius: u32) -> Vec<Hex> {
+        if radius == 0 {
+            return vec![*self];
+        }
+
+        let mut results = Vec::with_capacity(6 * radius as usize);
+        let mut hex = *self + Direction(4).offset() * radius as i32;
+        for direction in Direction::ALL {
+            for _ in 0..radius {
+                results.push(hex);
+                hex = hex.neighbor(direction);
+            }
+        }
+
+        results
+    }
+
+    /// Returns the position of this hexagon after rotating it around `center` by `sixths` sixths
+    /// of a turn. Positive values rotate anticlockwise and negative values clockwise.
+    pub fn rotate_around(&self, center: &Hex, sixths: i32) -> Self {
+        let (mut q, mut r, mut s) = (*self - *center).cube();
+        for _ in 0..sixths.rem_euclid(6) {
+            (q, r, s) = (-s, -q, -r);
+        }
+
+        Hex::from_cube(q, r, s).unwrap() + *center
+    }
+}
+
+impl Add for Hex {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl Sub for Hex {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl Mul<i32> for Hex {
+    type Output = Self;
+
+    fn mul(self, rhs: i32) -> Self {
+        Self::new(self.q * rhs, self.r * rhs)
+    }
+}
+
+impl fmt::Display for Hex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.q, self.r)
+    }
+}
+
+/// Returns an ASCII drawing of the area bounding `tiles`, with '#' for each hexagon in `tiles`
+/// and '.' for every other hexagon. North is at the top. Pointy-topped hexagons are drawn as
+/// rows, with alternate rows offset by one character, and flat-topped hexagons as columns, with
+/// alternate columns offset by one line.
+pub fn render(tiles: &HashSet<Hex>, orientation: Orientation) -> String {
+    let Some(((min_x, min_y), (max_x, max_y))) = tiles
+        .iter()
+        .map(|h| orientation.doubled_position(h))
+        .fold(None, |bounds, (x, y)| {
+            let ((min_x, min_y), (max_x, max_y)) = bounds.unwrap_or(((x, y), (x, y)));
+            Some(((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y))))
+        })
+    else {
+        return String::new();
+    };
+
+    let mut output = String::new();
+    for y in min_y..=max_y {
+        let line: String = (min_x..=max_x)
+            .map(|x| {
+                if (x + y) % 2 != 0 {
+                    ' '
+                } else if tiles.contains(&orientation.hex_at_doubled(x, y)) {
+                    TILE_PRESENT
+                } else {
+                    TILE_ABSENT
+                }
+            })
+            .collect();
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pointy_path(s: &str) -> Vec<Direction> {
+        Orientation::PointyTop.parse_path(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(Hex::ORIGIN.travel(&pointy_path("esew")), Hex::new(0, 1));
+        assert_eq!(Hex::ORIGIN.travel(&pointy_path("nwwswee")), Hex::ORIGIN);
+        assert_eq!(pointy_path(""), vec![]);
+        assert!(Orientation::PointyTop.parse_path("nen").is_err());
+        assert!(Orientation::PointyTop.parse_path("n").is_err());
+        assert!(Orientation::PointyTop.parse_path("ex").is_err());
+
+        let flat = |s| Orientation::FlatTop.parse_path(s).unwrap();
+        assert_eq!(Hex::ORIGIN.travel(&flat("nsse")), Hex::new(1, 0));
+        assert_eq!(Hex::ORIGIN.travel(&flat("nwn")), Hex::new(-1, -1));
+        assert!(Orientation::FlatTop.parse_path("e").is_err());
+    }
+
+    #[test]
+    fn test_direction_names() {
+        for orientation in [Orientation::PointyTop, Orientation::FlatTop] {
+            for d in Direction::ALL {
+                let name = orientation.direction_name(d);
+                assert_eq!(orientation.parse_direction(name), Ok(d));
+
+                let opposite = orientation.direction_name(d.opposite());
+                let flip = |c| match c {
+                    'n' => 's',
+                    's' => 'n',
+                    'e' => 'w',
+                    'w' => 'e',
+                    _ => unreachable!(),
+                };
+                assert_eq!(opposite, name.chars().map(flip).collect::<String>());
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotate_direction() {
+        let d = Orientation::PointyTop.parse_direction("e").unwrap();
+        let name = |d| Orientation::PointyTop.direction_name(d);
+        assert_eq!(name(d.rotate(1)), "ne");
+        assert_eq!(name(d.rotate(-1)), "se");
+        assert_eq!(name(d.rotate(8)), "nw");
+        assert_eq!(d.rotate(6), d);
+        assert_eq!(Direction::from_index(9), Direction::ALL[3]);
+    }
+
+    #[test]
+    fn test_cube() {
+        let hex = Hex::new(3, -5);
+        assert_eq!(hex.cube(), (3, -5, 2));
+        assert_eq!(Hex::from_cube(3, -5, 2), Ok(hex));
+        assert!(Hex::from_cube(3, -5, 1).is_err());
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let hex = Hex::new(2, -1);
+        let neighbors: Vec<Hex> = hex.neighbors().collect();
+        assert_eq!(neighbors.len(), 6);
+        for n in &neighbors {
+            assert_eq!(hex.distance(n), 1);
+        }
+
+        let unique: HashSet<Hex> = neighbors.into_iter().collect();
+        assert_eq!(unique.len(), 6);
+    }
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(Hex::ORIGIN.distance(&Hex::ORIGIN), 0);
+        assert_eq!(Hex::ORIGIN.distance(&Hex::new(3, 0)), 3);
+        assert_eq!(Hex::ORIGIN.distance(&Hex::new(3, -3)), 3);
+        assert_eq!(Hex::new(-2, 1).distance(&Hex::new(2, 1)), 4);
+        assert_eq!(Hex::ORIGIN.distance(&Hex::new(2, 3)), 5);
+        assert_eq!(
+            Hex::ORIGIN.distance(&Hex::ORIGIN.travel(&pointy_path("nenenwnw"))),
+            4
+        );
+    }
+
+    #[test]
+    fn test_ring() {
+        let center = Hex::new(1, 1);
+        assert_eq!(center.ring(0), vec![center]);
+
+        for radius in 1..5 {
+            let ring = center.ring(radius);
+            assert_eq!(ring.len(), 6 * radius as usize);
+            assert_eq!(ring.iter().collect::<HashSet<_>>().len(), ring.len());
+            assert!(ring.iter().all(|h| center.distance(h) == radius));
+        }
+
+        assert_eq!(
+            Hex::ORIGIN.ring(1),
+            vec![
+                Hex::new(-1, 1),
+                Hex::new(0, 1),
+                Hex::new(1, 0),
+                Hex::new(1, -1),
+                Hex::new(0, -1),
+                Hex::new(-1, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rotate_around() {
+        let center = Hex::new(1, -2);
+        let hex = center + Hex::new(2, 0);
+
+        assert_eq!(hex.rotate_around(&center, 1), center + Hex::new(2, -2));
+        assert_eq!(hex.rotate_around(&center, -1), center + Hex::new(0, 2));
+        assert_eq!(hex.rotate_around(&center, 3), center + Hex::new(-2, 0));
+        assert_eq!(hex.rotate_around(&center, 6), hex);
+        assert_eq!(center.rotate_around(&center, 2), center);
+
+        for d in Direction::ALL {
+            assert_eq!(
+                Hex::ORIGIN.neighbor(d).rotate_around(&Hex::ORIGIN, 2),
+                Hex::ORIGIN.neighbor(d.rotate(2))
+            );
+        }
+    }
+
+    #[test]
+    fn test_render_pointy_top() {
+        let tiles: HashSet<Hex> = ["", "e", "se", "nwnw"]
+            .iter()
+            .map(|p| Hex::ORIGIN.travel(&pointy_path(p)))
+            .collect();
+
+        assert_eq!(
+            render(&tiles, Orientation::PointyTop),
+            "\
+# . .
+ . .
+. # #
+ . #
+"
+        );
+    }
+
+    #[test]
+    fn test_render_flat_top() {
+        let path = |s| Orientation::FlatTop.parse_path(s).unwrap();
+        let tiles: HashSet<Hex> = ["", "n", "se", "sese"]
+            .iter()
+            .map(|p| Hex::ORIGIN.travel(&path(p)))
+            .collect();
+
+        assert_eq!(
+            render(&tiles, Orientation::FlatTop),
+            "\
+# .
+ .
+# .
+ #
+. #
+"
+        );
+    }
+
+    #[test]
+    fn test_render_empty() {
+        assert_eq!(render(&HashSet::new(), Orientation::PointyTop), "");
+    }
+}
@@ -0,0 +1,247 @@
+//! A lanternfish population, as described in Advent of Code 2021 Day 6.
+//! https://adventofcode.com/2021/day/6
+//!
+//! Each fish has a timer counting the days until it next spawns. Every day, each timer is reduced
+//! by one, except that a fish whose timer is zero instead spawns a new fish with a timer of
+//! `new_timer`, and restarts its own timer at `reset_timer`. Fish with the same timer behave
+//! identically, so the population is held as the number of fish with each timer value.
+//!
+//! A day is a linear transformation of those counts, so the population after any number of days
+//! is found by raising a `CountMatrix` to that power, with counts that are either exact or
+//! reduced modulo a number.
+
+use crate::big_uint::BigUint;
+use crate::count_matrix::{CountMatrix, CountMode};
+
+pub type Timer = usize;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Population {
+    /// `counts[t]` is the number of fish with a timer of `t`.
+    counts: Vec<BigUint>,
+    reset_timer: Timer,
+    new_timer: Timer,
+}
+
+impl Population {
+    /// Returns a population with no fish, where a fish that spawns restarts its timer at
+    /// `reset_timer`, and the new fish starts with a timer of `new_timer`.
+    pub fn new(reset_timer: Timer, new_timer: Timer) -> Self {
+        Self {
+            counts: vec![BigUint::zero(); reset_timer.max(new_timer) + 1],
+            reset_timer,
+            new_timer,
+        }
+    }
+
+    /// Returns a population with the fish in `input`, which holds their comma-separated timers,
+    /// e.g., "3,4,3,1,2". The timers are as described for `new`.
+    ///
+    /// Returns an error if a timer is not a number, or is larger than both `reset_timer` and
+    /// `new_timer`.
+    pub fn parse(input: &str, reset_timer: Timer, new_timer: Timer) -> Result<Self, String> {
+        let mut population = Self::new(reset_timer, new_timer);
+
+        for timer in input.trim().split(',') {
+            let count = timer
+                .trim()
+                .parse::<Timer>()
+                .ok()
+                .and_then(|timer| population.counts.get_mut(timer))
+                .ok_or_else(|| format!("Invalid fish timer '{}'", timer))?;
+            *count = &*count + &BigUint::from(1);
+        }
+
+        Ok(population)
+    }
+
+    /// Returns the number of fish with each timer value, indexed by the timer.
+    pub fn counts(&self) -> &[BigUint] {
+        &self.counts
+    }
+
+    pub fn total(&self) -> BigUint {
+        self.counts
+            .iter()
+            .fold(BigUint::zero(), |total, count| total + count.clone())
+    }
+
+    /// Returns the matrix that transforms the counts of fish with each timer by one day.
+    fn daily_transition(&self) -> CountMatrix {
+        let mut matrix = CountMatrix::zero(self.counts.len());
+
+        for timer in 1..self.counts.len() {
+            matrix.add(timer - 1, timer, 1);
+        }
+        matrix.add(self.reset_timer, 0, 1);
+        matrix.add(self.new_timer, 0, 1);
+
+        matrix
+    }
+
+    /// Returns the population after `days` days, with counts reduced as `mode` requires.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mode` has a modulus of zero.
+    pub fn after_days(&self, days: u64, mode: CountMode) -> Self {
+        let counts = self
+            .daily_transition()
+            .power(days, mode)
+            .apply(&self.counts, mode);
+
+        Self {
+            counts,
+            ..self.clone()
+        }
+    }
+
+    /// Returns a CSV table of the number of fish with each timer value and in total, on each day
+    /// from day 0 to day `days`, with counts reduced as `mode` requires.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mode` has a modulus of zero.
+    pub fn to_csv(&self, days: u64, mode: CountMode) -> String {
+        let transition = self.daily_transition();
+        let timer_columns: Vec<String> = (0..self.counts.len())
+            .map(|timer| format!("timer_{}", timer))
+            .collect();
+        let mut csv = format!("day,{},total\n", timer_columns.join(","));
+
+        let mut population = Self {
+            counts: self
+                .counts
+                .iter()
+                .map(|count| mode.reduce(count.clone()))
+                .collect(),
+            ..self.clone()
+        };
+        for day in 0..=days {
+            if day > 0 {
+                population.counts = transition.apply(&population.counts, mode);
+            }
+
+            let counts: Vec<String> = population.counts.iter().map(BigUint::to_string).collect();
+            csv += &format!(
+                "{},{},{}\n",
+                day,
+                counts.join(","),
+                mode.reduce(population.total())
+            );
+        }
+
+        csv
+    }
+}
+
+// Test data based on examples on the challenge page.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "3,4,3,1,2";
+
+    fn counts(population: &Population) -> Vec<u64> {
+        population
+            .counts()
+            .iter()
+            .map(|count| count.to_u64().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let population = Population::parse(TEST_INPUT, 6, 8).unwrap();
+        assert_eq!(counts(&population), [0, 1, 1, 2, 1, 0, 0, 0, 0]);
+        assert_eq!(population.total(), BigUint::from(5));
+
+        assert!(Population::parse("3,4,x", 6, 8).is_err());
+        assert!(Population::parse("3,9", 6, 8).is_err());
+        assert!(Population::parse("3,9", 9, 8).is_ok());
+    }
+
+    #[test]
+    fn test_after_days() {
+        let population = Population::parse(TEST_INPUT, 6, 8).unwrap();
+
+        let after = population.after_days(1, CountMode::Exact);
+        assert_eq!(counts(&after), [1, 1, 2, 1, 0, 0, 0, 0, 0]);
+        let after = population.after_days(2, CountMode::Exact);
+        assert_eq!(counts(&after), [1, 2, 1, 0, 0, 0, 1, 0, 1]);
+        let after = population.after_days(18, CountMode::Exact);
+        assert_eq!(counts(&after), [3, 5, 3, 2, 2, 1, 5, 1, 4]);
+
+        assert_eq!(
+            population.after_days(18, CountMode::Exact).total(),
+            BigUint::from(26)
+        );
+        assert_eq!(
+            population.after_days(80, CountMode::Exact).total(),
+            BigUint::from(5934)
+        );
+        assert_eq!(
+            population.after_days(256, CountMode::Exact).total(),
+            BigUint::from(26984457539)
+        );
+    }
+
+    #[test]
+    fn test_after_many_days() {
+        let population = Population::parse(TEST_INPUT, 6, 8).unwrap();
+
+        assert_eq!(
+            population
+                .after_days(1000, CountMode::Exact)
+                .total()
+                .to_string(),
+            "379589061144698259131825683795505058481"
+        );
+
+        let mode = CountMode::Modulo(1_000_000_007);
+        let exact = population.after_days(1000, CountMode::Exact).total();
+        assert_eq!(
+            mode.reduce(population.after_days(1000, mode).total()),
+            mode.reduce(exact)
+        );
+        assert_eq!(
+            mode.reduce(population.after_days(1_000_000_000, mode).total()),
+            BigUint::from(249911329)
+        );
+    }
+
+    #[test]
+    fn test_other_timers() {
+        // With both timers at zero, every fish spawns every day, doubling the population.
+        let population = Population::parse("0,0,0", 0, 0).unwrap();
+        assert_eq!(
+            population.after_days(10, CountMode::Exact).total(),
+            BigUint::from(3 * 1024)
+        );
+
+        // A reset timer longer than the new fish timer.
+        let population = Population::parse("0", 2, 1).unwrap();
+        let after = population.after_days(1, CountMode::Exact);
+        assert_eq!(counts(&after), [0, 1, 1]);
+        let after = population.after_days(2, CountMode::Exact);
+        assert_eq!(counts(&after), [1, 1, 0]);
+        let after = population.after_days(3, CountMode::Exact);
+        assert_eq!(counts(&after), [1, 1, 1]);
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let population = Population::parse(TEST_INPUT, 6, 8).unwrap();
+
+        assert_eq!(
+            population.to_csv(2, CountMode::Exact),
+            "day,timer_0,timer_1,timer_2,timer_3,timer_4,timer_5,timer_6,timer_7,timer_8,total\n\
+             0,0,1,1,2,1,0,0,0,0,5\n\
+             1,1,1,2,1,0,0,0,0,0,5\n\
+             2,1,2,1,0,0,0,1,0,1,6\n"
+        );
+        assert!(population
+            .to_csv(2, CountMode::Modulo(5))
+            .ends_with("2,1,2,1,0,0,0,1,0,1,1\n"));
+    }
+}
@@ -0,0 +1,133 @@
+//! Code shared by more than one challenge. Each challenge is a separate binary in `src/bin`, and
+//! the modules here hold types that several of these binaries build on.
+
+pub mod almanac;
+pub mod assignment;
+pub mod bag_graph;
+pub mod big_uint;
+pub mod camel_cards;
+pub mod cave_paths;
+pub mod conway_cubes;
+pub mod count_matrix;
+pub mod crane;
+pub mod cup_ring;
+pub mod filesystem;
+pub mod hex_grid;
+pub mod lanternfish;
+pub mod memory_game;
+pub mod monkey_business;
+pub mod nested_list;
+pub mod page_ordering;
+pub mod point_cloud;
+pub mod polymer;
+pub mod probe_launch;
+pub mod rope;
+pub mod sand_simulation;
+pub mod segment_display;
+pub mod sensor_coverage;
+pub mod snailfish;
+pub mod valve_planner;
+
+use std::env;
+use std::str::FromStr;
+
+/// The options passed to a challenge binary on the command line. Each is either a flag such as
+/// "--show", or an option followed by a value such as "--days <days>".
+#[derive(Debug, Default, PartialEq)]
+pub struct CommandLine {
+    flags: Vec<String>,
+    values: Vec<(String, String)>,
+}
+
+impl CommandLine {
+    /// Parses the command line arguments `args`, where `flags` lists the options that stand alone
+    /// and `valued` lists the options that take a value.
+    ///
+    /// Returns an error if an option is in neither list, or is missing its value.
+    pub fn from_args(args: &[String], flags: &[&str], valued: &[&str]) -> Result<Self, String> {
+        let mut command_line = Self::default();
+
+        let mut args = args.iter();
+        while let Some(option) = args.next() {
+            if flags.contains(&option.as_str()) {
+                command_line.flags.push(option.clone());
+            } else if valued.contains(&option.as_str()) {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Missing value for option '{}'", option))?;
+                command_line.values.push((option.clone(), value.clone()));
+            } else {
+                return Err(format!("Unknown option '{}'", option));
+            }
+        }
+
+        Ok(command_line)
+    }
+
+    /// As `from_args`, but parses the arguments the program was run with.
+    pub fn from_env(flags: &[&str], valued: &[&str]) -> Result<Self, String> {
+        let args: Vec<String> = env::args().skip(1).collect();
+        Self::from_args(&args, flags, valued)
+    }
+
+    /// Returns whether the flag `flag` was passed.
+    pub fn flag(&self, flag: &str) -> bool {
+        self.flags.iter().any(|f| f == flag)
+    }
+
+    /// Returns the value of option `option`, or `None` if it was not passed. If it was passed more
+    /// than once, the last value is returned.
+    pub fn value(&self, option: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .rev()
+            .find(|(o, _)| o == option)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the value of option `option` parsed as a `T`, or `None` if it was not passed.
+    ///
+    /// Returns an error naming `description` if the value cannot be parsed.
+    pub fn parse<T: FromStr>(&self, option: &str, description: &str) -> Result<Option<T>, String> {
+        self.value(option)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| format!("Invalid {} '{}'", description, value))
+            })
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_command_line() {
+        let command_line =
+            CommandLine::from_args(&args("--days 3 --csv --days 5"), &["--csv"], &["--days"])
+                .unwrap();
+        assert!(command_line.flag("--csv"));
+        assert!(!command_line.flag("--days"));
+        assert_eq!(command_line.value("--days"), Some("5"));
+        assert_eq!(command_line.parse("--days", "number of days"), Ok(Some(5)));
+        assert_eq!(command_line.parse::<u32>("--modulo", "modulus"), Ok(None));
+
+        let command_line = CommandLine::from_args(&args("--days many"), &[], &["--days"]).unwrap();
+        assert!(command_line
+            .parse::<u32>("--days", "number of days")
+            .is_err());
+
+        assert_eq!(
+            CommandLine::from_args(&[], &["--csv"], &["--days"]),
+            Ok(CommandLine::default())
+        );
+        assert!(CommandLine::from_args(&args("--days"), &[], &["--days"]).is_err());
+        assert!(CommandLine::from_args(&args("--csv"), &[], &["--days"]).is_err());
+    }
+}
@@ -0,0 +1,301 @@
+//! The elves' memory game, as described in Advent of Code 2020 Day 15.
+//! https://adventofcode.com/2020/day/15
+//!
+//! The players first speak each of the starting numbers in turn. After that, each turn's number
+//! depends on whether the previous number had been spoken before: if not, the number is 0,
+//! otherwise it is how many turns apart its two most recent utterances were.
+//!
+//! Only the turn each number was last spoken needs to be recorded. Numbers spoken are never
+//! larger than the number of turns played, apart from the starting numbers, so numbers below a
+//! limit are recorded in a `Vec` indexed by number, which is much faster than a `HashMap`. Any
+//! larger numbers are recorded in a `HashMap` instead.
+
+use std::collections::HashMap;
+use std::io::Write;
+
+pub type Number = u32;
+pub type Turn = u32;
+
+/// Returns the comma-separated starting numbers in `input`, e.g., "0,3,6".
+pub fn parse_starting_numbers(input: &str) -> Result<Vec<Number>, String> {
+    let numbers = input
+        .trim()
+        .split(',')
+        .map(|n| {
+            n.trim()
+                .parse()
+                .map_err(|_| format!("Invalid starting number '{}'", n))
+        })
+        .collect::<Result<Vec<Number>, String>>()?;
+
+    Ok(numbers)
+}
+
+/// A game in progress, which is an `Iterator` over the numbers spoken, starting with the starting
+/// numbers.
+#[derive(Clone, Debug)]
+pub struct MemoryGame {
+    starting_numbers: Vec<Number>,
+    /// `dense[n]` is the turn number `n` was last spoken, or 0 if it has not been.
+    dense: Vec<Turn>,
+    /// The turn each number too large for `dense` was last spoken.
+    sparse: HashMap<Number, Turn>,
+    /// The number of turns played so far, where the first turn is 1.
+    turn: Turn,
+    /// The number spoken on the current turn, which is only recorded as spoken when the next
+    /// number is worked out, as that needs the turn it was spoken before this.
+    last_spoken: Option<Number>,
+}
+
+impl MemoryGame {
+    /// Returns a game with the `starting_numbers` passed, recording the turns numbers below
+    /// `dense_limit` were last spoken in a `Vec`. For speed, `dense_limit` should be the number
+    /// of turns that will be played.
+    ///
+    /// Returns an error if there are no starting numbers.
+    pub fn new(starting_numbers: &[Number], dense_limit: usize) -> Result<Self, String> {
+        if starting_numbers.is_empty() {
+            return Err("There must be at least one starting number".to_string());
+        }
+
+        Ok(Self {
+            starting_numbers: starting_numbers.to_vec(),
+            dense: vec![0; dense_limit],
+            sparse: HashMap::new(),
+            turn: 0,
+            last_spoken: None,
+        })
+    }
+
+    /// Returns the number of turns played so far.
+    pub fn turn(&self) -> Turn {
+        self.turn
+    }
+
+    /// Plays until `turn` and returns the number spoken on it, or `None` if that turn has already
+    /// been played.
+    pub fn number_at_turn(&mut self, turn: Turn) -> Option<Number> {
+        if turn <= self.turn {
+            return None;
+        }
+        self.nth((turn - self.turn - 1) as usize)
+    }
+
+    /// Records that `number` was spoken on `turn`, returning the turn it was last spoken before
+    /// that, if any.
+    fn record(&mut self, number: Number, turn: Turn) -> Option<Turn> {
+        let previous = match self.dense.get_mut(number as usize) {
+            Some(last_turn) => std::mem::replace(last_turn, turn),
+            None => self.sparse.insert(number, turn).unwrap_or(0),
+        };
+
+        (previous != 0).then_some(previous)
+    }
+}
+
+impl Iterator for MemoryGame {
+    type Item = Number;
+
+    fn next(&mut self) -> Option<Number> {
+        let number = match self.last_spoken {
+            Some(last) => {
+                let previous = self.record(last, self.turn);
+                match self.starting_numbers.get(self.turn as usize) {
+                    Some(&starting_number) => starting_number,
+                    None => previous.map_or(0, |previous| self.turn - previous),
+                }
+            }
+            None => self.starting_numbers[0],
+        };
+
+        self.turn = self.turn.checked_add(1)?;
+        self.last_spoken = Some(number);
+        Some(number)
+    }
+}
+
+/// Plays a game with `starting_numbers` for `turns` turns, returning the number spoken on the last
+/// turn. If `output` is passed, every number spoken is also written to it, one per line.
+pub fn play(
+    starting_numbers: &[Number],
+    turns: Turn,
+    output: Option<&mut dyn Write>,
+) -> Result<Number, String> {
+    let mut game = MemoryGame::new(starting_numbers, turns as usize)?;
+
+    let last = match output {
+        Some(output) => {
+            let mut last = None;
+            for number in game.by_ref().take(turns as usize) {
+                writeln!(output, "{}", number)
+                    .map_err(|e| format!("Error writing spoken numbers: {}", e))?;
+                last = Some(number);
+            }
+            last
+        }
+        None => game.number_at_turn(turns),
+    };
+
+    last.ok_or_else(|| "The game must be played for at least one turn".to_string())
+}
+
+/// The options for running the game from the command line.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RunOptions {
+    /// The starting numbers given instead of those in the input file.
+    pub starting_numbers: Option<Vec<Number>>,
+    pub turns: Turn,
+    /// The file to write every number spoken to, one per line.
+    pub output: Option<String>,
+}
+
+impl RunOptions {
+    /// Parses the command line arguments `args`, which may contain "--start <numbers>", "--turns
+    /// <turns>" and "--output <file>". The number of turns defaults to `default_turns`.
+    pub fn from_args(args: &[String], default_turns: Turn) -> Result<Self, String> {
+        let mut options = Self {
+            starting_numbers: None,
+            turns: default_turns,
+            output: None,
+        };
+
+        let mut args = args.iter();
+        while let Some(option) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("Missing value for option '{}'", option))?;
+
+            match option.as_str() {
+                "--start" => options.starting_numbers = Some(parse_starting_numbers(value)?),
+                "--turns" => {
+                    options.turns = value
+                        .parse()
+                        .ok()
+                        .filter(|&turns| turns > 0)
+                        .ok_or_else(|| format!("Invalid number of turns '{}'", value))?
+                }
+                "--output" => options.output = Some(value.clone()),
+                _ => return Err(format!("Unknown option '{}'", option)),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+// Test data based on examples on the challenge page.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn number_at_turn(starting_numbers: &[Number], turn: Turn, dense_limit: usize) -> Number {
+        MemoryGame::new(starting_numbers, dense_limit)
+            .unwrap()
+            .number_at_turn(turn)
+            .unwrap()
+    }
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_starting_numbers() {
+        assert_eq!(parse_starting_numbers("0,3,6\n"), Ok(vec![0, 3, 6]));
+        assert!(parse_starting_numbers("0,x,6").is_err());
+        assert!(parse_starting_numbers("").is_err());
+        assert!(parse_starting_numbers("0,-3").is_err());
+    }
+
+    #[test]
+    fn test_sequence() {
+        let game = MemoryGame::new(&[0, 3, 6], 10).unwrap();
+        assert_eq!(
+            game.take(10).collect::<Vec<_>>(),
+            vec![0, 3, 6, 0, 3, 3, 1, 0, 4, 0]
+        );
+
+        let game = MemoryGame::new(&[7, 7, 7], 10).unwrap();
+        assert_eq!(game.take(6).collect::<Vec<_>>(), vec![7, 7, 7, 1, 0, 0]);
+
+        assert_eq!(number_at_turn(&[1, 7, 8, 9, 1], 6, 10), 4);
+        assert_eq!(number_at_turn(&[33, 33, 29, 78, 1], 6, 10), 0);
+        assert_eq!(number_at_turn(&[4, 0, 9, 3], 6, 10), 3);
+    }
+
+    #[test]
+    fn test_number_at_turn() {
+        for (starting_numbers, expected) in [
+            ([0, 3, 6], 436),
+            ([1, 3, 2], 1),
+            ([2, 1, 3], 10),
+            ([1, 2, 3], 27),
+            ([2, 3, 1], 78),
+            ([3, 2, 1], 438),
+            ([3, 1, 2], 1836),
+        ] {
+            assert_eq!(number_at_turn(&starting_numbers, 2020, 2020), expected);
+        }
+
+        let mut game = MemoryGame::new(&[0, 3, 6], 2020).unwrap();
+        assert_eq!(game.number_at_turn(4), Some(0));
+        assert_eq!(game.turn(), 4);
+        assert_eq!(game.number_at_turn(4), None);
+        assert_eq!(game.number_at_turn(10), Some(0));
+    }
+
+    #[test]
+    fn test_sparse_store() {
+        // With no dense store every number is recorded in the `HashMap`, and with a small one
+        // the store used changes as the numbers grow. Both must agree with the dense store.
+        for dense_limit in [0, 5, 100] {
+            let game = MemoryGame::new(&[0, 3, 6], dense_limit).unwrap();
+            let expected = MemoryGame::new(&[0, 3, 6], 2020).unwrap();
+            assert!(game.take(2020).eq(expected.take(2020)));
+        }
+
+        assert_eq!(number_at_turn(&[1_000_000, 5, 1_000_000], 4, 10), 2);
+    }
+
+    #[test]
+    fn test_no_starting_numbers() {
+        assert!(MemoryGame::new(&[], 10).is_err());
+    }
+
+    #[test]
+    fn test_play() {
+        assert_eq!(play(&[0, 3, 6], 2020, None), Ok(436));
+        assert!(play(&[0, 3, 6], 0, None).is_err());
+
+        let mut output = Vec::new();
+        assert_eq!(play(&[0, 3, 6], 10, Some(&mut output)), Ok(0));
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "0\n3\n6\n0\n3\n3\n1\n0\n4\n0\n"
+        );
+    }
+
+    #[test]
+    fn test_run_options() {
+        assert_eq!(
+            RunOptions::from_args(&[], 2020),
+            Ok(RunOptions {
+                starting_numbers: None,
+                turns: 2020,
+                output: None
+            })
+        );
+        assert_eq!(
+            RunOptions::from_args(&args("--turns 10 --start 0,3,6 --output out.txt"), 2020),
+            Ok(RunOptions {
+                starting_numbers: Some(vec![0, 3, 6]),
+                turns: 10,
+                output: Some("out.txt".to_string())
+            })
+        );
+        assert!(RunOptions::from_args(&args("--turns"), 2020).is_err());
+        assert!(RunOptions::from_args(&args("--turns 0"), 2020).is_err());
+        assert!(RunOptions::from_args(&args("--start 1,x"), 2020).is_err());
+        assert!(RunOptions::from_args(&args("--colour blue"), 2020).is_err());
+    }
+}
@@ -0,0 +1,854 @@
+//! Simulating monkeys throwing items to each other, as described in Advent of Code 2022 Day 11.
+//! https://adventofcode.com/2022/day/11
+//!
+//! Each monkey inspects the items it holds in turn, which changes the "worry level" of the item
+//! according to the monkey's operation, an arithmetic expression over the old worry level. Some
+//! worry relief is then applied, and the item is thrown to one of two monkeys depending on whether
+//! its worry level is divisible by the monkey's test divisor. A round is every monkey taking its
+//! turn, starting with monkey 0.
+//!
+//! How worry levels are relieved is chosen when simulating, which also decides how worry levels
+//! are held:
+//!     - dividing by a fixed value after each inspection, as in part 1 of the challenge, holds
+//!       them in 64 bits and fails if they overflow.
+//!     - taking them modulo the least common multiple of all the test divisors, as in part 2,
+//!       keeps them small without changing the outcome of any test.
+//!     - applying no relief at all holds them as arbitrary-precision integers, which grow quickly,
+//!       so this is only practical for a few rounds.
+
+use crate::big_uint::BigUint;
+use std::iter::Peekable;
+use std::mem;
+use std::str::Chars;
+use std::str::FromStr;
+
+const INPUT_TOKEN_MONKEY: &str = "Monkey ";
+const INPUT_TOKEN_ITEMS: &str = "Starting items: ";
+const INPUT_TOKEN_OPERATION: &str = "Operation: new = ";
+const INPUT_TOKEN_TEST: &str = "Test: divisible by ";
+const INPUT_TOKEN_IF_TRUE: &str = "If true: throw to monkey ";
+const INPUT_TOKEN_IF_FALSE: &str = "If false: throw to monkey ";
+
+pub type WorryLevel = u64;
+pub type MonkeyId = usize;
+
+/// An arithmetic expression over the variable `old`, built from non-negative integers, `+`, `-`,
+/// `*`, `/` and parentheses, with the usual precedence. Division discards any remainder.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expression {
+    Old,
+    Number(WorryLevel),
+    Add(Box<Expression>, Box<Expression>),
+    Subtract(Box<Expression>, Box<Expression>),
+    Multiply(Box<Expression>, Box<Expression>),
+    Divide(Box<Expression>, Box<Expression>),
+}
+
+impl Expression {
+    /// Returns the value of this expression when `old` is `old`, using 64-bit arithmetic.
+    ///
+    /// Returns an error if the result, or any intermediate value, overflows or is negative, or if
+    /// there is a division by zero.
+    pub fn evaluate(&self, old: WorryLevel) -> Result<WorryLevel, String> {
+        self.evaluate_with(&old, &CheckedArithmetic(1))
+    }
+
+    /// Returns the value of this expression in the arithmetic passed.
+    fn evaluate_with<A: Arithmetic>(
+        &self,
+        old: &A::Value,
+        arithmetic: &A,
+    ) -> Result<A::Value, String> {
+        let operands = |a: &Self, b: &Self| -> Result<_, String> {
+            Ok((
+                a.evaluate_with(old, arithmetic)?,
+                b.evaluate_with(old, arithmetic)?,
+            ))
+        };
+
+        match self {
+            Self::Old => Ok(old.clone()),
+            Self::Number(n) => Ok(arithmetic.constant(*n)),
+            Self::Add(a, b) => {
+                let (a, b) = operands(a, b)?;
+                arithmetic.add(&a, &b)
+            }
+            Self::Subtract(a, b) => {
+                let (a, b) = operands(a, b)?;
+                arithmetic.subtract(&a, &b)
+            }
+            Self::Multiply(a, b) => {
+                let (a, b) = operands(a, b)?;
+                arithmetic.multiply(&a, &b)
+            }
+            Self::Divide(a, b) => {
+                let (a, b) = operands(a, b)?;
+                arithmetic.divide(&a, &b)
+            }
+        }
+    }
+
+    /// Parses a sum or difference of terms.
+    fn parse_sum(chars: &mut Peekable<Chars>) -> Result<Self, String> {
+        let mut expression = Self::parse_product(chars)?;
+
+        while let Some(operator) = next_non_space_if(chars, |c| c == '+' || c == '-') {
+            let right = Box::new(Self::parse_product(chars)?);
+            let left = Box::new(expression);
+            expression = if operator == '+' {
+                Self::Add(left, right)
+            } else {
+                Self::Subtract(left, right)
+            };
+        }
+
+        Ok(expression)
+    }
+
+    /// Parses a product or quotient of factors.
+    fn parse_product(chars: &mut Peekable<Chars>) -> Result<Self, String> {
+        let mut expression = Self::parse_factor(chars)?;
+
+        while let Some(operator) = next_non_space_if(chars, |c| c == '*' || c == '/') {
+            let right = Box::new(Self::parse_factor(chars)?);
+            let left = Box::new(expression);
+            expression = if operator == '*' {
+                Self::Multiply(left, right)
+            } else {
+                Self::Divide(left, right)
+            };
+        }
+
+        Ok(expression)
+    }
+
+    /// Parses `old`, a number, or an expression in parentheses.
+    fn parse_factor(chars: &mut Peekable<Chars>) -> Result<Self, String> {
+        if next_non_space_if(chars, |c| c == '(').is_some() {
+            let expression = Self::parse_sum(chars)?;
+            return match next_non_space_if(chars, |c| c == ')') {
+                Some(_) => Ok(expression),
+                None => Err("Expected ')' in expression".to_string()),
+            };
+        }
+
+        let mut word = String::new();
+        while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric()) {
+            word.push(c);
+        }
+
+        if word == "old" {
+            Ok(Self::Old)
+        } else if !word.is_empty() && word.bytes().all(|b| b.is_ascii_digit()) {
+            word.parse()
+                .map(Self::Number)
+                .map_err(|e| format!("Invalid number '{}' in expression: {}", word, e))
+        } else if word.is_empty() {
+            match chars.peek() {
+                Some(c) => Err(format!("Unexpected '{}' in expression", c)),
+                None => Err("Expression ended unexpectedly".to_string()),
+            }
+        } else {
+            Err(format!("Unknown term '{}' in expression", word))
+        }
+    }
+
+    fn contains_division(&self) -> bool {
+        match self {
+            Self::Old | Self::Number(_) => false,
+            Self::Divide(..) => true,
+            Self::Add(a, b) | Self::Subtract(a, b) | Self::Multiply(a, b) => {
+                a.contains_division() || b.contains_division()
+            }
+        }
+    }
+}
+
+/// Skips any spaces, then consumes and returns the next character if it satisfies `predicate`.
+fn next_non_space_if(
+    chars: &mut Peekable<Chars>,
+    predicate: impl Fn(char) -> bool,
+) -> Option<char> {
+    while chars.next_if_eq(&' ').is_some() {}
+    chars.next_if(|&c| predicate(c))
+}
+
+impl FromStr for Expression {
+    type Err = String;
+
+    /// Parses an expression such as "old * (old + 3) / 2".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars().peekable();
+        let expression = Self::parse_sum(&mut chars)?;
+
+        match next_non_space_if(&mut chars, |_| true) {
+            Some(c) => Err(format!("Unexpected '{}' in expression '{}'", c, s)),
+            None => Ok(expression),
+        }
+    }
+}
+
+/// How an item's worry level is relieved after each inspection.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WorryRelief {
+    /// The worry level is divided by the value, discarding any remainder.
+    DivideBy(WorryLevel),
+    /// The worry level is taken modulo the least common multiple of all the monkeys' test
+    /// divisors. Division cannot be combined with this, so operations must not contain it.
+    ModuloLcm,
+    /// The worry level is left as it is, and can grow without limit.
+    Unbounded,
+}
+
+/// The arithmetic used for worry levels, which differs depending on the `WorryRelief` chosen.
+trait Arithmetic {
+    type Value: Clone + ToString;
+
+    fn constant(&self, n: WorryLevel) -> Self::Value;
+    fn add(&self, a: &Self::Value, b: &Self::Value) -> Result<Self::Value, String>;
+    fn subtract(&self, a: &Self::Value, b: &Self::Value) -> Result<Self::Value, String>;
+    fn multiply(&self, a: &Self::Value, b: &Self::Value) -> Result<Self::Value, String>;
+    fn divide(&self, a: &Self::Value, b: &Self::Value) -> Result<Self::Value, String>;
+    fn is_divisible_by(&self, value: &Self::Value, divisor: WorryLevel) -> bool;
+
+    /// Returns `value` after worry relief is applied.
+    fn relieve(&self, value: Self::Value) -> Self::Value;
+}
+
+/// 64-bit arithmetic that fails on overflow, with the worry level divided by the divisor held
+/// after each inspection.
+struct CheckedArithmetic(WorryLevel);
+
+impl CheckedArithmetic {
+    fn checked(
+        result: Option<WorryLevel>,
+        operation: &str,
+        a: WorryLevel,
+        b: WorryLevel,
+    ) -> Result<WorryLevel, String> {
+        result.ok_or_else(|| {
+            format!(
+                "Cannot calculate {} {} {} as a worry level",
+                a, operation, b
+            )
+        })
+    }
+}
+
+impl Arithmetic for CheckedArithmetic {
+    type Value = WorryLevel;
+
+    fn constant(&self, n: WorryLevel) -> WorryLevel {
+        n
+    }
+
+    fn add(&self, a: &WorryLevel, b: &WorryLevel) -> Result<WorryLevel, String> {
+        Self::checked(a.checked_add(*b), "+", *a, *b)
+    }
+
+    fn subtract(&self, a: &WorryLevel, b: &WorryLevel) -> Result<WorryLevel, String> {
+        Self::checked(a.checked_sub(*b), "-", *a, *b)
+    }
+
+    fn multiply(&self, a: &WorryLevel, b: &WorryLevel) -> Result<WorryLevel, String> {
+        Self::checked(a.checked_mul(*b), "*", *a, *b)
+    }
+
+    fn divide(&self, a: &WorryLevel, b: &WorryLevel) -> Result<WorryLevel, String> {
+        Self::checked(a.checked_div(*b), "/", *a, *b)
+    }
+
+    fn is_divisible_by(&self, value: &WorryLevel, divisor: WorryLevel) -> bool {
+        value.is_multiple_of(divisor)
+    }
+
+    fn relieve(&self, value: WorryLevel) -> WorryLevel {
+        value / self.0
+    }
+}
+
+/// Arithmetic modulo the modulus held. A subtraction whose true result would be negative cannot
+/// be detected, and wraps around the modulus.
+struct ModularArithmetic(WorryLevel);
+
+impl ModularArithmetic {
+    fn reduce(&self, value: u128) -> WorryLevel {
+        (value % u128::from(self.0)) as WorryLevel
+    }
+}
+
+impl Arithmetic for ModularArithmetic {
+    type Value = WorryLevel;
+
+    fn constant(&self, n: WorryLevel) -> WorryLevel {
+        n % self.0
+    }
+
+    fn add(&self, a: &WorryLevel, b: &WorryLevel) -> Result<WorryLevel, String> {
+        Ok(self.reduce(u128::from(*a) + u128::from(*b)))
+    }
+
+    fn subtract(&self, a: &WorryLevel, b: &WorryLevel) -> Result<WorryLevel, String> {
+        Ok(self.reduce(u128::from(*a) + u128::from(self.0) - u128::from(*b)))
+    }
+
+    fn multiply(&self, a: &WorryLevel, b: &WorryLevel) -> Result<WorryLevel, String> {
+        Ok(self.reduce(u128::from(*a) * u128::from(*b)))
+    }
+
+    fn divide(&self, _: &WorryLevel, _: &WorryLevel) -> Result<WorryLevel, String> {
+        Err("Division cannot be combined with modulo worry relief".to_string())
+    }
+
+    fn is_divisible_by(&self, value: &WorryLevel, divisor: WorryLevel) -> bool {
+        value.is_multiple_of(divisor)
+    }
+
+    fn relieve(&self, value: WorryLevel) -> WorryLevel {
+        value
+    }
+}
+
+/// Arbitrary-precision arithmetic. Division is only supported by values that fit in 64 bits.
+struct UnboundedArithmetic;
+
+impl Arithmetic for UnboundedArithmetic {
+    type Value = BigUint;
+
+    fn constant(&self, n: WorryLevel) -> BigUint {
+        n.into()
+    }
+
+    fn add(&self, a: &BigUint, b: &BigUint) -> Result<BigUint, String> {
+        Ok(a + b)
+    }
+
+    fn subtract(&self, a: &BigUint, b: &BigUint) -> Result<BigUint, String> {
+        a.checked_sub(b)
+            .ok_or_else(|| format!("Cannot calculate {} - {} as a worry level", a, b))
+    }
+
+    fn multiply(&self, a: &BigUint, b: &BigUint) -> Result<BigUint, String> {
+        Ok(a * b)
+    }
+
+    fn divide(&self, a: &BigUint, b: &BigUint) -> Result<BigUint, String> {
+        b.to_u64()
+            .and_then(|divisor| a.div_rem_u64(divisor))
+            .map(|(quotient, _)| quotient)
+            .ok_or_else(|| format!("Cannot calculate {} / {} as a worry level", a, b))
+    }
+
+    fn is_divisible_by(&self, value: &BigUint, divisor: WorryLevel) -> bool {
+        value.rem_u64(divisor) == 0
+    }
+
+    fn relieve(&self, value: BigUint) -> BigUint {
+        value
+    }
+}
+
+/// A single monkey, with the worry levels of the items it starts with, its operation, and the
+/// test that decides which monkey it throws each item to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Monkey {
+    pub items: Vec<WorryLevel>,
+    pub operation: Expression,
+    pub divisible_by: WorryLevel,
+    pub if_true: MonkeyId,
+    pub if_false: MonkeyId,
+}
+
+/// Returns the text following `prefix` in `line`, ignoring any indentation.
+fn strip_token<'a>(line: Option<&'a str>, prefix: &str) -> Result<&'a str, String> {
+    let line = line.ok_or_else(|| format!("Missing line starting '{}'", prefix))?;
+
+    line.trim()
+        .strip_prefix(prefix)
+        .ok_or_else(|| format!("Expected line starting '{}', found '{}'", prefix, line))
+}
+
+fn parse_number<T: FromStr>(s: &str) -> Result<T, String> {
+    s.parse().map_err(|_| format!("Invalid number '{}'", s))
+}
+
+impl Monkey {
+    /// Parses a 6-line block of the form below, returning the monkey and its Id.
+    ///     Monkey 0:
+    ///       Starting items: 79, 98
+    ///       Operation: new = old * 19
+    ///       Test: divisible by 23
+    ///         If true: throw to monkey 2
+    ///         If false: throw to monkey 3
+    fn parse_block(block: &str) -> Result<(MonkeyId, Self), String> {
+        let mut lines = block.lines().filter(|line| !line.trim().is_empty());
+
+        let id = strip_token(lines.next(), INPUT_TOKEN_MONKEY)?;
+        let id = parse_number(
+            id.strip_suffix(':')
+                .ok_or_else(|| format!("Expected ':' after monkey Id {}", id))?,
+        )?;
+
+        let items = strip_token(lines.next(), INPUT_TOKEN_ITEMS)?;
+        let items = if items.is_empty() {
+            Vec::new()
+        } else {
+            items
+                .split(',')
+                .map(|item| parse_number(item.trim()))
+                .collect::<Result<_, _>>()?
+        };
+
+        let operation = strip_token(lines.next(), INPUT_TOKEN_OPERATION)?.parse()?;
+
+        let divisible_by = parse_number(strip_token(lines.next(), INPUT_TOKEN_TEST)?)?;
+        if divisible_by == 0 {
+            return Err(format!("Monkey {} cannot test for divisibility by 0", id));
+        }
+
+        let if_true = parse_number(strip_token(lines.next(), INPUT_TOKEN_IF_TRUE)?)?;
+        let if_false = parse_number(strip_token(lines.next(), INPUT_TOKEN_IF_FALSE)?)?;
+
+        if let Some(extra) = lines.next() {
+            return Err(format!("Unexpected line '{}' for monkey {}", extra, id));
+        }
+
+        Ok((
+            id,
+            Self {
+                items,
+                operation,
+                divisible_by,
+                if_true,
+                if_false,
+            },
+        ))
+    }
+}
+
+/// The entire group of monkeys, where the `Vec` index is each monkey's Id.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonkeyGroup {
+    pub monkeys: Vec<Monkey>,
+}
+
+impl FromStr for MonkeyGroup {
+    type Err = String;
+
+    /// Parses blocks of the form described in `Monkey::parse_block`, separated by blank lines.
+    /// The monkeys must be numbered in order from 0, and only throw to monkeys in the group.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut monkeys = Vec::new();
+
+        for block in s.split("\n\n").filter(|block| !block.trim().is_empty()) {
+            let (id, monkey) = Monkey::parse_block(block)?;
+            if id != monkeys.len() {
+                return Err(format!(
+                    "Monkey {} found where monkey {} was expected",
+                    id,
+                    monkeys.len()
+                ));
+            }
+            monkeys.push(monkey);
+        }
+
+        for (id, monkey) in monkeys.iter().enumerate() {
+            if monkey.if_true >= monkeys.len() || monkey.if_false >= monkeys.len() {
+                return Err(format!(
+                    "Monkey {} throws to a monkey that does not exist",
+                    id
+                ));
+            }
+        }
+
+        Ok(Self { monkeys })
+    }
+}
+
+impl MonkeyGroup {
+    /// Returns the least common multiple of all the monkeys' test divisors. Taking worry levels
+    /// modulo this does not change the outcome of any test.
+    ///
+    /// Returns an error if the least common multiple overflows.
+    pub fn divisor_lcm(&self) -> Result<WorryLevel, String> {
+        self.monkeys.iter().try_fold(1, |lcm: WorryLevel, monkey| {
+            let mut a = lcm;
+            let mut b = monkey.divisible_by;
+            while b != 0 {
+                (a, b) = (b, a % b);
+            }
+            (lcm / a).checked_mul(monkey.divisible_by).ok_or_else(|| {
+                "The least common multiple of the test divisors overflows".to_string()
+            })
+        })
+    }
+
+    /// Plays the given number of `rounds`, starting with the items the monkeys start with, and
+    /// applying `relief` after every inspection.
+    ///
+    /// Returns an error if a worry level cannot be calculated, e.g., because it overflows or
+    /// because an operation contains a division and `relief` is `WorryRelief::ModuloLcm`.
+    pub fn simulate(&self, relief: WorryRelief, rounds: usize) -> Result<Simulation, String> {
+        match relief {
+            WorryRelief::DivideBy(0) => Err("Cannot relieve worry by dividing by 0".to_string()),
+            WorryRelief::DivideBy(divisor) => self.play(&CheckedArithmetic(divisor), rounds),
+            WorryRelief::ModuloLcm => {
+                if self.monkeys.iter().any(|m| m.operation.contains_division()) {
+                    return Err("Division cannot be combined with modulo worry relief".to_string());
+                }
+                self.play(&ModularArithmetic(self.divisor_lcm()?), rounds)
+            }
+            WorryRelief::Unbounded => self.play(&UnboundedArithmetic, rounds),
+        }
+    }
+
+    fn play<A: Arithmetic>(&self, arithmetic: &A, rounds: usize) -> Result<Simulation, String> {
+        let mut items: Vec<Vec<A::Value>> = self
+            .monkeys
+            .iter()
+            .map(|m| m.items.iter().map(|&i| arithmetic.constant(i)).collect())
+            .collect();
+        let mut inspections = Vec::with_capacity(rounds);
+
+        for _ in 0..rounds {
+            let mut round_inspections = Vec::with_capacity(self.monkeys.len());
+
+            for (id, monkey) in self.monkeys.iter().enumerate() {
+                let held = mem::take(&mut items[id]);
+                round_inspections.push(held.len());
+
+                for item in held {
+                    let worry = monkey.operation.evaluate_with(&item, arithmetic)?;
+                    let worry = arithmetic.relieve(worry);
+                    let target = if arithmetic.is_divisible_by(&worry, monkey.divisible_by) {
+                        monkey.if_true
+                    } else {
+                        monkey.if_false
+                    };
+                    items[target].push(worry);
+                }
+            }
+
+            inspections.push(round_inspections);
+        }
+
+        Ok(Simulation {
+            inspections,
+            items: items
+                .iter()
+                .map(|held| held.iter().map(|item| item.to_string()).collect())
+                .collect(),
+        })
+    }
+}
+
+/// The outcome of `MonkeyGroup::simulate`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Simulation {
+    /// `inspections[r][m]` is the number of items monkey `m` inspected in round `r + 1`.
+    inspections: Vec<Vec<usize>>,
+    /// The decimal worry levels of the items each monkey holds after the final round.
+    items: Vec<Vec<String>>,
+}
+
+impl Simulation {
+    /// Returns the number of items each monkey inspected in `round`, where the first round is 1.
+    pub fn round_inspections(&self, round: usize) -> Option<&[usize]> {
+        round
+            .checked_sub(1)
+            .and_then(|r| self.inspections.get(r))
+            .map(Vec::as_slice)
+    }
+
+    /// Returns the total number of items each monkey inspected over all the rounds.
+    pub fn total_inspections(&self) -> Vec<usize> {
+        let mut totals = vec![0; self.items.len()];
+        for round in &self.inspections {
+            for (total, count) in totals.iter_mut().zip(round) {
+                *total += count;
+            }
+        }
+        totals
+    }
+
+    /// Returns the decimal worry levels of the items each monkey holds after the final round.
+    pub fn items(&self) -> &[Vec<String>] {
+        &self.items
+    }
+
+    /// Returns the "level of monkey business", which is the product of the two highest total
+    /// inspection counts.
+    pub fn monkey_business(&self) -> usize {
+        let mut totals = self.total_inspections();
+        totals.sort_unstable_by(|a, b| b.cmp(a));
+        totals.iter().take(2).product()
+    }
+
+    /// Returns the inspection counts as CSV, with a header line and then one line per round and
+    /// monkey, giving the count in that round and the running total.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("round,monkey,inspections,total_inspections\n");
+        let mut totals = vec![0; self.items.len()];
+
+        for (r, round) in self.inspections.iter().enumerate() {
+            for (monkey, count) in round.iter().enumerate() {
+                totals[monkey] += count;
+                csv += &format!("{},{},{},{}\n", r + 1, monkey, count, totals[monkey]);
+            }
+        }
+
+        csv
+    }
+}
+
+// Test data based on examples on the challenge page.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "\
+Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1
+";
+
+    fn test_group() -> MonkeyGroup {
+        TEST_INPUT.parse().unwrap()
+    }
+
+    fn expression(s: &str) -> Expression {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_expression() {
+        use Expression::*;
+
+        assert_eq!(
+            expression("old * 19"),
+            Multiply(Box::new(Old), Box::new(Number(19)))
+        );
+        assert_eq!(
+            expression("old+old*2"),
+            Add(
+                Box::new(Old),
+                Box::new(Multiply(Box::new(Old), Box::new(Number(2))))
+            )
+        );
+        assert_eq!(
+            expression("10 - old - 1"),
+            Subtract(
+                Box::new(Subtract(Box::new(Number(10)), Box::new(Old))),
+                Box::new(Number(1))
+            )
+        );
+
+        assert!("".parse::<Expression>().is_err());
+        assert!("old +".parse::<Expression>().is_err());
+        assert!("(old + 1".parse::<Expression>().is_err());
+        assert!("old ^ 2".parse::<Expression>().is_err());
+        assert!("new * 2".parse::<Expression>().is_err());
+        assert!("old 2".parse::<Expression>().is_err());
+    }
+
+    #[test]
+    fn test_evaluate_expression() {
+        assert_eq!(expression("old * 19").evaluate(79), Ok(1501));
+        assert_eq!(expression("old * old").evaluate(79), Ok(6241));
+        assert_eq!(expression("2 + 3 * old").evaluate(4), Ok(14));
+        assert_eq!(expression("(2 + 3) * old").evaluate(4), Ok(20));
+        assert_eq!(expression("old * (old + 3) / 2").evaluate(5), Ok(20));
+        assert_eq!(expression("old - 4 - 3").evaluate(10), Ok(3));
+        assert_eq!(expression(" ( old ) ").evaluate(10), Ok(10));
+
+        assert!(expression("old - 11").evaluate(10).is_err());
+        assert!(expression("old / (old - 10)").evaluate(10).is_err());
+        assert!(expression("old * old").evaluate(u64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_parse_group() {
+        let group = test_group();
+
+        assert_eq!(group.monkeys.len(), 4);
+        assert_eq!(
+            group.monkeys[0],
+            Monkey {
+                items: vec![79, 98],
+                operation: expression("old * 19"),
+                divisible_by: 23,
+                if_true: 2,
+                if_false: 3,
+            }
+        );
+        assert_eq!(group.monkeys[1].items, vec![54, 65, 75, 74]);
+        assert_eq!(group.monkeys[2].operation, expression("old * old"));
+        assert_eq!(group.monkeys[3].divisible_by, 17);
+        assert_eq!(group.divisor_lcm(), Ok(23 * 19 * 13 * 17));
+    }
+
+    #[test]
+    fn test_parse_group_errors() {
+        assert!(TEST_INPUT
+            .replace("Monkey 1:", "Monkey 2:")
+            .parse::<MonkeyGroup>()
+            .is_err());
+        assert!(TEST_INPUT
+            .replace("monkey 3", "monkey 4")
+            .parse::<MonkeyGroup>()
+            .is_err());
+        assert!(TEST_INPUT
+            .replace("by 23", "by 0")
+            .parse::<MonkeyGroup>()
+            .is_err());
+        assert!(TEST_INPUT
+            .replace("old + 6", "old +")
+            .parse::<MonkeyGroup>()
+            .is_err());
+        assert!(TEST_INPUT
+            .replace("79, 98", "79, x")
+            .parse::<MonkeyGroup>()
+            .is_err());
+        assert!(TEST_INPUT
+            .replace("    If false: throw to monkey 0\n", "")
+            .parse::<MonkeyGroup>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_divisor_lcm() {
+        let input = TEST_INPUT
+            .replace("by 23", "by 26")
+            .replace("by 19", "by 4");
+        assert_eq!(
+            input.parse::<MonkeyGroup>().unwrap().divisor_lcm(),
+            Ok(26 * 2 * 17)
+        );
+
+        let group = TEST_INPUT
+            .replace("by 23", "by 4294967291")
+            .replace("by 19", "by 4294967279")
+            .parse::<MonkeyGroup>()
+            .unwrap();
+        assert!(group.divisor_lcm().is_err());
+        assert!(group.simulate(WorryRelief::ModuloLcm, 1).is_err());
+    }
+
+    #[test]
+    fn test_divide_by_three() {
+        let group = test_group();
+
+        let simulation = group.simulate(WorryRelief::DivideBy(3), 1).unwrap();
+        assert_eq!(simulation.round_inspections(1), Some(&[2, 4, 3, 5][..]));
+        assert_eq!(
+            simulation.items(),
+            [
+                vec!["20", "23", "27", "26"],
+                vec!["2080", "25", "167", "207", "401", "1046"],
+                vec![],
+                vec![]
+            ]
+        );
+
+        let simulation = group.simulate(WorryRelief::DivideBy(3), 20).unwrap();
+        assert_eq!(
+            simulation.items(),
+            [
+                vec!["10", "12", "14", "26", "34"],
+                vec!["245", "93", "53", "199", "115"],
+                vec![],
+                vec![]
+            ]
+        );
+        assert_eq!(simulation.total_inspections(), vec![101, 95, 7, 105]);
+        assert_eq!(simulation.monkey_business(), 10605);
+    }
+
+    #[test]
+    fn test_modulo_lcm() {
+        let group = test_group();
+
+        let simulation = group.simulate(WorryRelief::ModuloLcm, 1).unwrap();
+        assert_eq!(simulation.total_inspections(), vec![2, 4, 3, 6]);
+
+        let simulation = group.simulate(WorryRelief::ModuloLcm, 20).unwrap();
+        assert_eq!(simulation.total_inspections(), vec![99, 97, 8, 103]);
+
+        let simulation = group.simulate(WorryRelief::ModuloLcm, 10000).unwrap();
+        assert_eq!(
+            simulation.total_inspections(),
+            vec![52166, 47830, 1938, 52013]
+        );
+        assert_eq!(simulation.monkey_business(), 2713310158);
+    }
+
+    #[test]
+    fn test_unbounded_matches_modulo_lcm() {
+        let group = test_group();
+        let unbounded = group.simulate(WorryRelief::Unbounded, 20).unwrap();
+        let modulo = group.simulate(WorryRelief::ModuloLcm, 20).unwrap();
+
+        assert_eq!(unbounded.total_inspections(), vec![99, 97, 8, 103]);
+        assert_eq!(unbounded.to_csv(), modulo.to_csv());
+        assert_ne!(unbounded.items(), modulo.items());
+    }
+
+    #[test]
+    fn test_relief_errors() {
+        let group = test_group();
+        assert!(group.simulate(WorryRelief::DivideBy(0), 1).is_err());
+        assert!(group.simulate(WorryRelief::DivideBy(1), 20).is_err());
+
+        let input = TEST_INPUT.replace("old + 6", "old / 2 + 6");
+        let group: MonkeyGroup = input.parse().unwrap();
+        assert!(group.simulate(WorryRelief::ModuloLcm, 1).is_err());
+        assert!(group.simulate(WorryRelief::Unbounded, 1).is_ok());
+        assert!(group.simulate(WorryRelief::DivideBy(3), 1).is_ok());
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let simulation = test_group().simulate(WorryRelief::DivideBy(3), 2).unwrap();
+
+        assert_eq!(
+            simulation.to_csv(),
+            "\
+round,monkey,inspections,total_inspections
+1,0,2,2
+1,1,4,4
+1,2,3,3
+1,3,5,5
+2,0,4,6
+2,1,6,10
+2,2,1,4
+2,3,5,10
+"
+        );
+        assert_eq!(simulation.round_inspections(0), None);
+        assert_eq!(simulation.round_inspections(3), None);
+    }
+}
@@ -0,0 +1,356 @@
+//! Nested lists of integers, as used for the packets in Advent of Code 2022 Day 13.
+//! https://adventofcode.com/2022/day/13
+//!
+//! A `NestedList` is either an individual integer or a list of zero or more `NestedList`s. Values
+//! are ordered using the rules given in the challenge:
+//!
+//! - two integers are compared by value;
+//! - two lists are compared element by element, and if every element compared is equal, the
+//!   shorter list is ordered first;
+//! - an integer compared with a list is first promoted to a list containing only that integer.
+//!
+//! As a result of the last rule, values with different structures can be equal, e.g., `4`, `[4]`
+//! and `[[4]]` are all equal to one another.
+
+use std::cmp::Ordering;
+use std::fmt::{Display, Error, Formatter};
+use std::iter::Peekable;
+use std::slice;
+use std::str::{Chars, FromStr};
+
+/// A `NestedList` contains either an individual integer or a `Vec` of zero or more
+/// `NestedList`s.
+#[derive(Clone, Debug)]
+pub enum NestedList<T> {
+    Integer(T),
+    List(Vec<NestedList<T>>),
+}
+
+impl<T: Ord> NestedList<T> {
+    /// Returns this value as a slice of elements, promoting an `Integer` to a list containing
+    /// only itself, as required when comparing an integer with a list.
+    fn as_elements(&self) -> &[NestedList<T>] {
+        match self {
+            NestedList::Integer(_) => slice::from_ref(self),
+            NestedList::List(list) => list,
+        }
+    }
+}
+
+impl<T: FromStr> NestedList<T> {
+    /// Internal function that parses the value at the start of `chars`, consuming the characters
+    /// that form it.
+    fn parse_recurse(chars: &mut Peekable<Chars<'_>>) -> Result<Self, String> {
+        match chars.peek() {
+            Some('[') => {
+                chars.next();
+                let mut elements = Vec::new();
+
+                if chars.next_if_eq(&']').is_some() {
+                    return Ok(NestedList::List(elements));
+                }
+
+                loop {
+                    elements.push(Self::parse_recurse(chars)?);
+
+                    match chars.next() {
+                        Some(',') => {}
+                        Some(']') => return Ok(NestedList::List(elements)),
+                        Some(c) => return Err(format!("Unexpected character '{}' in list", c)),
+                        None => {
+                            return Err(
+                                "The input contains unbalanced start and end list tags".to_string()
+                            )
+                        }
+                    }
+                }
+            }
+            Some(c) if c.is_ascii_digit() || *c == '-' => {
+                let mut token = String::new();
+                while let Some(c) = chars.next_if(|c| !matches!(c, ',' | '[' | ']')) {
+                    token.push(c);
+                }
+
+                token
+                    .parse()
+                    .map(NestedList::Integer)
+                    .map_err(|_| format!("Invalid integer '{}' in input", token))
+            }
+            Some(c) => Err(format!("Unrecognized character '{}' in input", c)),
+            None => Err("Unexpected end of input".to_string()),
+        }
+    }
+}
+
+/// Parses a value in the text form used in the challenge, e.g., "[[1],[2,3,4]]".
+impl<T: FromStr> FromStr for NestedList<T> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.trim().chars().peekable();
+        let value = Self::parse_recurse(&mut chars)?;
+
+        if let Some(c) = chars.next() {
+            return Err(format!("Unexpected character '{}' after end of value", c));
+        }
+
+        Ok(value)
+    }
+}
+
+/// Writes a value in the same text form it is parsed from.
+impl<T: Display> Display for NestedList<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            NestedList::Integer(int) => write!(f, "{}", int),
+            NestedList::List(list) => {
+                write!(f, "[")?;
+                for (index, element) in list.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+/// Orders values using the challenge rules described in the module documentation.
+impl<T: Ord> Ord for NestedList<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if let (NestedList::Integer(left), NestedList::Integer(right)) = (self, other) {
+            return left.cmp(right);
+        }
+
+        // Lexicographic ordering of the elements orders a list before any longer list that starts
+        // with the same elements, as the challenge requires.
+        self.as_elements().iter().cmp(other.as_elements())
+    }
+}
+
+impl<T: Ord> PartialOrd for NestedList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Two values are equal if neither is ordered before the other, so equality follows the
+/// challenge rules rather than requiring an identical structure.
+impl<T: Ord> PartialEq for NestedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Ord> Eq for NestedList<T> {}
+
+// Test using data from the examples on the challenge page.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Packet = NestedList<u8>;
+
+    fn packet(s: &str) -> Packet {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!(matches!(
+            packet("[11,0]"),
+            NestedList::List(ref list) if matches!(
+                list[..],
+                [NestedList::Integer(11), NestedList::Integer(0)]
+            )
+        ));
+        assert!(matches!(packet("[]"), NestedList::List(ref list) if list.is_empty()));
+        assert!(matches!(packet("7"), NestedList::Integer(7)));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for input in [
+            "[1,1,3,1,1]",
+            "[[1],[2,3,4]]",
+            "[[4,4],4,4,4]",
+            "[]",
+            "[[[]]]",
+            "[1,[2,[3,[4,[5,6,7]]]],8,9]",
+        ] {
+            assert_eq!(packet(input).to_string(), input);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!("[9,6,[2],a,5]".parse::<Packet>().is_err());
+        assert!("[9,6,[2]".parse::<Packet>().is_err());
+        assert!("[9,6]]".parse::<Packet>().is_err());
+        assert!("[1,,2]".parse::<Packet>().is_err());
+        assert!("[256]".parse::<Packet>().is_err());
+        assert!("".parse::<Packet>().is_err());
+    }
+
+    #[test]
+    fn test_ordering_examples() {
+        let examples = [
+            ("[1,1,3,1,1]", "[1,1,5,1,1]", Ordering::Less),
+            ("[[1],[2,3,4]]", "[[1],4]", Ordering::Less),
+            ("[9]", "[[8,7,6]]", Ordering::Greater),
+            ("[[4,4],4,4]", "[[4,4],4,4,4]", Ordering::Less),
+            ("[7,7,7,7]", "[7,7,7]", Ordering::Greater),
+            ("[]", "[3]", Ordering::Less),
+            ("[[[]]]", "[[]]", Ordering::Greater),
+            (
+                "[1,[2,[3,[4,[5,6,7]]]],8,9]",
+                "[1,[2,[3,[4,[5,6,0]]]],8,9]",
+                Ordering::Greater,
+            ),
+        ];
+
+        for (left, right, expected) in examples {
+            assert_eq!(packet(left).cmp(&packet(right)), expected);
+        }
+    }
+
+    #[test]
+    fn test_integer_promotion_equality() {
+        assert_eq!(packet("4"), packet("[4]"));
+        assert_eq!(packet("[4]"), packet("[[[4]]]"));
+        assert_ne!(packet("[4]"), packet("[4,4]"));
+        assert_ne!(packet("[]"), packet("[[]]"));
+    }
+
+    // Property tests. Random values are generated with a simple deterministic pseudo-random
+    // number generator so that any failure can be reproduced.
+
+    const PROPERTY_TEST_CASES: usize = 500;
+
+    /// An xorshift pseudo-random number generator.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, bound: u64) -> u64 {
+            self.next() % bound
+        }
+
+        /// Returns a random value nested at most `depth` lists deep. Integers are kept small so
+        /// that equal elements are common and the deeper comparison rules are exercised.
+        fn packet(&mut self, depth: usize) -> Packet {
+            if depth == 0 || self.below(3) == 0 {
+                return NestedList::Integer(self.below(4) as u8);
+            }
+
+            let len = self.below(4) as usize;
+            NestedList::List((0..len).map(|_| self.packet(depth - 1)).collect())
+        }
+    }
+
+    #[test]
+    fn property_ordering_is_antisymmetric() {
+        let mut rng = Rng(0x2022_1301);
+        for _ in 0..PROPERTY_TEST_CASES {
+            let (a, b) = (rng.packet(4), rng.packet(4));
+            assert_eq!(a.cmp(&b), b.cmp(&a).reverse(), "{} vs {}", a, b);
+            assert_eq!(a.cmp(&a), Ordering::Equal, "{}", a);
+        }
+    }
+
+    #[test]
+    fn property_ordering_is_transitive() {
+        let mut rng = Rng(0x2022_1302);
+        for _ in 0..PROPERTY_TEST_CASES {
+            let (a, b, c) = (rng.packet(3), rng.packet(3), rng.packet(3));
+            if a <= b && b <= c {
+                assert!(a <= c, "{} {} {}", a, b, c);
+            }
+            if a == b && b == c {
+                assert_eq!(a, c, "{} {} {}", a, b, c);
+            }
+        }
+    }
+
+    #[test]
+    fn property_integers_compare_by_value() {
+        let mut rng = Rng(0x2022_1303);
+        for _ in 0..PROPERTY_TEST_CASES {
+            let (a, b) = (rng.below(256) as u8, rng.below(256) as u8);
+            assert_eq!(
+                NestedList::Integer(a).cmp(&NestedList::Integer(b)),
+                a.cmp(&b)
+            );
+        }
+    }
+
+    #[test]
+    fn property_integer_compares_as_single_element_list() {
+        let mut rng = Rng(0x2022_1304);
+        for _ in 0..PROPERTY_TEST_CASES {
+            let int = rng.below(4) as u8;
+            let other = rng.packet(4);
+            let promoted = NestedList::List(vec![NestedList::Integer(int)]);
+
+            assert_eq!(
+                NestedList::Integer(int).cmp(&other),
+                promoted.cmp(&other),
+                "{} vs {}",
+                int,
+                other
+            );
+        }
+    }
+
+    #[test]
+    fn property_list_is_before_its_extensions() {
+        let mut rng = Rng(0x2022_1305);
+        for _ in 0..PROPERTY_TEST_CASES {
+            if let NestedList::List(mut list) = rng.packet(4) {
+                let prefix = NestedList::List(list.clone());
+                list.push(rng.packet(3));
+                let extended = NestedList::List(list);
+
+                assert!(prefix < extended, "{} vs {}", prefix, extended);
+            }
+        }
+    }
+
+    #[test]
+    fn property_first_difference_decides_order() {
+        let mut rng = Rng(0x2022_1306);
+        for _ in 0..PROPERTY_TEST_CASES {
+            let (a, b) = (rng.packet(3), rng.packet(3));
+            let mut common: Vec<Packet> = (0..rng.below(3)).map(|_| rng.packet(2)).collect();
+            let mut left = common.clone();
+            left.push(a.clone());
+            left.push(rng.packet(2));
+            common.push(b.clone());
+
+            if a != b {
+                assert_eq!(
+                    NestedList::List(left).cmp(&NestedList::List(common)),
+                    a.cmp(&b)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn property_display_round_trips() {
+        let mut rng = Rng(0x2022_1307);
+        for _ in 0..PROPERTY_TEST_CASES {
+            let value = rng.packet(5);
+            let text = value.to_string();
+            assert_eq!(packet(&text).to_string(), text);
+        }
+    }
+}
@@ -0,0 +1,372 @@
+//! Page ordering rules, as described in Advent of Code 2024 Day 5.
+//! https://adventofcode.com/2024/day/5
+//!
+//! Each rule states that one page must come before another whenever both are in an update. The
+//! rules form a directed graph between pages, but not necessarily a consistent ordering of every
+//! page, as the rules for pages that are never updated together may form a cycle. Only the rules
+//! between pages in the same update apply to it, so an update is put in order by a topological
+//! sort of just those pages.
+//!
+//! If the rules that apply to an update form a cycle then no order satisfies them all, and the
+//! sort reports the contradictory rules. Pages are identified by any string without whitespace,
+//! a '|' or a ','.
+
+use std::collections::{BTreeSet, HashMap};
+use std::str::FromStr;
+
+const INPUT_TOKEN_RULE: char = '|';
+const INPUT_TOKEN_PAGE_SEPARATOR: char = ',';
+
+type PageId = usize;
+pub type Update = Vec<String>;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OrderingRules {
+    pages: Vec<String>,
+    ids: HashMap<String, PageId>,
+    /// `later[p]` holds the pages that must come after page `p`.
+    later: Vec<BTreeSet<PageId>>,
+}
+
+impl FromStr for OrderingRules {
+    type Err = String;
+
+    /// Parses one rule per line, e.g., "47|53", meaning page 47 must come before page 53.
+    ///
+    /// Returns an error if a rule is malformed.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rules = Self::new();
+
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (earlier, later) = line
+                .split_once(INPUT_TOKEN_RULE)
+                .map(|(earlier, later)| (earlier.trim(), later.trim()))
+                .filter(|(earlier, later)| is_valid_page(earlier) && is_valid_page(later))
+                .ok_or_else(|| format!("Malformed ordering rule: {}", line))?;
+            rules.add_rule(earlier, later);
+        }
+
+        Ok(rules)
+    }
+}
+
+fn is_valid_page(page: &str) -> bool {
+    !page.is_empty()
+        && !page.contains(|c: char| {
+            c.is_whitespace() || c == INPUT_TOKEN_RULE || c == INPUT_TOKEN_PAGE_SEPARATOR
+        })
+}
+
+impl OrderingRules {
+    /// Returns an empty set of rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn page_id(&mut self, page: &str) -> PageId {
+        if let Some(&id) = self.ids.get(page) {
+            return id;
+        }
+
+        let id = self.pages.len();
+        self.pages.push(page.to_string());
+        self.ids.insert(page.to_string(), id);
+        self.later.push(BTreeSet::new());
+        id
+    }
+
+    /// Adds a rule that page `earlier` must come before page `later`.
+    pub fn add_rule(&mut self, earlier: &str, later: &str) {
+        let earlier = self.page_id(earlier);
+        let later = self.page_id(later);
+        self.later[earlier].insert(later);
+    }
+
+    /// Returns whether there is a rule that page `earlier` must come before page `later`.
+    pub fn must_precede(&self, earlier: &str, later: &str) -> bool {
+        match (self.ids.get(earlier), self.ids.get(later)) {
+            (Some(&earlier), Some(&later)) => self.later[earlier].contains(&later),
+            _ => false,
+        }
+    }
+
+    /// Returns the first rule broken by `update`, as the pages that rule says must come first and
+    /// second, or `None` if `update` follows every rule.
+    pub fn first_violation<'a, S: AsRef<str>>(
+        &self,
+        update: &'a [S],
+    ) -> Option<(&'a str, &'a str)> {
+        update.iter().enumerate().find_map(|(i, later)| {
+            update[i + 1..]
+                .iter()
+                .find(|earlier| self.must_precede(earlier.as_ref(), later.as_ref()))
+                .map(|earlier| (earlier.as_ref(), later.as_ref()))
+        })
+    }
+
+    /// Returns whether `update` follows every rule.
+    pub fn is_ordered<S: AsRef<str>>(&self, update: &[S]) -> bool {
+        self.first_violation(update).is_none()
+    }
+
+    /// Returns the pages of `update` in an order that follows every rule between them. Where the
+    /// rules allow more than one order, pages keep their order in `update` as far as possible.
+    ///
+    /// Returns an error if a page appears more than once in `update`, or the rules between its
+    /// pages form a cycle, in which case the rules on the cycle are listed.
+    pub fn sort<S: AsRef<str>>(&self, update: &[S]) -> Result<Update, String> {
+        let pages: Vec<&str> = update.iter().map(AsRef::as_ref).collect();
+        for (i, page) in pages.iter().enumerate() {
+            if pages[..i].contains(page) {
+                return Err(format!(
+                    "Page '{}' appears more than once in the update",
+                    page
+                ));
+            }
+        }
+
+        // `later[i]` holds the positions in `update` of the pages that must come after the page
+        // at position `i`, and `earlier_count[i]` how many pages must come before it.
+        let later: Vec<Vec<usize>> = (0..pages.len())
+            .map(|i| {
+                (0..pages.len())
+                    .filter(|&j| self.must_precede(pages[i], pages[j]))
+                    .collect()
+            })
+            .collect();
+        let mut earlier_count = vec![0; pages.len()];
+        for &j in later.iter().flatten() {
+            earlier_count[j] += 1;
+        }
+
+        // Repeatedly take the first page that no remaining page must come before.
+        let mut sorted = Vec::with_capacity(pages.len());
+        let mut placed = vec![false; pages.len()];
+        while let Some(i) = (0..pages.len()).find(|&i| !placed[i] && earlier_count[i] == 0) {
+            placed[i] = true;
+            sorted.push(pages[i].to_string());
+            for &j in &later[i] {
+                earlier_count[j] -= 1;
+            }
+        }
+
+        if sorted.len() == pages.len() {
+            return Ok(sorted);
+        }
+
+        let remaining: Vec<&str> = (0..pages.len())
+            .filter(|&i| !placed[i])
+            .map(|i| pages[i])
+            .collect();
+        let cycle = self
+            .find_cycle(&remaining)
+            .expect("Pages that cannot be sorted are on a cycle");
+        let cycle_rules: Vec<String> = cycle
+            .windows(2)
+            .map(|pair| format!("{}{}{}", pair[0], INPUT_TOKEN_RULE, pair[1]))
+            .collect();
+        Err(format!(
+            "The rules for the update contradict each other: {}",
+            cycle_rules.join(", ")
+        ))
+    }
+
+    /// Returns a cycle of rules between `pages`, as the pages on it, starting and ending with the
+    /// same page, or `None` if the rules between `pages` are consistent.
+    pub fn find_cycle<S: AsRef<str>>(&self, pages: &[S]) -> Option<Vec<String>> {
+        let pages: Vec<&str> = pages.iter().map(AsRef::as_ref).collect();
+        let mut done = vec![false; pages.len()];
+        let mut path = Vec::new();
+
+        (0..pages.len())
+            .find(|&i| !done[i] && self.visit_for_cycle(&pages, i, &mut done, &mut path))
+            .map(|_| path.iter().map(|&i| pages[i].to_string()).collect())
+    }
+
+    /// Depth-first search from the page at position `i` in `pages` for a cycle. Returns whether
+    /// one was found, in which case `path` holds the positions of its pages, with the first
+    /// repeated at the end. `done` records the pages already shown not to be on a cycle.
+    fn visit_for_cycle(
+        &self,
+        pages: &[&str],
+        i: usize,
+        done: &mut [bool],
+        path: &mut Vec<usize>,
+    ) -> bool {
+        path.push(i);
+
+        for j in 0..pages.len() {
+            if done[j] || !self.must_precede(pages[i], pages[j]) {
+                continue;
+            }
+            if let Some(start) = path.iter().position(|&p| p == j) {
+                path.drain(..start);
+                path.push(j);
+                return true;
+            }
+            if self.visit_for_cycle(pages, j, done, path) {
+                return true;
+            }
+        }
+
+        done[i] = true;
+        path.pop();
+        false
+    }
+}
+
+/// Returns the ordering rules in the first section of `input`, and the updates in the second,
+/// with the sections separated by a blank line. Each update is a line of comma-separated pages,
+/// e.g., "75,47,61,53,29".
+///
+/// Returns an error if a rule or update is malformed.
+pub fn parse_input(input: &str) -> Result<(OrderingRules, Vec<Update>), String> {
+    let input = input.replace("\r\n", "\n");
+    let (rules, updates) = input
+        .split_once("\n\n")
+        .ok_or("The input must have a blank line between the rules and the updates")?;
+
+    let rules: OrderingRules = rules.parse()?;
+    let updates = updates
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let pages: Update = line
+                .split(INPUT_TOKEN_PAGE_SEPARATOR)
+                .map(|page| page.trim().to_string())
+                .collect();
+            if pages.iter().all(|page| is_valid_page(page)) {
+                Ok(pages)
+            } else {
+                Err(format!("Malformed update: {}", line))
+            }
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok((rules, updates))
+}
+
+// Test data based on examples on the challenge page.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "\
+47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47
+";
+
+    #[test]
+    fn test_parse_input() {
+        let (rules, updates) = parse_input(TEST_INPUT).unwrap();
+
+        assert!(rules.must_precede("47", "53"));
+        assert!(!rules.must_precede("53", "47"));
+        assert!(!rules.must_precede("47", "99"));
+        assert_eq!(updates.len(), 6);
+        assert_eq!(updates[2], ["75", "29", "13"]);
+
+        assert!(parse_input("47|53\n75,47").is_err());
+        assert!(parse_input("47-53\n\n75,47").is_err());
+        assert!(parse_input("47|\n\n75,47").is_err());
+        assert!(parse_input("47|53\n\n75,,47").is_err());
+    }
+
+    #[test]
+    fn test_is_ordered() {
+        let (rules, updates) = parse_input(TEST_INPUT).unwrap();
+
+        let ordered: Vec<bool> = updates.iter().map(|u| rules.is_ordered(u)).collect();
+        assert_eq!(ordered, [true, true, true, false, false, false]);
+        assert_eq!(rules.first_violation(&updates[3]), Some(("97", "75")));
+        assert_eq!(rules.first_violation(&updates[4]), Some(("29", "13")));
+    }
+
+    #[test]
+    fn test_sort() {
+        let (rules, updates) = parse_input(TEST_INPUT).unwrap();
+
+        assert_eq!(rules.sort(&updates[0]), Ok(updates[0].clone()));
+        assert_eq!(
+            rules.sort(&updates[3]).unwrap(),
+            ["97", "75", "47", "61", "53"]
+        );
+        assert_eq!(rules.sort(&updates[4]).unwrap(), ["61", "29", "13"]);
+        assert_eq!(
+            rules.sort(&updates[5]).unwrap(),
+            ["97", "75", "47", "29", "13"]
+        );
+
+        // Pages without rules keep their order.
+        assert_eq!(
+            rules.sort(&["x", "13", "y", "97"]).unwrap(),
+            ["x", "y", "97", "13"]
+        );
+        assert!(rules.sort(&["13", "97", "13"]).is_err());
+    }
+
+    #[test]
+    fn test_arbitrary_pages() {
+        let rules: OrderingRules = "intro|chapter-1\nchapter-1|chapter-2\n1000|intro"
+            .parse()
+            .unwrap();
+
+        assert!(!rules.is_ordered(&["chapter-2", "intro", "1000"]));
+        assert_eq!(
+            rules
+                .sort(&["chapter-2", "intro", "chapter-1", "1000"])
+                .unwrap(),
+            ["1000", "intro", "chapter-1", "chapter-2"]
+        );
+    }
+
+    #[test]
+    fn test_cycle() {
+        let rules: OrderingRules = "1|2\n2|3\n3|1\n3|4".parse().unwrap();
+
+        // The rules only form a cycle when all three pages on it are present.
+        assert_eq!(rules.sort(&["3", "2", "4"]).unwrap(), ["2", "3", "4"]);
+        assert_eq!(rules.find_cycle(&["3", "2", "4"]), None);
+
+        assert_eq!(
+            rules.find_cycle(&["4", "2", "1", "3"]),
+            Some(vec![
+                "2".to_string(),
+                "3".to_string(),
+                "1".to_string(),
+                "2".to_string()
+            ])
+        );
+        assert_eq!(
+            rules.sort(&["4", "2", "1", "3"]),
+            Err("The rules for the update contradict each other: 2|3, 3|1, 1|2".to_string())
+        );
+    }
+}
@@ -0,0 +1,792 @@
+//! Registering overlapping 3D point clouds, as described in Advent of Code 2021 Day 19.
+//! https://adventofcode.com/2021/day/19
+//!
+//! Each scanner reports the positions of the beacons it can see relative to itself, but neither
+//! its own position nor its orientation is known. Where two scanners can see at least a threshold
+//! number of the same beacons, one can be registered against the other: the rotation and
+//! translation that map its beacons onto the other scanner's beacons are recovered. Registering
+//! every scanner against a reference scanner places all the beacons on one merged map.
+//!
+//! Trying every rotation of every pair of scanners is slow, so each scanner is first given a
+//! fingerprint of the squared distances between each pair of its beacons. These do not depend on
+//! the scanner's position or orientation, so two scanners that see `n` of the same beacons must
+//! share at least `n * (n - 1) / 2` distances, and only pairs of scanners that do are tried.
+//!
+//! Scanners that cannot be registered against each other, directly or through other scanners,
+//! form separate groups, each with its own map relative to the lowest numbered scanner in it.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::fmt;
+use std::ops::{Add, Sub};
+use std::str::FromStr;
+
+const SCANNER_INPUT_START_END: &str = "---";
+const SCANNER_INPUT_KEYWORD: &str = "scanner";
+
+pub type Coordinate = i32;
+
+/// A location in 3D space. Coordinates can be negative.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Position {
+    pub x: Coordinate,
+    pub y: Coordinate,
+    pub z: Coordinate,
+}
+
+impl Position {
+    pub const ORIGIN: Self = Self { x: 0, y: 0, z: 0 };
+
+    pub fn new(x: Coordinate, y: Coordinate, z: Coordinate) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn manhattan_distance(&self, other: &Self) -> u32 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    /// Returns the square of the straight line distance to `other`, which unlike the Manhattan
+    /// distance does not change when both positions are rotated.
+    fn squared_distance(&self, other: &Self) -> i64 {
+        let difference = *self - *other;
+        [difference.x, difference.y, difference.z]
+            .iter()
+            .map(|&d| i64::from(d) * i64::from(d))
+            .sum()
+    }
+
+    fn coordinates(&self) -> [Coordinate; 3] {
+        [self.x, self.y, self.z]
+    }
+}
+
+impl Add for Position {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Position {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl FromStr for Position {
+    type Err = String;
+
+    /// Parses three comma-separated coordinates, e.g., "-618,-824,-621".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let coordinates = s
+            .split(',')
+            .map(|c| c.trim().parse())
+            .collect::<Result<Vec<Coordinate>, _>>()
+            .map_err(|e| format!("Invalid coordinate in '{}': {}", s, e))?;
+
+        match coordinates[..] {
+            [x, y, z] => Ok(Self::new(x, y, z)),
+            _ => Err(format!("Expected three coordinates in '{}'", s)),
+        }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
+
+/// A rotation by a multiple of 90 degrees about each axis, held as a 3x3 matrix whose rows are
+/// applied to a position's (x, y, z) coordinates to give the rotated coordinates.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Rotation(pub [[Coordinate; 3]; 3]);
+
+impl Rotation {
+    pub const IDENTITY: Self = Self([[1, 0, 0], [0, 1, 0], [0, 0, 1]]);
+
+    /// Returns all 24 rotations a scanner could be in, starting with `Rotation::IDENTITY`. These
+    /// are the matrices with a single 1 or -1 in each row and column that do not mirror space,
+    /// i.e., whose determinant is 1.
+    pub fn all() -> Vec<Self> {
+        const PERMUTATIONS: [[usize; 3]; 6] = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+        let mut rotations = Vec::with_capacity(24);
+
+        for permutation in PERMUTATIONS {
+            for signs in 0..8 {
+                let mut matrix = [[0; 3]; 3];
+                for (row, &column) in permutation.iter().enumerate() {
+                    matrix[row][column] = if signs & (1 << row) == 0 { 1 } else { -1 };
+                }
+
+                let rotation = Self(matrix);
+                if rotation.determinant() == 1 {
+                    rotations.push(rotation);
+                }
+            }
+        }
+
+        rotations
+    }
+
+    pub fn apply(&self, position: &Position) -> Position {
+        let coordinates = position.coordinates();
+        let [x, y, z] = self
+            .0
+            .map(|row| (0..3).map(|i| row[i] * coordinates[i]).sum());
+
+        Position::new(x, y, z)
+    }
+
+    /// Returns the rotation equivalent to applying `other` and then `self`.
+    pub fn compose(&self, other: &Self) -> Self {
+        let mut matrix = [[0; 3]; 3];
+        for (row, values) in matrix.iter_mut().enumerate() {
+            for (column, value) in values.iter_mut().enumerate() {
+                *value = (0..3).map(|i| self.0[row][i] * other.0[i][column]).sum();
+            }
+        }
+
+        Self(matrix)
+    }
+
+    fn determinant(&self) -> Coordinate {
+        let m = &self.0;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+}
+
+impl fmt::Display for Rotation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rows: Vec<String> = self
+            .0
+            .iter()
+            .map(|row| format!("[{}, {}, {}]", row[0], row[1], row[2]))
+            .collect();
+
+        write!(f, "[{}]", rows.join(", "))
+    }
+}
+
+/// A rotation followed by a translation, mapping positions relative to one scanner to positions
+/// relative to another. The translation is the position of the first scanner relative to the
+/// second.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Transform {
+    pub rotation: Rotation,
+    pub translation: Position,
+}
+
+impl Transform {
+    pub const IDENTITY: Self = Self {
+        rotation: Rotation::IDENTITY,
+        translation: Position::ORIGIN,
+    };
+
+    pub fn apply(&self, position: &Position) -> Position {
+        self.rotation.apply(position) + self.translation
+    }
+
+    /// Returns the transform equivalent to applying `other` and then `self`.
+    pub fn compose(&self, other: &Self) -> Self {
+        Self {
+            rotation: self.rotation.compose(&other.rotation),
+            translation: self.apply(&other.translation),
+        }
+    }
+}
+
+/// The beacons a scanner can see, relative to the scanner's own position and orientation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scanner {
+    pub id: usize,
+    pub beacons: Vec<Position>,
+    /// How many pairs of beacons are each squared distance apart.
+    fingerprint: HashMap<i64, usize>,
+}
+
+impl Scanner {
+    pub fn new(id: usize, beacons: Vec<Position>) -> Self {
+        let mut fingerprint = HashMap::new();
+        for (i, a) in beacons.iter().enumerate() {
+            for b in &beacons[i + 1..] {
+                *fingerprint.entry(a.squared_distance(b)).or_insert(0) += 1;
+            }
+        }
+
+        Self {
+            id,
+            beacons,
+            fingerprint,
+        }
+    }
+
+    /// Returns the number of distances between pairs of beacons that this scanner and `other`
+    /// have in common.
+    fn shared_distances(&self, other: &Self) -> usize {
+        self.fingerprint
+            .iter()
+            .map(|(distance, &count)| count.min(*other.fingerprint.get(distance).unwrap_or(&0)))
+            .sum()
+    }
+
+    /// Returns the transform that maps the most of `other`'s beacons onto this scanner's beacons,
+    /// or `None` if it maps fewer than `match_threshold` of them.
+    ///
+    /// Returns an error if more than one transform maps that many beacons.
+    fn align(&self, other: &Self, match_threshold: usize) -> Result<Option<Transform>, String> {
+        let needed = match_threshold * match_threshold.saturating_sub(1) / 2;
+        if self.shared_distances(other) < needed {
+            return Ok(None);
+        }
+
+        let mut best: Option<Transform> = None;
+        let mut best_count = 0;
+        let mut tied = false;
+        for rotation in Rotation::all() {
+            let rotated: Vec<Position> = other.beacons.iter().map(|b| rotation.apply(b)).collect();
+
+            // Each pairing of beacons votes for the position of `other` that would make them the
+            // same beacon.
+            let mut votes: HashMap<Position, usize> = HashMap::new();
+            for beacon in &self.beacons {
+                for other_beacon in &rotated {
+                    *votes.entry(*beacon - *other_beacon).or_insert(0) += 1;
+                }
+            }
+
+            for (translation, count) in votes {
+                match count.cmp(&best_count) {
+                    Ordering::Less => {}
+                    Ordering::Equal => tied = true,
+                    Ordering::Greater => {
+                        best_count = count;
+                        tied = false;
+                        best = Some(Transform {
+                            rotation,
+                            translation,
+                        });
+                    }
+                }
+            }
+        }
+
+        if best_count < match_threshold {
+            return Ok(None);
+        }
+        if tied {
+            return Err(format!(
+                "Scanner {} can be registered against scanner {} in more than one way",
+                other.id, self.id
+            ));
+        }
+        Ok(best)
+    }
+}
+
+/// Returns the scanners in `input`, each a header line such as "--- scanner 0 ---" followed by
+/// one line per beacon, with blank lines between scanners. Scanners must be numbered in order
+/// from 0.
+pub fn parse_scanners(input: &str) -> Result<Vec<Scanner>, String> {
+    let mut scanners = Vec::new();
+
+    for block in input.split("\n\n").filter(|block| !block.trim().is_empty()) {
+        let mut lines = block.lines().filter(|line| !line.is_empty());
+        let header = lines.next().unwrap();
+        let tokens: Vec<&str> = header.split(' ').collect();
+
+        let id: usize = match tokens[..] {
+            [SCANNER_INPUT_START_END, SCANNER_INPUT_KEYWORD, id, SCANNER_INPUT_START_END] => id
+                .parse()
+                .map_err(|e| format!("Invalid scanner Id in '{}': {}", header, e))?,
+            _ => return Err(format!("Expected a scanner header, found '{}'", header)),
+        };
+        if id != scanners.len() {
+            return Err(format!(
+                "Scanner {} found where scanner {} was expected",
+                id,
+                scanners.len()
+            ));
+        }
+
+        let beacons = lines.map(str::parse).collect::<Result<Vec<_>, _>>()?;
+        if beacons.is_empty() {
+            return Err(format!("Scanner {} has no beacons", id));
+        }
+
+        scanners.push(Scanner::new(id, beacons));
+    }
+
+    Ok(scanners)
+}
+
+/// The outcome of registering a set of scanners against each other.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Registration {
+    /// The Ids of the scanners in each group, in ascending order. Groups are ordered by their
+    /// lowest Id, so scanner 0 is always in the first group.
+    groups: Vec<Vec<usize>>,
+    /// `placements[id]` is the index of the scanner's group and the transform from the scanner's
+    /// own coordinates to those of the first scanner in the group.
+    placements: Vec<(usize, Transform)>,
+    /// The merged map of beacons for each group.
+    maps: Vec<BTreeSet<Position>>,
+}
+
+impl Registration {
+    /// Registers each scanner against any others it sees at least `match_threshold` of the same
+    /// beacons as.
+    ///
+    /// Returns an error if `match_threshold` is 0, or if a pair of scanners can be registered
+    /// against each other in more than one way.
+    pub fn new(scanners: &[Scanner], match_threshold: usize) -> Result<Self, String> {
+        if match_threshold == 0 {
+            return Err("The match threshold must be at least 1".to_string());
+        }
+
+        let mut placements: Vec<Option<(usize, Transform)>> = vec![None; scanners.len()];
+        let mut groups = Vec::new();
+
+        for reference in 0..scanners.len() {
+            if placements[reference].is_some() {
+                continue;
+            }
+
+            let group = groups.len();
+            let mut members = vec![reference];
+            placements[reference] = Some((group, Transform::IDENTITY));
+            let mut queue = VecDeque::from([reference]);
+
+            while let Some(known) = queue.pop_front() {
+                let (_, known_transform) = placements[known].unwrap();
+
+                for other in 0..scanners.len() {
+                    if placements[other].is_some() {
+                        continue;
+                    }
+                    if let Some(transform) =
+                        scanners[known].align(&scanners[other], match_threshold)?
+                    {
+                        placements[other] = Some((group, known_transform.compose(&transform)));
+                        members.push(other);
+                        queue.push_back(other);
+                    }
+                }
+            }
+
+            members.sort_unstable();
+            groups.push(members);
+        }
+
+        let placements: Vec<(usize, Transform)> = placements.into_iter().flatten().collect();
+        let mut maps = vec![BTreeSet::new(); groups.len()];
+        for (scanner, &(group, transform)) in scanners.iter().zip(&placements) {
+            maps[group].extend(scanner.beacons.iter().map(|b| transform.apply(b)));
+        }
+
+        Ok(Self {
+            groups,
+            placements,
+            maps,
+        })
+    }
+
+    /// Returns the Ids of the scanners in each group of scanners registered against each other.
+    /// The first group contains scanner 0.
+    pub fn groups(&self) -> &[Vec<usize>] {
+        &self.groups
+    }
+
+    /// Returns true if every scanner has been registered against scanner 0.
+    pub fn is_connected(&self) -> bool {
+        self.groups.len() <= 1
+    }
+
+    /// Returns the index of the group containing scanner `id`.
+    pub fn group_of(&self, id: usize) -> usize {
+        self.placements[id].0
+    }
+
+    /// Returns the transform from scanner `id`'s coordinates to those of the first scanner in its
+    /// group. Its translation is the position of scanner `id` relative to that scanner.
+    pub fn transform(&self, id: usize) -> &Transform {
+        &self.placements[id].1
+    }
+
+    /// Returns the position of every beacon seen by the scanners in `group`, relative to the first
+    /// scanner in the group.
+    pub fn beacons(&self, group: usize) -> &BTreeSet<Position> {
+        &self.maps[group]
+    }
+
+    /// Returns the largest Manhattan distance between any two scanners in `group`.
+    pub fn max_scanner_distance(&self, group: usize) -> u32 {
+        let positions: Vec<Position> = self.groups[group]
+            .iter()
+            .map(|&id| self.transform(id).translation)
+            .collect();
+
+        positions
+            .iter()
+            .flat_map(|a| positions.iter().map(|b| a.manhattan_distance(b)))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns a description of the groups, such as "[0, 1, 3], [2]".
+    pub fn describe_groups(&self) -> String {
+        let groups: Vec<String> = self.groups.iter().map(|g| format!("{:?}", g)).collect();
+        groups.join(", ")
+    }
+}
+
+// Test data based on examples on the challenge page.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MATCH_THRESHOLD: usize = 12;
+
+    const TEST_INPUT: &str = "\
+--- scanner 0 ---
+404,-588,-901
+528,-643,409
+-838,591,734
+390,-675,-793
+-537,-823,-458
+-485,-357,347
+-345,-311,381
+-661,-816,-575
+-876,649,763
+-618,-824,-621
+553,345,-567
+474,580,667
+-447,-329,318
+-584,868,-557
+544,-627,-890
+564,392,-477
+455,729,728
+-892,524,684
+-689,845,-530
+423,-701,434
+7,-33,-71
+630,319,-379
+443,580,662
+-789,900,-551
+459,-707,401
+
+--- scanner 1 ---
+686,422,578
+605,423,415
+515,917,-361
+-336,658,858
+95,138,22
+-476,619,847
+-340,-569,-846
+567,-361,727
+-460,603,-452
+669,-402,600
+729,430,532
+-500,-761,534
+-322,571,750
+-466,-666,-811
+-429,-592,574
+-355,545,-477
+703,-491,-529
+-328,-685,520
+413,935,-424
+-391,539,-444
+586,-435,557
+-364,-763,-893
+807,-499,-711
+755,-354,-619
+553,889,-390
+
+--- scanner 2 ---
+649,640,665
+682,-795,504
+-784,533,-524
+-644,584,-595
+-588,-843,648
+-30,6,44
+-674,560,763
+500,723,-460
+609,671,-379
+-555,-800,653
+-675,-892,-343
+697,-426,-610
+578,704,681
+493,664,-388
+-671,-858,530
+-667,343,800
+571,-461,-707
+-138,-166,112
+-889,563,-600
+646,-828,498
+640,759,510
+-630,509,768
+-681,-892,-333
+673,-379,-804
+-742,-814,-386
+577,-820,562
+
+--- scanner 3 ---
+-589,542,597
+605,-692,669
+-500,565,-823
+-660,373,557
+-458,-679,-417
+-488,449,543
+-626,468,-788
+338,-750,-386
+528,-832,-391
+562,-778,733
+-938,-730,414
+543,643,-506
+-524,371,-870
+407,773,750
+-104,29,83
+378,-903,-323
+-778,-728,485
+426,699,580
+-438,-605,-362
+-469,-447,-387
+509,732,623
+647,635,-688
+-868,-804,481
+614,-800,639
+595,780,-596
+
+--- scanner 4 ---
+727,592,562
+-293,-554,779
+441,611,-461
+-714,465,-776
+-743,427,-804
+-660,-479,-426
+832,-632,460
+927,-485,-438
+408,393,-506
+466,436,-512
+110,16,151
+-258,-428,682
+-393,719,612
+-211,-452,876
+808,-476,-593
+-575,615,604
+-485,667,467
+-680,325,-822
+-627,-443,-432
+872,-547,-609
+833,512,582
+807,604,487
+839,-516,451
+891,-625,532
+-652,-548,-490
+30,-46,-14
+";
+
+    const EXPECTED_BEACONS: &str = "\
+-892,524,684 -876,649,763 -838,591,734 -789,900,-551 -739,-1745,668 -706,-3180,-659
+-697,-3072,-689 -689,845,-530 -687,-1600,576 -661,-816,-575 -654,-3158,-753 -635,-1737,486
+-631,-672,1502 -624,-1620,1868 -620,-3212,371 -618,-824,-621 -612,-1695,1788 -601,-1648,-643
+-584,868,-557 -537,-823,-458 -532,-1715,1894 -518,-1681,-600 -499,-1607,-770 -485,-357,347
+-470,-3283,303 -456,-621,1527 -447,-329,318 -430,-3130,366 -413,-627,1469 -345,-311,381
+-36,-1284,1171 -27,-1108,-65 7,-33,-71 12,-2351,-103 26,-1119,1091 346,-2985,342 366,-3059,397
+377,-2827,367 390,-675,-793 396,-1931,-563 404,-588,-901 408,-1815,803 423,-701,434
+432,-2009,850 443,580,662 455,729,728 456,-540,1869 459,-707,401 465,-695,1988 474,580,667
+496,-1584,1900 497,-1838,-617 527,-524,1933 528,-643,409 534,-1912,768 544,-627,-890
+553,345,-567 564,392,-477 568,-2007,-577 605,-1665,1952 612,-1593,1893 630,319,-379
+686,-3108,-505 776,-3184,-501 846,-3110,-434 1135,-1161,1235 1243,-1093,1063 1660,-552,429
+1693,-557,386 1735,-437,1738 1749,-1800,1813 1772,-405,1572 1776,-675,371 1779,-442,1789
+1780,-1548,337 1786,-1538,337 1847,-1591,415 1889,-1729,1762 1994,-1805,1792";
+
+    fn position(s: &str) -> Position {
+        s.parse().unwrap()
+    }
+
+    fn test_registration() -> Registration {
+        Registration::new(&parse_scanners(TEST_INPUT).unwrap(), MATCH_THRESHOLD).unwrap()
+    }
+
+    #[test]
+    fn test_parse_position() {
+        assert_eq!(position("11,-22,-33"), Position::new(11, -22, -33));
+        assert_eq!(position("11,-22,-33").to_string(), "11,-22,-33");
+        assert!("11,-22".parse::<Position>().is_err());
+        assert!("11,-22,-33,4".parse::<Position>().is_err());
+        assert!("11,x,-33".parse::<Position>().is_err());
+    }
+
+    #[test]
+    fn test_parse_scanners() {
+        let scanners = parse_scanners(TEST_INPUT).unwrap();
+
+        assert_eq!(scanners.len(), 5);
+        assert_eq!(scanners[4].id, 4);
+        assert_eq!(scanners[0].beacons.len(), 25);
+        assert_eq!(scanners[4].beacons.len(), 26);
+        assert!(scanners[0].beacons.contains(&position("-345,-311,381")));
+        assert!(!scanners[1].beacons.contains(&position("-345,-311,381")));
+        assert!(scanners[1].beacons.contains(&position("686,422,578")));
+        assert!(scanners[2].beacons.contains(&position("697,-426,-610")));
+        assert!(scanners[3].beacons.contains(&position("595,780,-596")));
+        assert!(scanners[4].beacons.contains(&position("30,-46,-14")));
+
+        assert!(parse_scanners("--- scanner 1 ---\n1,2,3\n").is_err());
+        assert!(parse_scanners("--- scanner 0 ---\n").is_err());
+        assert!(parse_scanners("--- scannr 0 ---\n1,2,3\n").is_err());
+        assert!(parse_scanners("--- scanner 0 ---\n1,2\n").is_err());
+    }
+
+    #[test]
+    fn test_all_rotations() {
+        let rotations = Rotation::all();
+        assert_eq!(rotations.len(), 24);
+        assert_eq!(rotations[0], Rotation::IDENTITY);
+
+        let original = position("8,0,7");
+        let rotated: BTreeSet<Position> = rotations.iter().map(|r| r.apply(&original)).collect();
+        assert_eq!(rotated.len(), 24);
+        for expected in ["8,0,7", "-8,-7,0", "-7,0,8", "7,0,8", "0,7,-8"] {
+            assert!(rotated.contains(&position(expected)));
+        }
+
+        // A mirror image is not a rotation.
+        let asymmetric = position("1,2,3");
+        assert!(rotations
+            .iter()
+            .all(|r| r.apply(&asymmetric) != position("-1,2,3")));
+    }
+
+    #[test]
+    fn test_compose() {
+        let rotations = Rotation::all();
+        let p = position("1,2,3");
+
+        for a in &rotations {
+            for b in &rotations {
+                let composed = a.compose(b);
+                assert!(rotations.contains(&composed));
+                assert_eq!(composed.apply(&p), a.apply(&b.apply(&p)));
+            }
+        }
+
+        let a = Transform {
+            rotation: rotations[5],
+            translation: position("10,-20,30"),
+        };
+        let b = Transform {
+            rotation: rotations[17],
+            translation: position("-4,5,6"),
+        };
+        assert_eq!(a.compose(&b).apply(&p), a.apply(&b.apply(&p)));
+    }
+
+    #[test]
+    fn test_align() {
+        let scanners = parse_scanners(TEST_INPUT).unwrap();
+
+        let transform = scanners[0].align(&scanners[1], 12).unwrap().unwrap();
+        assert_eq!(transform.translation, position("68,-1246,-43"));
+        assert_eq!(
+            transform.apply(&position("686,422,578")),
+            position("-618,-824,-621")
+        );
+        assert!(Rotation::all().contains(&transform.rotation));
+
+        assert_eq!(scanners[0].align(&scanners[2], 12), Ok(None));
+        assert!(scanners[0].shared_distances(&scanners[1]) >= 66);
+    }
+
+    #[test]
+    fn test_registration_positions() {
+        let registration = test_registration();
+
+        assert!(registration.is_connected());
+        assert_eq!(registration.groups(), [vec![0, 1, 2, 3, 4]]);
+
+        let positions: Vec<Position> = (0..5)
+            .map(|id| registration.transform(id).translation)
+            .collect();
+        assert_eq!(
+            positions,
+            [
+                "0,0,0",
+                "68,-1246,-43",
+                "1105,-1205,1229",
+                "-92,-2380,-20",
+                "-20,-1133,1061"
+            ]
+            .map(position)
+        );
+    }
+
+    #[test]
+    fn test_merged_map() {
+        let expected: BTreeSet<Position> =
+            EXPECTED_BEACONS.split_whitespace().map(position).collect();
+
+        let registration = test_registration();
+        assert_eq!(registration.beacons(0).len(), 79);
+        assert_eq!(registration.beacons(0), &expected);
+    }
+
+    #[test]
+    fn test_max_scanner_distance() {
+        assert_eq!(
+            position("1105,-1205,1229").manhattan_distance(&position("-92,-2380,-20")),
+            3621
+        );
+        assert_eq!(test_registration().max_scanner_distance(0), 3621);
+    }
+
+    #[test]
+    fn test_disconnected_groups() {
+        // Scanner 2 only overlaps with scanner 4, which is removed.
+        let scanners = parse_scanners(TEST_INPUT).unwrap()[..3].to_vec();
+        let registration = Registration::new(&scanners, MATCH_THRESHOLD).unwrap();
+
+        assert!(!registration.is_connected());
+        assert_eq!(registration.groups(), [vec![0, 1], vec![2]]);
+        assert_eq!(registration.describe_groups(), "[0, 1], [2]");
+        assert_eq!(registration.group_of(2), 1);
+        assert_eq!(registration.transform(2), &Transform::IDENTITY);
+        assert_eq!(registration.beacons(1).len(), scanners[2].beacons.len());
+        assert_eq!(registration.max_scanner_distance(1), 0);
+    }
+
+    #[test]
+    fn test_match_threshold() {
+        let scanners = parse_scanners(TEST_INPUT).unwrap();
+
+        let registration = Registration::new(&scanners, 13).unwrap();
+        assert_eq!(registration.groups().len(), 5);
+
+        assert!(Registration::new(&scanners, 0).is_err());
+
+        // A low threshold still finds the best alignment of each overlapping pair.
+        let expected = test_registration();
+        let registration = Registration::new(&scanners, 3).unwrap();
+        assert!(registration.is_connected());
+        assert_eq!(registration.beacons(0), expected.beacons(0));
+        for id in 0..scanners.len() {
+            assert_eq!(registration.transform(id), expected.transform(id));
+        }
+
+        // With a threshold of 1, scanners that do not overlap match equally well in many ways.
+        assert!(Registration::new(&scanners, 1).is_err());
+    }
+}
@@ -0,0 +1,522 @@
+//! Polymer pair insertion, as described in Advent of Code 2021 Day 14.
+//! https://adventofcode.com/2021/day/14
+//!
+//! Each step, the element given by the pair insertion rules is inserted between every pair of
+//! adjacent elements in the polymer, so its length almost doubles every step. Rather than
+//! building the polymer, only the number of each pair of adjacent elements is tracked: each step,
+//! every pair `AB` with the rule `AB -> C` becomes one `AC` pair and one `CB` pair. That is a
+//! linear transformation of the pair counts, so it is held as a `CountMatrix` and raised to the
+//! power of the number of steps. Pairs with no rule are left unchanged.
+//!
+//! Every element in the polymer is the first element of exactly one pair, apart from the last
+//! element, which never changes. So the count of each element is the sum of the counts of the
+//! pairs starting with it, plus one for the last element.
+//!
+//! Counts reduced by `CountMode::Modulo` cannot be compared to find the most and least common
+//! elements, so in that mode the matrix is also raised to the same power with each entry held as
+//! its natural logarithm, giving approximate counts that can be compared however large they are.
+//! This finds the right elements unless two counts differ by only a tiny fraction.
+
+use crate::big_uint::BigUint;
+use crate::count_matrix::{CountMatrix, CountMode};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::str::FromStr;
+
+const INPUT_TOKEN_RULE_SEPARATOR: &str = " -> ";
+
+pub type Element = char;
+pub type Pair = [Element; 2];
+
+/// A polymer template and its pair insertion rules.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Polymer {
+    template: Vec<Element>,
+    rules: HashMap<Pair, Element>,
+}
+
+/// The number of each element in a polymer after a number of steps.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ElementCounts {
+    mode: CountMode,
+    /// The count of each element that is in the polymer.
+    counts: BTreeMap<Element, BigUint>,
+    /// The elements in the polymer from least to most common.
+    ranking: Vec<Element>,
+}
+
+impl FromStr for Polymer {
+    type Err = String;
+
+    /// Parses a template on the first line, then a blank line, then one pair insertion rule per
+    /// line, e.g., "CH -> B".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+
+        let template: Vec<Element> = lines.next().unwrap_or_default().trim().chars().collect();
+        if template.is_empty() {
+            return Err("The polymer template is missing".to_string());
+        }
+        if lines.next().is_some_and(|line| !line.trim().is_empty()) {
+            return Err("Expected a blank line after the polymer template".to_string());
+        }
+
+        let mut rules = HashMap::new();
+        for line in lines.filter(|line| !line.trim().is_empty()) {
+            let malformed = || format!("Malformed insertion rule: {}", line);
+            let (pair, element) = line
+                .trim()
+                .split_once(INPUT_TOKEN_RULE_SEPARATOR)
+                .ok_or_else(malformed)?;
+
+            let pair: Vec<Element> = pair.chars().collect();
+            let element: Vec<Element> = element.chars().collect();
+            match (pair.as_slice(), element.as_slice()) {
+                (&[first, second], &[element]) => {
+                    if rules.insert([first, second], element).is_some() {
+                        return Err(format!("Duplicate insertion rule: {}", line));
+                    }
+                }
+                _ => return Err(malformed()),
+            }
+        }
+
+        Ok(Self { template, rules })
+    }
+}
+
+impl Polymer {
+    pub fn template(&self) -> String {
+        self.template.iter().collect()
+    }
+
+    /// Returns the element inserted between the elements of `pair`, if there is a rule for it.
+    pub fn rule(&self, pair: &Pair) -> Option<Element> {
+        self.rules.get(pair).copied()
+    }
+
+    /// Returns the number of each pair of adjacent elements after `steps` steps, leaving out
+    /// pairs with a count of zero in `CountMode::Exact`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mode` has a modulus of zero.
+    pub fn pair_counts(&self, steps: u64, mode: CountMode) -> BTreeMap<Pair, BigUint> {
+        let pairs = self.reachable_pairs();
+        let counts = self
+            .transition_matrix(&pairs)
+            .power(steps, mode)
+            .apply(&self.template_pair_counts(&pairs), mode);
+
+        pairs
+            .into_iter()
+            .zip(counts)
+            .filter(|(_, count)| mode != CountMode::Exact || !count.is_zero())
+            .collect()
+    }
+
+    /// Returns the number of each element after `steps` steps.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mode` has a modulus of zero.
+    pub fn element_counts(&self, steps: u64, mode: CountMode) -> ElementCounts {
+        let pair_counts = self.pair_counts(steps, mode);
+        let last = *self.template.last().expect("The template is never empty");
+
+        let mut counts = BTreeMap::new();
+        for (pair, count) in pair_counts {
+            let total = counts.entry(pair[0]).or_insert_with(BigUint::zero);
+            *total = &*total + &count;
+        }
+        let total = counts.entry(last).or_insert_with(BigUint::zero);
+        *total = &*total + &BigUint::from(1);
+
+        let mut ranking: Vec<Element>;
+        match mode {
+            CountMode::Exact => {
+                counts.retain(|_, count| !count.is_zero());
+                ranking = counts.keys().copied().collect();
+                ranking.sort_by(|a, b| counts[a].cmp(&counts[b]));
+            }
+            CountMode::Modulo(_) => {
+                let approximate = self.approximate_element_counts(steps);
+                counts.retain(|element, _| approximate.contains_key(element));
+                for count in counts.values_mut() {
+                    *count = mode.reduce(count.clone());
+                }
+                ranking = approximate.keys().copied().collect();
+                ranking.sort_by(|a, b| approximate[a].total_cmp(&approximate[b]));
+            }
+        }
+
+        ElementCounts {
+            mode,
+            counts,
+            ranking,
+        }
+    }
+
+    /// Returns every pair that can ever be in the polymer, in order.
+    fn reachable_pairs(&self) -> Vec<Pair> {
+        let mut pairs: BTreeSet<Pair> = self.template.windows(2).map(|p| [p[0], p[1]]).collect();
+        let mut to_visit: Vec<Pair> = pairs.iter().copied().collect();
+
+        while let Some(pair) = to_visit.pop() {
+            if let Some(element) = self.rule(&pair) {
+                for new_pair in [[pair[0], element], [element, pair[1]]] {
+                    if pairs.insert(new_pair) {
+                        to_visit.push(new_pair);
+                    }
+                }
+            }
+        }
+
+        pairs.into_iter().collect()
+    }
+
+    /// Returns the counts of each of `pairs` in the template.
+    fn template_pair_counts(&self, pairs: &[Pair]) -> Vec<BigUint> {
+        let mut counts = vec![BigUint::zero(); pairs.len()];
+        for window in self.template.windows(2) {
+            let index = pair_index(pairs, &[window[0], window[1]]);
+            counts[index] = &counts[index] + &BigUint::from(1);
+        }
+        counts
+    }
+
+    /// Returns the matrix giving the pairs each of `pairs` becomes after one step.
+    fn transition_matrix(&self, pairs: &[Pair]) -> CountMatrix {
+        let mut matrix = CountMatrix::zero(pairs.len());
+
+        for (from, pair) in pairs.iter().enumerate() {
+            match self.rule(pair) {
+                Some(element) => {
+                    matrix.add(pair_index(pairs, &[pair[0], element]), from, 1);
+                    matrix.add(pair_index(pairs, &[element, pair[1]]), from, 1);
+                }
+                None => matrix.add(from, from, 1),
+            }
+        }
+
+        matrix
+    }
+
+    /// Returns the natural logarithm of the count of each element after `steps` steps, leaving
+    /// out elements with a count of zero.
+    fn approximate_element_counts(&self, steps: u64) -> BTreeMap<Element, f64> {
+        let pairs = self.reachable_pairs();
+        let matrix = LogMatrix::from(&self.transition_matrix(&pairs)).power(steps);
+        let start = self.template_pair_counts(&pairs);
+
+        let mut terms: BTreeMap<Element, Vec<f64>> = BTreeMap::new();
+        for (row, pair) in pairs.iter().enumerate() {
+            let pair_terms = start.iter().enumerate().filter_map(|(column, count)| {
+                let count = count.to_u64().expect("Template pair counts are small") as f64;
+                (count > 0.0).then(|| matrix.get(row, column) + count.ln())
+            });
+            terms.entry(pair[0]).or_default().extend(pair_terms);
+        }
+        let last = *self.template.last().expect("The template is never empty");
+        terms.entry(last).or_default().push(0.0);
+
+        terms
+            .into_iter()
+            .map(|(element, terms)| (element, log_sum_exp(terms)))
+            .filter(|(_, count)| count.is_finite())
+            .collect()
+    }
+}
+
+impl ElementCounts {
+    /// Returns the count of `element`, reduced by the `CountMode` used, or `None` if it is not in
+    /// the polymer.
+    pub fn count(&self, element: Element) -> Option<&BigUint> {
+        self.counts.get(&element)
+    }
+
+    pub fn most_common(&self) -> Option<Element> {
+        self.ranking.last().copied()
+    }
+
+    pub fn least_common(&self) -> Option<Element> {
+        self.ranking.first().copied()
+    }
+
+    /// Returns the count of the most common element minus the count of the least common, reduced
+    /// by the `CountMode` used, as in the challenge answer.
+    pub fn most_minus_least(&self) -> Option<BigUint> {
+        let most = self.count(self.most_common()?)?;
+        let least = self.count(self.least_common()?)?;
+
+        match self.mode {
+            CountMode::Exact => most.checked_sub(least),
+            CountMode::Modulo(modulus) => {
+                let most = most + &BigUint::from(modulus);
+                Some(self.mode.reduce(most.checked_sub(least)?))
+            }
+        }
+    }
+}
+
+/// Returns the position of `pair` in the sorted `pairs`.
+fn pair_index(pairs: &[Pair], pair: &Pair) -> usize {
+    pairs
+        .binary_search(pair)
+        .expect("Every pair produced by a rule is reachable")
+}
+
+/// Returns the natural logarithm of the sum of the numbers whose natural logarithms are `terms`,
+/// without the numbers themselves ever overflowing.
+fn log_sum_exp(terms: impl IntoIterator<Item = f64>) -> f64 {
+    let terms: Vec<f64> = terms.into_iter().collect();
+    let max = terms.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if max == f64::NEG_INFINITY {
+        return max;
+    }
+    max + terms
+        .iter()
+        .map(|term| (term - max).exp())
+        .sum::<f64>()
+        .ln()
+}
+
+/// A square matrix holding the natural logarithm of each entry, where a zero entry is held as
+/// negative infinity.
+#[derive(Clone, Debug)]
+struct LogMatrix {
+    size: usize,
+    entries: Vec<f64>,
+}
+
+impl From<&CountMatrix> for LogMatrix {
+    fn from(matrix: &CountMatrix) -> Self {
+        let size = matrix.size();
+        let entries = (0..size * size)
+            .map(|i| {
+                let entry = matrix.get(i / size, i % size);
+                (entry.to_u64().expect("Transition entries are small") as f64).ln()
+            })
+            .collect();
+
+        Self { size, entries }
+    }
+}
+
+impl LogMatrix {
+    fn identity(size: usize) -> Self {
+        let entries = (0..size * size)
+            .map(|i| {
+                if i / size == i % size {
+                    0.0
+                } else {
+                    f64::NEG_INFINITY
+                }
+            })
+            .collect();
+
+        Self { size, entries }
+    }
+
+    fn get(&self, row: usize, column: usize) -> f64 {
+        self.entries[row * self.size + column]
+    }
+
+    fn multiply(&self, other: &Self) -> Self {
+        let size = self.size;
+        let entries = (0..size * size)
+            .map(|i| {
+                let (row, column) = (i / size, i % size);
+                log_sum_exp((0..size).map(|k| self.get(row, k) + other.get(k, column)))
+            })
+            .collect();
+
+        Self { size, entries }
+    }
+
+    fn power(&self, mut exponent: u64) -> Self {
+        let mut result = Self::identity(self.size);
+        let mut square = self.clone();
+
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.multiply(&square);
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                square = square.multiply(&square);
+            }
+        }
+
+        result
+    }
+}
+
+// Test data based on examples on the challenge page.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "\
+NNCB
+
+CH -> B
+HH -> N
+CB -> H
+NH -> C
+HB -> C
+HC -> B
+HN -> C
+NN -> C
+BH -> H
+NC -> B
+NB -> B
+BN -> B
+BB -> N
+BC -> B
+CC -> N
+CN -> C";
+
+    const MODULUS: u64 = 1_000_000_007;
+
+    fn test_polymer() -> Polymer {
+        TEST_INPUT.parse().unwrap()
+    }
+
+    /// Returns the exact counts of the pairs in `polymer`.
+    fn count_pairs(polymer: &str) -> BTreeMap<Pair, BigUint> {
+        let polymer: Vec<Element> = polymer.chars().collect();
+        let mut counts = BTreeMap::new();
+        for window in polymer.windows(2) {
+            let count = counts
+                .entry([window[0], window[1]])
+                .or_insert_with(BigUint::zero);
+            *count = &*count + &BigUint::from(1);
+        }
+        counts
+    }
+
+    #[test]
+    fn test_parse() {
+        let polymer = test_polymer();
+
+        assert_eq!(polymer.template(), "NNCB");
+        assert_eq!(polymer.rule(&['C', 'H']), Some('B'));
+        assert_eq!(polymer.rule(&['C', 'N']), Some('C'));
+        assert_eq!(polymer.rule(&['N', 'X']), None);
+        assert_eq!(polymer.rules.len(), 16);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!("".parse::<Polymer>().is_err());
+        assert!("NNCB\nCH -> B".parse::<Polymer>().is_err());
+        assert!("NNCB\n\nCH => B".parse::<Polymer>().is_err());
+        assert!("NNCB\n\nCHH -> B".parse::<Polymer>().is_err());
+        assert!("NNCB\n\nCH -> BB".parse::<Polymer>().is_err());
+        assert!("NNCB\n\nCH -> B\nCH -> N".parse::<Polymer>().is_err());
+    }
+
+    #[test]
+    fn test_pair_counts() {
+        let polymer = test_polymer();
+        let expected = [
+            "NNCB",
+            "NCNBCHB",
+            "NBCCNBBBCBHCB",
+            "NBBBCNCCNBBNBNBBCHBHHBCHB",
+            "NBBNBNBBCCNBCNCCNBBNBBNBBBNBBNBBCBHCBHHNHCBBCBHCB",
+        ];
+
+        for (steps, expected) in expected.iter().enumerate() {
+            assert_eq!(
+                polymer.pair_counts(steps as u64, CountMode::Exact),
+                count_pairs(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn test_element_counts() {
+        let polymer = test_polymer();
+
+        let counts = polymer.element_counts(4, CountMode::Exact);
+        assert_eq!(counts.count('B'), Some(&BigUint::from(23)));
+        assert_eq!(counts.count('C'), Some(&BigUint::from(10)));
+        assert_eq!(counts.count('H'), Some(&BigUint::from(5)));
+        assert_eq!(counts.count('N'), Some(&BigUint::from(11)));
+        assert_eq!(counts.count('X'), None);
+
+        let counts = polymer.element_counts(10, CountMode::Exact);
+        assert_eq!(counts.most_common(), Some('B'));
+        assert_eq!(counts.least_common(), Some('H'));
+        assert_eq!(counts.count('B'), Some(&BigUint::from(1749)));
+        assert_eq!(counts.count('C'), Some(&BigUint::from(298)));
+        assert_eq!(counts.count('H'), Some(&BigUint::from(161)));
+        assert_eq!(counts.count('N'), Some(&BigUint::from(865)));
+        assert_eq!(counts.most_minus_least(), Some(BigUint::from(1588)));
+
+        let counts = polymer.element_counts(40, CountMode::Exact);
+        assert_eq!(counts.count('B'), Some(&BigUint::from(2192039569602)));
+        assert_eq!(counts.count('H'), Some(&BigUint::from(3849876073)));
+        assert_eq!(
+            counts.most_minus_least(),
+            Some(BigUint::from(2188189693529))
+        );
+    }
+
+    #[test]
+    fn test_element_counts_beyond_u64() {
+        let counts = test_polymer().element_counts(100, CountMode::Exact);
+
+        assert_eq!(counts.most_common(), Some('B'));
+        assert_eq!(counts.least_common(), Some('H'));
+        assert_eq!(
+            counts.most_minus_least().unwrap().to_string(),
+            "2535296262066596202993060773164"
+        );
+    }
+
+    #[test]
+    fn test_element_counts_modulo() {
+        let polymer = test_polymer();
+        let mode = CountMode::Modulo(MODULUS);
+
+        for steps in [0, 1, 10, 40, 100] {
+            let exact = polymer.element_counts(steps, CountMode::Exact);
+            let modulo = polymer.element_counts(steps, mode);
+
+            assert_eq!(modulo.most_common(), exact.most_common());
+            assert_eq!(modulo.least_common(), exact.least_common());
+            assert_eq!(
+                modulo.most_minus_least(),
+                exact
+                    .most_minus_least()
+                    .map(|difference| mode.reduce(difference))
+            );
+        }
+
+        let counts = polymer.element_counts(1_000_000_000_000, mode);
+        assert_eq!(counts.most_common(), Some('B'));
+        assert_eq!(counts.least_common(), Some('H'));
+        assert_eq!(counts.most_minus_least(), Some(BigUint::from(609751411)));
+    }
+
+    #[test]
+    fn test_missing_rules() {
+        // Pairs without a rule are never split, and elements only in rules that never apply are
+        // not in the polymer.
+        let polymer: Polymer = "ABA\n\nAB -> C\nCB -> C\nXY -> Z".parse().unwrap();
+        let counts = polymer.element_counts(3, CountMode::Exact);
+
+        // ABA, ACBA, ACCBA, ACCCBA.
+        assert_eq!(counts.count('C'), Some(&BigUint::from(3)));
+        assert_eq!(counts.count('Z'), None);
+        assert_eq!(counts.most_common(), Some('C'));
+
+        let counts = polymer.element_counts(3, CountMode::Modulo(2));
+        assert_eq!(counts.most_common(), Some('C'));
+        assert_eq!(counts.least_common(), Some('B'));
+        assert_eq!(counts.most_minus_least(), Some(BigUint::from(0)));
+        assert_eq!(counts.count('Z'), None);
+    }
+}
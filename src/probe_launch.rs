@@ -0,0 +1,462 @@
+//! Probe launches into a target area, as described in Advent of Code 2021 Day 17.
+//! https://adventofcode.com/2021/day/17
+//!
+//! A probe starts at the origin with an initial velocity. On each step it moves by its velocity,
+//! then drag moves its `x` velocity one towards zero, and gravity reduces its `y` velocity by
+//! one. A launch hits if the probe is within the target area after any step.
+//!
+//! The velocities worth trying are bounded using the shape of the motion. The `x` position moves
+//! away from the origin and stops after `|vx|` steps at the triangular number of `vx`, so `vx`
+//! can be neither so large that the first step overshoots the target nor so small that the probe
+//! stops short of it. A probe launched upwards with `vy` comes back down through the same heights
+//! it went up through, so it passes `y = 0` falling at `-vy - 1`, which must not skip past the
+//! target. If the target contains `y = 0`, every upwards launch passes through it, and there are
+//! infinitely many hits if some `x` velocity stops inside the target.
+
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+const INPUT_TOKEN_PREFIX: &str = "target area: x=";
+const INPUT_TOKEN_Y: &str = ", y=";
+const INPUT_TOKEN_RANGE: &str = "..";
+const INPUT_TOKEN_VELOCITY_SEPARATOR: char = ',';
+
+pub type Coordinate = i64;
+pub type Step = u64;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Position {
+    pub x: Coordinate,
+    pub y: Coordinate,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Velocity {
+    pub x: Coordinate,
+    pub y: Coordinate,
+}
+
+impl FromStr for Velocity {
+    type Err = String;
+
+    /// Parses a velocity of the form "7,2".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split_once(INPUT_TOKEN_VELOCITY_SEPARATOR)
+            .and_then(|(x, y)| {
+                Some(Velocity {
+                    x: x.trim().parse().ok()?,
+                    y: y.trim().parse().ok()?,
+                })
+            })
+            .ok_or_else(|| format!("Malformed velocity '{}'", s))
+    }
+}
+
+impl fmt::Display for Velocity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+/// An initial velocity that hits the target.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Hit {
+    pub velocity: Velocity,
+    /// The first step after which the probe is within the target.
+    pub step: Step,
+    /// The highest `y` position the probe reaches, including its starting position.
+    pub highest: Coordinate,
+}
+
+/// Returns the triangular number of `n`, i.e., `1 + 2 + ... + n`.
+fn triangular(n: Coordinate) -> Coordinate {
+    n * (n + 1) / 2
+}
+
+/// Returns the smallest non-negative `n` whose triangular number is at least `at_least`.
+fn triangular_root(at_least: Coordinate) -> Coordinate {
+    let mut n = (((8 * at_least.max(0) + 1) as f64).sqrt() as Coordinate - 1) / 2;
+    while triangular(n) < at_least {
+        n += 1;
+    }
+    while n > 0 && triangular(n - 1) >= at_least {
+        n -= 1;
+    }
+    n
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Target {
+    x: RangeInclusive<Coordinate>,
+    y: RangeInclusive<Coordinate>,
+}
+
+impl FromStr for Target {
+    type Err = String;
+
+    /// Parses a target area of the form "target area: x=20..30, y=-10..-5". Each range may be
+    /// given in either order.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let malformed = || format!("Malformed target area: {}", s.trim());
+        let parse_range = |s: &str| -> Option<RangeInclusive<Coordinate>> {
+            let (a, b) = s.split_once(INPUT_TOKEN_RANGE)?;
+            let (a, b): (Coordinate, Coordinate) = (a.parse().ok()?, b.parse().ok()?);
+            Some(a.min(b)..=a.max(b))
+        };
+
+        let (x, y) = s
+            .trim()
+            .strip_prefix(INPUT_TOKEN_PREFIX)
+            .and_then(|s| s.split_once(INPUT_TOKEN_Y))
+            .ok_or_else(malformed)?;
+
+        Ok(Self {
+            x: parse_range(x).ok_or_else(malformed)?,
+            y: parse_range(y).ok_or_else(malformed)?,
+        })
+    }
+}
+
+impl Target {
+    pub fn new(x: RangeInclusive<Coordinate>, y: RangeInclusive<Coordinate>) -> Self {
+        Self { x, y }
+    }
+
+    pub fn x(&self) -> &RangeInclusive<Coordinate> {
+        &self.x
+    }
+
+    pub fn y(&self) -> &RangeInclusive<Coordinate> {
+        &self.y
+    }
+
+    pub fn contains(&self, position: &Position) -> bool {
+        self.x.contains(&position.x) && self.y.contains(&position.y)
+    }
+
+    /// Returns whether a probe at `position` moving with `velocity` can never reach the target.
+    fn is_passed(&self, position: &Position, velocity: &Velocity) -> bool {
+        let falling_below = position.y < *self.y.start() && velocity.y < 0;
+        let moving_away = (position.x > *self.x.end() && velocity.x >= 0)
+            || (position.x < *self.x.start() && velocity.x <= 0);
+        falling_below || moving_away
+    }
+
+    /// Returns the ranges of initial `x` and `y` velocities that can hit the target.
+    ///
+    /// Returns an error if infinitely many velocities hit the target.
+    pub fn velocity_bounds(
+        &self,
+    ) -> Result<(RangeInclusive<Coordinate>, RangeInclusive<Coordinate>), String> {
+        let (x_min, x_max) = (*self.x.start(), *self.x.end());
+        let (y_min, y_max) = (*self.y.start(), *self.y.end());
+
+        let x_bounds = if x_min > 0 {
+            triangular_root(x_min)..=x_max
+        } else if x_max < 0 {
+            x_min..=-triangular_root(-x_max)
+        } else {
+            x_min..=x_max
+        };
+
+        let y_bounds = if y_min > 0 {
+            triangular_root(y_min)..=y_max
+        } else if y_max < 0 {
+            y_min..=-y_min - 1
+        } else {
+            // Every upwards launch passes through `y = 0` within the target, so only the time
+            // the `x` position spends within the target limits how high a launch can go.
+            let stops_inside = x_bounds
+                .clone()
+                .any(|vx| self.x.contains(&(vx.signum() * triangular(vx.abs()))));
+            if stops_inside {
+                return Err("Infinitely many initial velocities hit the target".to_string());
+            }
+            let last_step_within_x = x_min.abs().max(x_max.abs());
+            y_min..=y_max.max(-y_min - 1).max(last_step_within_x)
+        };
+
+        Ok((x_bounds, y_bounds))
+    }
+
+    /// Returns the positions of a probe launched with `velocity` after each step, until it is
+    /// within the target or can never reach it.
+    pub fn trajectory(&self, velocity: Velocity) -> Vec<Position> {
+        let mut position = Position { x: 0, y: 0 };
+        let mut velocity = velocity;
+        let mut positions = Vec::new();
+
+        loop {
+            position.x += velocity.x;
+            position.y += velocity.y;
+            velocity.x -= velocity.x.signum();
+            velocity.y -= 1;
+            positions.push(position);
+
+            if self.contains(&position) || self.is_passed(&position, &velocity) {
+                return positions;
+            }
+        }
+    }
+
+    /// Returns the details of the hit if a probe launched with `velocity` hits the target, or
+    /// `None` if it misses.
+    pub fn hit(&self, velocity: Velocity) -> Option<Hit> {
+        let trajectory = self.trajectory(velocity);
+        let last = trajectory.last()?;
+
+        self.contains(last).then(|| Hit {
+            velocity,
+            step: trajectory.len() as Step,
+            highest: triangular(velocity.y.max(0)),
+        })
+    }
+
+    /// Returns every initial velocity that hits the target, ordered by `x` and then `y`.
+    ///
+    /// Returns an error if infinitely many velocities hit the target.
+    pub fn hits(&self) -> Result<Vec<Hit>, String> {
+        let (x_bounds, y_bounds) = self.velocity_bounds()?;
+
+        Ok(x_bounds
+            .flat_map(|x| y_bounds.clone().map(move |y| Velocity { x, y }))
+            .filter_map(|velocity| self.hit(velocity))
+            .collect())
+    }
+
+    /// Returns the hit that reaches the highest `y` position, or `None` if no velocity hits. If
+    /// several hits reach the same height, the first in the order of `hits` is returned.
+    ///
+    /// Returns an error if infinitely many velocities hit the target.
+    pub fn highest_hit(&self) -> Result<Option<Hit>, String> {
+        Ok(self.hits()?.into_iter().rev().max_by_key(|hit| hit.highest))
+    }
+
+    /// Returns the bounds of the target, the origin and the trajectory of a probe launched with
+    /// `velocity`, as the minimum and maximum positions.
+    fn plot_bounds(&self, trajectory: &[Position]) -> (Position, Position) {
+        let corners = [
+            Position { x: 0, y: 0 },
+            Position {
+                x: *self.x.start(),
+                y: *self.y.start(),
+            },
+            Position {
+                x: *self.x.end(),
+                y: *self.y.end(),
+            },
+        ];
+        let all = || corners.iter().chain(trajectory);
+
+        (
+            Position {
+                x: all().map(|p| p.x).min().unwrap_or(0),
+                y: all().map(|p| p.y).min().unwrap_or(0),
+            },
+            Position {
+                x: all().map(|p| p.x).max().unwrap_or(0),
+                y: all().map(|p| p.y).max().unwrap_or(0),
+            },
+        )
+    }
+
+    /// Returns a plot of the trajectory of a probe launched with `velocity`, as in the challenge
+    /// examples, with 'S' at the origin, '#' at the probe's positions and 'T' in the target.
+    pub fn plot_ascii(&self, velocity: Velocity) -> String {
+        let trajectory = self.trajectory(velocity);
+        let (min, max) = self.plot_bounds(&trajectory);
+
+        let mut plot = String::new();
+        for y in (min.y..=max.y).rev() {
+            for x in min.x..=max.x {
+                let position = Position { x, y };
+                plot.push(if x == 0 && y == 0 {
+                    'S'
+                } else if trajectory.contains(&position) {
+                    '#'
+                } else if self.contains(&position) {
+                    'T'
+                } else {
+                    '.'
+                });
+            }
+            plot.push('\n');
+        }
+
+        plot
+    }
+
+    /// Returns an SVG image of the trajectory of a probe launched with `velocity`, as a line
+    /// through its positions, against the target as a rectangle. Up is positive `y`.
+    pub fn plot_svg(&self, velocity: Velocity) -> String {
+        let trajectory = self.trajectory(velocity);
+        let (min, max) = self.plot_bounds(&trajectory);
+
+        let points: Vec<String> = [Position { x: 0, y: 0 }]
+            .iter()
+            .chain(&trajectory)
+            .map(|p| format!("{},{}", p.x, -p.y))
+            .collect();
+
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">\n\
+             \x20 <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"lightblue\" />\n\
+             \x20 <polyline points=\"{}\" fill=\"none\" stroke=\"black\" stroke-width=\"0.2\" />\n\
+             </svg>\n",
+            min.x - 1,
+            -max.y - 1,
+            max.x - min.x + 2,
+            max.y - min.y + 2,
+            self.x.start(),
+            -self.y.end(),
+            self.x.end() - self.x.start(),
+            self.y.end() - self.y.start(),
+            points.join(" ")
+        )
+    }
+}
+
+// Test data based on examples on the challenge page.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "target area: x=20..30, y=-10..-5";
+
+    fn velocity(x: Coordinate, y: Coordinate) -> Velocity {
+        Velocity { x, y }
+    }
+
+    /// Returns every velocity within a generous square that hits `target`.
+    fn hits_slowly(target: &Target, bound: Coordinate) -> Vec<Velocity> {
+        (-bound..=bound)
+            .flat_map(|x| (-bound..=bound).map(move |y| velocity(x, y)))
+            .filter(|&v| target.hit(v).is_some())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let target: Target = TEST_INPUT.parse().unwrap();
+        assert_eq!(target, Target::new(20..=30, -10..=-5));
+
+        assert_eq!(
+            "target area: x=30..20, y=-5..-10".parse(),
+            Ok(Target::new(20..=30, -10..=-5))
+        );
+        assert!("target area: x=20..30".parse::<Target>().is_err());
+        assert!("target area: x=20-30, y=-10..-5".parse::<Target>().is_err());
+
+        assert_eq!("7,-2".parse(), Ok(velocity(7, -2)));
+        assert!("7".parse::<Velocity>().is_err());
+        assert_eq!(velocity(7, -2).to_string(), "7,-2");
+    }
+
+    #[test]
+    fn test_hit() {
+        let target: Target = TEST_INPUT.parse().unwrap();
+
+        assert_eq!(
+            target.hit(velocity(7, 2)),
+            Some(Hit {
+                velocity: velocity(7, 2),
+                step: 7,
+                highest: 3
+            })
+        );
+        assert_eq!(target.hit(velocity(6, 3)).map(|hit| hit.step), Some(9));
+        assert_eq!(target.hit(velocity(9, 0)).map(|hit| hit.step), Some(4));
+        assert_eq!(target.hit(velocity(17, -4)), None);
+    }
+
+    #[test]
+    fn test_hits() {
+        let target: Target = TEST_INPUT.parse().unwrap();
+        let hits = target.hits().unwrap();
+
+        assert_eq!(hits.len(), 112);
+        assert_eq!(
+            hits.iter().map(|hit| hit.velocity).collect::<Vec<_>>(),
+            hits_slowly(&target, 50)
+        );
+        assert_eq!(
+            target
+                .highest_hit()
+                .unwrap()
+                .map(|hit| (hit.velocity, hit.highest)),
+            Some((velocity(6, 9), 45))
+        );
+    }
+
+    #[test]
+    fn test_velocity_bounds() {
+        let target: Target = TEST_INPUT.parse().unwrap();
+        assert_eq!(target.velocity_bounds(), Ok((6..=30, -10..=9)));
+
+        // Targets to the left of, above, and around the origin.
+        for target in [
+            Target::new(-30..=-20, -10..=-5),
+            Target::new(20..=30, 5..=10),
+            Target::new(-30..=-20, 5..=10),
+            Target::new(-3..=4, -10..=-5),
+            Target::new(22..=27, -10..=3),
+            Target::new(-2..=2, 5..=10),
+        ] {
+            let (x_bounds, y_bounds) = target.velocity_bounds().unwrap();
+            let hits = target.hits().unwrap();
+            let expected = hits_slowly(&target, 50);
+
+            assert_eq!(
+                hits.iter().map(|hit| hit.velocity).collect::<Vec<_>>(),
+                expected
+            );
+            assert!(expected
+                .iter()
+                .all(|v| x_bounds.contains(&v.x) && y_bounds.contains(&v.y)));
+        }
+
+        // Probes launched at 7 in `x` stop within the target, and every upwards launch passes
+        // through `y = 0`, so those launches hit after any number of steps.
+        assert!(Target::new(20..=30, -10..=5).velocity_bounds().is_err());
+        assert!(Target::new(-1..=1, -1..=1).hits().is_err());
+    }
+
+    #[test]
+    fn test_plot_ascii() {
+        let target: Target = TEST_INPUT.parse().unwrap();
+
+        assert_eq!(
+            target.plot_ascii(velocity(7, 2)),
+            "\
+.............#....#............
+.......#..............#........
+...............................
+S........................#.....
+...............................
+...............................
+...........................#...
+...............................
+....................TTTTTTTTTTT
+....................TTTTTTTTTTT
+....................TTTTTTTT#TT
+....................TTTTTTTTTTT
+....................TTTTTTTTTTT
+....................TTTTTTTTTTT
+"
+        );
+    }
+
+    #[test]
+    fn test_plot_svg() {
+        let target: Target = TEST_INPUT.parse().unwrap();
+        let svg = target.plot_svg(velocity(7, 2));
+
+        assert!(
+            svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"-1 -4 32 15\">")
+        );
+        assert!(svg.contains("<rect x=\"20\" y=\"5\" width=\"10\" height=\"5\""));
+        assert!(svg.contains("points=\"0,0 7,-2 13,-3 18,-3 22,-2 25,0 27,3 28,7\""));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+}
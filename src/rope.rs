@@ -0,0 +1,559 @@
+//! A rope of knots pulled around a grid, as described in Advent of Code 2022 Day 9.
+//! https://adventofcode.com/2022/day/9
+//!
+//! The head of the rope is moved one cell at a time by a series of motions. After each step,
+//! every other knot that is no longer touching the knot in front of it moves one cell towards it,
+//! straight along a row or column if they share one and diagonally otherwise. The rope in part 1
+//! of the challenge has two knots, and the rope in part 2 has ten.
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+pub type Coordinate = i32;
+pub type Distance = u32;
+
+/// Knots further along the rope than this are labelled `LABEL_HIGH_KNOT` when rendered, as they
+/// have no single digit label.
+const MAX_DIGIT_LABEL: usize = 9;
+const LABEL_HIGH_KNOT: char = '*';
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Position {
+    pub x: Coordinate,
+    pub y: Coordinate,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Direction {
+    Down,
+    Left,
+    Right,
+    Up,
+}
+
+impl Direction {
+    /// Returns the change in `x` and `y` for one step in this direction. Up is positive `y`.
+    fn delta(&self) -> (Coordinate, Coordinate) {
+        match self {
+            Direction::Down => (0, -1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+            Direction::Up => (0, 1),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Motion {
+    pub direction: Direction,
+    pub distance: Distance,
+}
+
+impl FromStr for Motion {
+    type Err = String;
+
+    /// Parses a motion such as "R 6", which means "Right 6".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (direction, distance) = s
+            .trim()
+            .split_once(' ')
+            .ok_or_else(|| format!("Malformed motion '{}'", s))?;
+
+        let direction = match direction {
+            "D" => Direction::Down,
+            "L" => Direction::Left,
+            "R" => Direction::Right,
+            "U" => Direction::Up,
+            _ => return Err(format!("Unrecognized direction '{}'", direction)),
+        };
+        let distance = distance
+            .parse()
+            .map_err(|_| format!("Invalid distance in motion '{}'", s))?;
+
+        Ok(Self {
+            direction,
+            distance,
+        })
+    }
+}
+
+/// Displays the motion in the input format, e.g., "R 6".
+impl fmt::Display for Motion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let direction = match self.direction {
+            Direction::Down => 'D',
+            Direction::Left => 'L',
+            Direction::Right => 'R',
+            Direction::Up => 'U',
+        };
+        write!(f, "{} {}", direction, self.distance)
+    }
+}
+
+/// Returns the `Motion`s in `input`, one per line.
+pub fn parse_motions(input: &str) -> Result<Vec<Motion>, String> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// The smallest rectangle containing a set of positions, inclusive of both corners.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Bounds {
+    pub min: Position,
+    pub max: Position,
+}
+
+impl Bounds {
+    /// Returns the bounds of `positions`, or `None` if there are none.
+    pub fn of<'a>(positions: impl IntoIterator<Item = &'a Position>) -> Option<Self> {
+        positions.into_iter().fold(None, |bounds, p| {
+            Some(match bounds {
+                None => Bounds { min: *p, max: *p },
+                Some(Bounds { min, max }) => Bounds {
+                    min: Position {
+                        x: min.x.min(p.x),
+                        y: min.y.min(p.y),
+                    },
+                    max: Position {
+                        x: max.x.max(p.x),
+                        y: max.y.max(p.y),
+                    },
+                },
+            })
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rope {
+    /// The positions of the knots, starting with the head.
+    knots: Vec<Position>,
+    /// `visited[k]` holds every position knot `k` has been in, including where it started.
+    visited: Vec<HashSet<Position>>,
+}
+
+impl Rope {
+    /// Returns a rope of `knots` knots, all starting at the origin.
+    ///
+    /// Returns an error if `knots` is zero.
+    pub fn new(knots: usize) -> Result<Self, String> {
+        if knots == 0 {
+            return Err("A rope must have at least one knot".to_string());
+        }
+
+        let start = Position { x: 0, y: 0 };
+        Ok(Self {
+            knots: vec![start; knots],
+            visited: vec![HashSet::from([start]); knots],
+        })
+    }
+
+    /// Returns the positions of the knots, starting with the head.
+    pub fn knots(&self) -> &[Position] {
+        &self.knots
+    }
+
+    /// Returns the index of the last knot in the rope.
+    pub fn tail(&self) -> usize {
+        self.knots.len() - 1
+    }
+
+    /// Returns every position that knot `knot` has been in, or `None` if there is no such knot.
+    pub fn visited(&self, knot: usize) -> Option<&HashSet<Position>> {
+        self.visited.get(knot)
+    }
+
+    /// Returns the bounds of every position that any knot has been in.
+    pub fn bounds(&self) -> Bounds {
+        Bounds::of(self.visited.iter().flatten()).expect("A rope always has a starting position")
+    }
+
+    /// Moves the head of the rope one cell at a time as indicated by `motion`, updating the
+    /// following knots after each step.
+    pub fn execute_motion(&mut self, motion: &Motion) {
+        let (dx, dy) = motion.direction.delta();
+
+        for _ in 0..motion.distance {
+            self.knots[0].x += dx;
+            self.knots[0].y += dy;
+            self.visited[0].insert(self.knots[0]);
+
+            for k in 1..self.knots.len() {
+                if !Self::follow(self.knots[k - 1], &mut self.knots[k]) {
+                    // Knots further along cannot move if this one did not.
+                    break;
+                }
+                self.visited[k].insert(self.knots[k]);
+            }
+        }
+    }
+
+    /// Performs every `Motion` in `motions`.
+    pub fn execute_motions(&mut self, motions: &[Motion]) {
+        for motion in motions {
+            self.execute_motion(motion);
+        }
+    }
+
+    /// If `follower` is not adjacent to `leader`, moves it one cell closer, and returns whether it
+    /// moved. If they have the same `x` coordinates, only `follower`'s `y` coordinate is changed.
+    /// If they have the same `y` coordinates, only `follower`'s `x` coordinate is changed.
+    /// Otherwise `follower` moves diagonally.
+    fn follow(leader: Position, follower: &mut Position) -> bool {
+        let offset_horizontal = leader.x - follower.x;
+        let offset_vertical = leader.y - follower.y;
+
+        if offset_horizontal.abs() <= 1 && offset_vertical.abs() <= 1 {
+            return false;
+        }
+
+        let step = |offset: Coordinate| match offset.cmp(&0) {
+            Ordering::Greater => 1,
+            Ordering::Less => -1,
+            Ordering::Equal => 0,
+        };
+        follower.x += step(offset_horizontal);
+        follower.y += step(offset_vertical);
+        true
+    }
+
+    /// Returns the label used for knot `knot` when rendering the rope. The head is 'H'. In a rope
+    /// of two knots, the tail is 'T', otherwise knots are labelled by their index.
+    fn label(&self, knot: usize) -> char {
+        match knot {
+            0 => 'H',
+            1 if self.knots.len() == 2 => 'T',
+            k if k <= MAX_DIGIT_LABEL => char::from_digit(k as u32, 10).unwrap(),
+            _ => LABEL_HIGH_KNOT,
+        }
+    }
+
+    /// Returns the rope's current state as in the challenge examples, over the rectangle
+    /// `bounds`, with up as positive `y`. Where knots overlap, the one nearest the head is shown.
+    /// The starting position is shown as 's' if no knot covers it.
+    pub fn render(&self, bounds: &Bounds) -> String {
+        self.render_with(bounds, |p| {
+            self.knots
+                .iter()
+                .position(|knot| knot == p)
+                .map(|k| self.label(k))
+        })
+    }
+
+    /// Returns the positions that knot `knot` has been in as in the challenge examples, over the
+    /// rectangle `bounds`, with '#' for a visited position and 's' for the starting position.
+    /// Returns `None` if there is no such knot.
+    pub fn render_visited(&self, knot: usize, bounds: &Bounds) -> Option<String> {
+        let visited = self.visited(knot)?;
+        Some(self.render_with(bounds, |p| {
+            (p != &Position { x: 0, y: 0 } && visited.contains(p)).then_some('#')
+        }))
+    }
+
+    /// Returns a rendering of `bounds`, calling `cell` for the character at each position. Where
+    /// it returns `None`, the starting position is shown as 's' and any other as '.'.
+    fn render_with(&self, bounds: &Bounds, cell: impl Fn(&Position) -> Option<char>) -> String {
+        let mut output = String::new();
+
+        for y in (bounds.min.y..=bounds.max.y).rev() {
+            for x in bounds.min.x..=bounds.max.x {
+                let p = Position { x, y };
+                output.push(match cell(&p) {
+                    Some(c) => c,
+                    None if x == 0 && y == 0 => 's',
+                    None => '.',
+                });
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
+/// Options for modelling a rope, given on the command line.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RunOptions {
+    pub knots: usize,
+    /// The knot whose visited positions are counted, or `None` for the tail.
+    pub knot: Option<usize>,
+    /// Whether to print the rope after each motion.
+    pub show: bool,
+}
+
+impl RunOptions {
+    /// Parses the command line arguments `args`, which may contain "--knots <count>",
+    /// "--knot <index>" and "--show". The number of knots defaults to `default_knots`.
+    ///
+    /// Returns an error if an option is not recognized or its value is invalid, including a knot
+    /// index beyond the end of the rope.
+    pub fn from_args(args: &[String], default_knots: usize) -> Result<Self, String> {
+        let mut options = Self {
+            knots: default_knots,
+            knot: None,
+            show: false,
+        };
+
+        let mut args = args.iter();
+        while let Some(option) = args.next() {
+            if option == "--show" {
+                options.show = true;
+                continue;
+            }
+
+            let value = args
+                .next()
+                .ok_or_else(|| format!("Missing value for option '{}'", option))?;
+
+            match option.as_str() {
+                "--knots" => {
+                    options.knots = value
+                        .parse()
+                        .ok()
+                        .filter(|&knots| knots > 0)
+                        .ok_or_else(|| format!("Invalid number of knots '{}'", value))?
+                }
+                "--knot" => {
+                    options.knot = Some(
+                        value
+                            .parse()
+                            .map_err(|_| format!("Invalid knot '{}'", value))?,
+                    )
+                }
+                _ => return Err(format!("Unknown option '{}'", option)),
+            }
+        }
+
+        if let Some(knot) = options.knot.filter(|&knot| knot >= options.knots) {
+            return Err(format!(
+                "There is no knot {} in a rope of {} knots",
+                knot, options.knots
+            ));
+        }
+        Ok(options)
+    }
+}
+
+// Test data based on examples on the challenge page.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT_0: &str = "\
+R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2
+";
+
+    const TEST_INPUT_1: &str = "\
+R 5
+U 8
+L 8
+D 3
+R 17
+D 10
+L 25
+U 20
+";
+
+    const EXPECTED_SHORT_ROPE_VISITED: &str = "\
+..##..
+...##.
+.####.
+....#.
+s###..
+";
+
+    const EXPECTED_SHORT_ROPE_R_2: &str = "\
+......
+......
+.TH...
+......
+s.....
+";
+
+    const EXPECTED_LONG_ROPE_R_4: &str = "\
+......
+......
+......
+......
+4321H.
+";
+
+    const EXPECTED_LONG_ROPE_U_4: &str = "\
+....H.
+....1.
+..432.
+.5....
+6.....
+";
+
+    const EXPECTED_LONG_ROPE_TAIL_VISITED: &str = "\
+..........................
+..........................
+..........................
+..........................
+..........................
+..........................
+..........................
+..........................
+..........................
+#.........................
+#.............###.........
+#............#...#........
+.#..........#.....#.......
+..#..........#.....#......
+...#........#.......#.....
+....#......s.........#....
+.....#..............#.....
+......#............#......
+.......#..........#.......
+........#........#........
+.........########.........
+";
+
+    fn positions(coordinates: &[(Coordinate, Coordinate)]) -> Vec<Position> {
+        coordinates
+            .iter()
+            .map(|&(x, y)| Position { x, y })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_motions() {
+        let motions = parse_motions(TEST_INPUT_0).unwrap();
+
+        assert_eq!(motions.len(), 8);
+        assert_eq!(
+            motions[1],
+            Motion {
+                direction: Direction::Up,
+                distance: 4
+            }
+        );
+        assert_eq!(
+            motions.iter().map(Motion::to_string).collect::<Vec<_>>(),
+            TEST_INPUT_0.lines().collect::<Vec<_>>()
+        );
+
+        assert!(parse_motions("X 4").is_err());
+        assert!(parse_motions("R").is_err());
+        assert!(parse_motions("R -1").is_err());
+    }
+
+    #[test]
+    fn test_short_rope() {
+        let mut rope = Rope::new(2).unwrap();
+        rope.execute_motions(&parse_motions(TEST_INPUT_0).unwrap());
+
+        assert_eq!(rope.knots(), positions(&[(2, 2), (1, 2)]));
+        assert_eq!(rope.visited(rope.tail()).unwrap().len(), 13);
+        assert_eq!(
+            rope.render_visited(1, &rope.bounds()).unwrap(),
+            EXPECTED_SHORT_ROPE_VISITED
+        );
+        assert_eq!(rope.render(&rope.bounds()), EXPECTED_SHORT_ROPE_R_2);
+    }
+
+    #[test]
+    fn test_long_rope() {
+        let motions = parse_motions(TEST_INPUT_0).unwrap();
+        let mut rope = Rope::new(10).unwrap();
+
+        rope.execute_motion(&motions[0]);
+        assert_eq!(
+            rope.knots(),
+            positions(&[
+                (4, 0),
+                (3, 0),
+                (2, 0),
+                (1, 0),
+                (0, 0),
+                (0, 0),
+                (0, 0),
+                (0, 0),
+                (0, 0),
+                (0, 0)
+            ])
+        );
+
+        let bounds = Bounds {
+            min: Position { x: 0, y: 0 },
+            max: Position { x: 5, y: 4 },
+        };
+        assert_eq!(rope.render(&bounds), EXPECTED_LONG_ROPE_R_4);
+        rope.execute_motion(&motions[1]);
+        assert_eq!(rope.render(&bounds), EXPECTED_LONG_ROPE_U_4);
+
+        rope.execute_motions(&motions[2..]);
+        assert_eq!(rope.visited(rope.tail()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_visited_by_any_knot() {
+        let mut rope = Rope::new(10).unwrap();
+        rope.execute_motions(&parse_motions(TEST_INPUT_1).unwrap());
+
+        assert_eq!(rope.visited(9).unwrap().len(), 36);
+        assert_eq!(
+            rope.render_visited(9, &rope.bounds()).unwrap(),
+            EXPECTED_LONG_ROPE_TAIL_VISITED
+        );
+        assert!(rope.visited(10).is_none());
+
+        // Each knot visits no more positions than the one in front of it.
+        let counts: Vec<usize> = (0..10).map(|k| rope.visited(k).unwrap().len()).collect();
+        assert!(counts.windows(2).all(|pair| pair[0] >= pair[1]));
+
+        // The second knot of a long rope moves exactly as the tail of a short one.
+        let mut short_rope = Rope::new(2).unwrap();
+        short_rope.execute_motions(&parse_motions(TEST_INPUT_1).unwrap());
+        assert_eq!(rope.visited(1), short_rope.visited(1));
+    }
+
+    #[test]
+    fn test_single_knot() {
+        assert!(Rope::new(0).is_err());
+
+        let mut rope = Rope::new(1).unwrap();
+        rope.execute_motions(&parse_motions(TEST_INPUT_0).unwrap());
+        assert_eq!(rope.tail(), 0);
+        assert_eq!(rope.knots(), positions(&[(2, 2)]));
+    }
+
+    #[test]
+    fn test_run_options() {
+        let args = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+
+        assert_eq!(
+            RunOptions::from_args(&[], 2),
+            Ok(RunOptions {
+                knots: 2,
+                knot: None,
+                show: false
+            })
+        );
+        assert_eq!(
+            RunOptions::from_args(&args("--knots 20 --show --knot 5"), 2),
+            Ok(RunOptions {
+                knots: 20,
+                knot: Some(5),
+                show: true
+            })
+        );
+        assert!(RunOptions::from_args(&args("--knot 2"), 2).is_err());
+        assert!(RunOptions::from_args(&args("--knots 0"), 2).is_err());
+        assert!(RunOptions::from_args(&args("--knots"), 2).is_err());
+        assert!(RunOptions::from_args(&args("--length 3"), 2).is_err());
+    }
+}
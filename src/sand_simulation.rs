@@ -0,0 +1,606 @@
+//! Sand falling into a cave, as described in Advent of Code 2022 Day 14.
+//! https://adventofcode.com/2022/day/14
+//!
+//! Grains of sand enter the cave one at a time from one or more sources. Each grain falls
+//! straight down if it can, otherwise diagonally down and to the left, otherwise diagonally down
+//! and to the right, and comes to rest when it can do none of these. Without a floor, a grain
+//! that falls below the lowest rock falls forever. With a floor, which is a fixed number of rows
+//! below the lowest rock and infinitely wide, every grain eventually comes to rest.
+//!
+//! Every grain from a source follows the path of the grain before it until it reaches the cell
+//! where that grain came to rest. So rather than starting each grain at its source, the path of
+//! the previous grain is remembered and the next grain starts from the cell before the end of
+//! it. A grain from another source may come to rest on a remembered path, in which case the
+//! path is cut short at that cell.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+const INPUT_TOKEN_SEPARATOR: &str = " -> ";
+
+pub type Axis = i64;
+
+/// The source of sand in the challenge.
+pub const CHALLENGE_SOURCE: Point = Point { x: 500, y: 0 };
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Point {
+    pub x: Axis,
+    pub y: Axis,
+}
+
+impl FromStr for Point {
+    type Err = String;
+
+    /// Parses a pair of comma-separated integers, e.g., "500,0".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split_once(',')
+            .and_then(|(x, y)| {
+                Some(Self {
+                    x: x.trim().parse().ok()?,
+                    y: y.trim().parse().ok()?,
+                })
+            })
+            .ok_or_else(|| format!("Malformed point '{}'", s))
+    }
+}
+
+/// Possible contents of a cell. The default is `Air`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Cell {
+    #[default]
+    Air,
+    Rock,
+    Sand,
+}
+
+/// The result of dropping a grain of sand.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Drop {
+    /// The grain came to rest at the point held.
+    Rested(Point),
+    /// The grain fell below the lowest rock, with no floor to stop it.
+    FellForever,
+    /// The source is already filled with sand, so no grain could enter.
+    Blocked,
+}
+
+/// Maps `Point`s to their associated `Cell` contents.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Cave {
+    cells: HashMap<Point, Cell>,
+    /// The lowest row containing rock, which has the highest `y`, or `None` if there is no rock.
+    lowest_rock: Option<Axis>,
+}
+
+impl FromStr for Cave {
+    type Err = String;
+
+    /// Parses one path of rock per line, as points joined by straight lines, e.g.,
+    /// "498,4 -> 498,6 -> 496,6".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cave = Self::default();
+
+        for line in s.lines().filter(|line| !line.trim().is_empty()) {
+            let points = line
+                .trim()
+                .split(INPUT_TOKEN_SEPARATOR)
+                .map(str::parse)
+                .collect::<Result<Vec<Point>, _>>()?;
+
+            if let [point] = points.as_slice() {
+                cave.set(*point, Cell::Rock);
+            }
+            for line in points.windows(2) {
+                cave.add_line(&line[0], &line[1], Cell::Rock)?;
+            }
+        }
+
+        Ok(cave)
+    }
+}
+
+impl Cave {
+    pub fn get(&self, p: &Point) -> Cell {
+        *self.cells.get(p).unwrap_or(&Cell::Air)
+    }
+
+    pub fn set(&mut self, p: Point, value: Cell) {
+        self.cells.insert(p, value);
+
+        if value == Cell::Rock {
+            self.lowest_rock = Some(self.lowest_rock.map_or(p.y, |lowest| lowest.max(p.y)));
+        }
+    }
+
+    pub fn lowest_rock(&self) -> Option<Axis> {
+        self.lowest_rock
+    }
+
+    /// Creates a line of the given type of `Cell`, from the `start` point to the `end` inclusive.
+    /// `start` and `end` can be specified in either order.
+    ///
+    /// Returns an error if the line is neither horizontal nor vertical.
+    pub fn add_line(&mut self, start: &Point, end: &Point, value: Cell) -> Result<(), String> {
+        if start.x == end.x {
+            for y in start.y.min(end.y)..=start.y.max(end.y) {
+                self.set(Point { x: start.x, y }, value);
+            }
+        } else if start.y == end.y {
+            for x in start.x.min(end.x)..=start.x.max(end.x) {
+                self.set(Point { x, y: start.y }, value);
+            }
+        } else {
+            return Err(format!(
+                "Lines cannot be diagonal, as from {},{} to {},{}",
+                start.x, start.y, end.x, end.y
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SandSimulation {
+    cave: Cave,
+    sources: Vec<Point>,
+    /// The row of the floor, or `None` if there is no floor.
+    floor: Option<Axis>,
+    /// Whether each grain starts from the path of the previous grain from the same source.
+    path_memory: bool,
+    /// `paths[s]` holds the path of the last grain from source `s`, up to but not including
+    /// where it came to rest.
+    paths: Vec<Vec<Point>>,
+    rested: usize,
+}
+
+impl SandSimulation {
+    /// Returns a simulation of sand entering `cave` from `sources`. If `floor_offset` is given,
+    /// there is a floor that many rows below the lowest rock.
+    ///
+    /// Returns an error if there are no sources, or there is a floor but no rock to place it
+    /// below.
+    pub fn new(
+        cave: Cave,
+        sources: Vec<Point>,
+        floor_offset: Option<Axis>,
+    ) -> Result<Self, String> {
+        if sources.is_empty() {
+            return Err("There must be at least one source of sand".to_string());
+        }
+
+        let floor = match floor_offset {
+            None => None,
+            Some(offset) => Some(
+                cave.lowest_rock()
+                    .ok_or("A floor needs some rock to be placed below")?
+                    + offset,
+            ),
+        };
+
+        Ok(Self {
+            cave,
+            paths: vec![Vec::new(); sources.len()],
+            sources,
+            floor,
+            path_memory: true,
+            rested: 0,
+        })
+    }
+
+    /// Sets whether each grain starts from the path of the previous grain from the same source,
+    /// which is on by default. The results are the same either way, only slower without.
+    pub fn set_path_memory(&mut self, path_memory: bool) {
+        self.path_memory = path_memory;
+        for path in &mut self.paths {
+            path.clear();
+        }
+    }
+
+    pub fn cave(&self) -> &Cave {
+        &self.cave
+    }
+
+    /// Returns the number of grains of sand that have come to rest.
+    pub fn rested(&self) -> usize {
+        self.rested
+    }
+
+    /// Returns whether a grain can move into `p`.
+    fn is_open(&self, p: &Point) -> bool {
+        self.floor.is_none_or(|floor| p.y < floor) && self.cave.get(p) == Cell::Air
+    }
+
+    /// Drops one grain of sand from the source with index `source`, and returns what happened to
+    /// it.
+    pub fn drop_grain(&mut self, source: usize) -> Drop {
+        let start = self.sources[source];
+        if self.cave.get(&start) != Cell::Air {
+            return Drop::Blocked;
+        }
+
+        let mut path = if self.path_memory {
+            std::mem::take(&mut self.paths[source])
+        } else {
+            Vec::new()
+        };
+        if path.is_empty() {
+            path.push(start);
+        }
+
+        loop {
+            let position = *path.last().expect("A path always holds the source");
+
+            if self.floor.is_none() && self.cave.lowest_rock().is_none_or(|y| position.y >= y) {
+                if self.path_memory {
+                    self.paths[source] = path;
+                }
+                return Drop::FellForever;
+            }
+
+            let next = [0, -1, 1]
+                .iter()
+                .map(|dx| Point {
+                    x: position.x + dx,
+                    y: position.y + 1,
+                })
+                .find(|next| self.is_open(next));
+
+            match next {
+                Some(next) => path.push(next),
+                None => {
+                    path.pop();
+                    self.cave.set(position, Cell::Sand);
+                    self.rested += 1;
+
+                    // The grain may have landed on the remembered path of another source.
+                    for other_path in &mut self.paths {
+                        if let Some(i) = other_path.iter().position(|&p| p == position) {
+                            other_path.truncate(i);
+                        }
+                    }
+                    if self.path_memory {
+                        self.paths[source] = path;
+                    }
+                    return Drop::Rested(position);
+                }
+            }
+        }
+    }
+
+    /// Drops grains of sand from each source in turn, until every source is blocked or its
+    /// grains fall forever, and returns the number of grains that came to rest.
+    pub fn run(&mut self) -> usize {
+        self.run_with_frames(0, |_| {})
+    }
+
+    /// As `run`, but also calls `frame` with the simulation each time the number of grains that
+    /// have come to rest reaches a multiple of `every`, and at the end. If `every` is zero,
+    /// `frame` is only called at the end.
+    pub fn run_with_frames(&mut self, every: usize, mut frame: impl FnMut(&Self)) -> usize {
+        let mut active = vec![true; self.sources.len()];
+
+        while active.contains(&true) {
+            for (source, is_active) in active.iter_mut().enumerate() {
+                if !*is_active {
+                    continue;
+                }
+
+                match self.drop_grain(source) {
+                    Drop::Rested(_) => {
+                        if every > 0 && self.rested.is_multiple_of(every) {
+                            frame(self);
+                        }
+                    }
+                    Drop::FellForever | Drop::Blocked => *is_active = false,
+                }
+            }
+        }
+
+        if every == 0 || !self.rested.is_multiple_of(every) {
+            frame(self);
+        }
+        self.rested
+    }
+}
+
+/// Displays the cave in the format used by the challenge, with '#' for rock, 'o' for sand and
+/// '+' for a source not yet filled with sand. The floor is not shown.
+impl fmt::Display for SandSimulation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let points = || self.cave.cells.keys().chain(&self.sources);
+        let x_min = points().map(|p| p.x).min().unwrap_or(0);
+        let x_max = points().map(|p| p.x).max().unwrap_or(0);
+        let y_min = points().map(|p| p.y).min().unwrap_or(0);
+        let y_max = points().map(|p| p.y).max().unwrap_or(0);
+
+        for y in y_min..=y_max {
+            let row: String = (x_min..=x_max)
+                .map(|x| {
+                    let p = Point { x, y };
+                    match self.cave.get(&p) {
+                        Cell::Air if self.sources.contains(&p) => '+',
+                        Cell::Air => '.',
+                        Cell::Rock => '#',
+                        Cell::Sand => 'o',
+                    }
+                })
+                .collect();
+            writeln!(f, "{}", row)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Options for running a simulation, given on the command line.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RunOptions {
+    pub sources: Vec<Point>,
+    /// How many rows below the lowest rock the floor is, or `None` if there is no floor.
+    pub floor_offset: Option<Axis>,
+    /// How many grains come to rest between each display of the cave, or zero to never display
+    /// it.
+    pub frame_every: usize,
+    pub path_memory: bool,
+}
+
+impl RunOptions {
+    /// Parses the command line arguments `args`, which may contain "--source <x>,<y>" (given
+    /// once per source), "--floor <offset>", "--no-floor", "--frames <grains>" and
+    /// "--no-path-memory". The sources default to `CHALLENGE_SOURCE`, and the floor to
+    /// `default_floor_offset`.
+    pub fn from_args(args: &[String], default_floor_offset: Option<Axis>) -> Result<Self, String> {
+        let mut options = Self {
+            sources: Vec::new(),
+            floor_offset: default_floor_offset,
+            frame_every: 0,
+            path_memory: true,
+        };
+
+        let mut args = args.iter();
+        while let Some(option) = args.next() {
+            match option.as_str() {
+                "--no-floor" => {
+                    options.floor_offset = None;
+                    continue;
+                }
+                "--no-path-memory" => {
+                    options.path_memory = false;
+                    continue;
+                }
+                _ => {}
+            }
+
+            let value = args
+                .next()
+                .ok_or_else(|| format!("Missing value for option '{}'", option))?;
+
+            match option.as_str() {
+                "--source" => options.sources.push(value.parse()?),
+                "--floor" => {
+                    options.floor_offset = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|&offset| offset > 0)
+                            .ok_or_else(|| format!("Invalid floor offset '{}'", value))?,
+                    )
+                }
+                "--frames" => {
+                    options.frame_every = value
+                        .parse()
+                        .ok()
+                        .filter(|&every| every > 0)
+                        .ok_or_else(|| format!("Invalid number of grains '{}'", value))?
+                }
+                _ => return Err(format!("Unknown option '{}'", option)),
+            }
+        }
+
+        if options.sources.is_empty() {
+            options.sources.push(CHALLENGE_SOURCE);
+        }
+        Ok(options)
+    }
+}
+
+// Test data based on examples on the challenge page.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "\
+498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9
+";
+
+    const EXPECTED_OUTPUT_0: &str = "\
+......+...
+..........
+..........
+..........
+....#...##
+....#...#.
+..###...#.
+........#.
+........#.
+#########.
+";
+
+    const EXPECTED_OUTPUT_TURN_5: &str = "\
+......+...
+..........
+..........
+..........
+....#...##
+....#...#.
+..###...#.
+......o.#.
+....oooo#.
+#########.
+";
+
+    const EXPECTED_OUTPUT_TURN_24: &str = "\
+......+...
+..........
+......o...
+.....ooo..
+....#ooo##
+...o#ooo#.
+..###ooo#.
+....oooo#.
+.o.ooooo#.
+#########.
+";
+
+    // Edited from challenge to remove two-cell border on both left and right edges, and the row of
+    // rock at the bottom edge.
+    const EXPECTED_OUTPUT_FLOOR: &str = "\
+..........o..........
+.........ooo.........
+........ooooo........
+.......ooooooo.......
+......oo#ooo##o......
+.....ooo#ooo#ooo.....
+....oo###ooo#oooo....
+...oooo.oooo#ooooo...
+..oooooooooo#oooooo..
+.ooo#########ooooooo.
+ooooo.......ooooooooo
+";
+
+    fn simulation(floor_offset: Option<Axis>) -> SandSimulation {
+        let cave: Cave = TEST_INPUT.parse().unwrap();
+        SandSimulation::new(cave, vec![CHALLENGE_SOURCE], floor_offset).unwrap()
+    }
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let cave: Cave = TEST_INPUT.parse().unwrap();
+        assert_eq!(cave.get(&Point { x: 498, y: 5 }), Cell::Rock);
+        assert_eq!(cave.get(&Point { x: 497, y: 5 }), Cell::Air);
+        assert_eq!(cave.lowest_rock(), Some(9));
+        assert_eq!(simulation(None).to_string(), EXPECTED_OUTPUT_0);
+
+        assert!("498,4 -> 497,5".parse::<Cave>().is_err());
+        assert!("498,4 -> 498".parse::<Cave>().is_err());
+        assert!(SandSimulation::new(Cave::default(), vec![CHALLENGE_SOURCE], Some(2)).is_err());
+        assert!(SandSimulation::new(cave, vec![], None).is_err());
+    }
+
+    #[test]
+    fn test_drop_grain() {
+        let mut sim = simulation(None);
+
+        assert_eq!(sim.drop_grain(0), Drop::Rested(Point { x: 500, y: 8 }));
+        assert_eq!(sim.drop_grain(0), Drop::Rested(Point { x: 499, y: 8 }));
+        for _ in 0..3 {
+            sim.drop_grain(0);
+        }
+        assert_eq!(sim.to_string(), EXPECTED_OUTPUT_TURN_5);
+    }
+
+    #[test]
+    fn test_run_without_floor() {
+        let mut sim = simulation(None);
+        assert_eq!(sim.run(), 24);
+        assert_eq!(sim.to_string(), EXPECTED_OUTPUT_TURN_24);
+        assert_eq!(sim.drop_grain(0), Drop::FellForever);
+    }
+
+    #[test]
+    fn test_run_with_floor() {
+        let mut sim = simulation(Some(2));
+        assert_eq!(sim.run(), 93);
+        assert_eq!(sim.to_string(), EXPECTED_OUTPUT_FLOOR);
+        assert_eq!(sim.drop_grain(0), Drop::Blocked);
+
+        // A deeper floor lets the pile spread further before it reaches the source.
+        let mut sim = simulation(Some(4));
+        assert!(sim.run() > 93);
+    }
+
+    #[test]
+    fn test_path_memory() {
+        for floor_offset in [None, Some(2), Some(5)] {
+            let mut with_memory = simulation(floor_offset);
+            let mut without_memory = simulation(floor_offset);
+            without_memory.set_path_memory(false);
+
+            assert_eq!(with_memory.run(), without_memory.run());
+            assert_eq!(with_memory.cave(), without_memory.cave());
+        }
+    }
+
+    #[test]
+    fn test_multiple_sources() {
+        let cave: Cave = TEST_INPUT.parse().unwrap();
+        let sources = vec![Point { x: 497, y: 0 }, Point { x: 501, y: 2 }];
+
+        let mut with_memory = SandSimulation::new(cave.clone(), sources.clone(), Some(2)).unwrap();
+        let mut without_memory = SandSimulation::new(cave, sources, Some(2)).unwrap();
+        without_memory.set_path_memory(false);
+
+        let rested = with_memory.run();
+        assert_eq!(rested, without_memory.run());
+        assert_eq!(with_memory.cave(), without_memory.cave());
+
+        // Both sources end up buried, and sand from each reaches the other's side.
+        assert_eq!(with_memory.cave().get(&Point { x: 497, y: 0 }), Cell::Sand);
+        assert_eq!(with_memory.cave().get(&Point { x: 501, y: 2 }), Cell::Sand);
+    }
+
+    #[test]
+    fn test_frames() {
+        let mut sim = simulation(None);
+        let mut frames = Vec::new();
+        sim.run_with_frames(5, |sim| frames.push((sim.rested(), sim.to_string())));
+
+        assert_eq!(
+            frames.iter().map(|(rested, _)| *rested).collect::<Vec<_>>(),
+            [5, 10, 15, 20, 24]
+        );
+        assert_eq!(frames[0].1, EXPECTED_OUTPUT_TURN_5);
+        assert_eq!(frames[4].1, EXPECTED_OUTPUT_TURN_24);
+    }
+
+    #[test]
+    fn test_run_options() {
+        assert_eq!(
+            RunOptions::from_args(&[], Some(2)),
+            Ok(RunOptions {
+                sources: vec![CHALLENGE_SOURCE],
+                floor_offset: Some(2),
+                frame_every: 0,
+                path_memory: true,
+            })
+        );
+        assert_eq!(
+            RunOptions::from_args(
+                &args("--source 1,2 --no-floor --frames 10 --source 3,-4 --no-path-memory"),
+                Some(2)
+            ),
+            Ok(RunOptions {
+                sources: vec![Point { x: 1, y: 2 }, Point { x: 3, y: -4 }],
+                floor_offset: None,
+                frame_every: 10,
+                path_memory: false,
+            })
+        );
+        assert_eq!(
+            RunOptions::from_args(&args("--floor 5"), None).map(|o| o.floor_offset),
+            Ok(Some(5))
+        );
+        assert!(RunOptions::from_args(&args("--floor 0"), None).is_err());
+        assert!(RunOptions::from_args(&args("--frames"), None).is_err());
+        assert!(RunOptions::from_args(&args("--source 1"), None).is_err());
+        assert!(RunOptions::from_args(&args("--speed 3"), None).is_err());
+    }
+}
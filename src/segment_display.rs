@@ -0,0 +1,448 @@
+//! Segment displays with scrambled wiring, as described in Advent of Code 2021 Day 8.
+//! https://adventofcode.com/2021/day/8
+//!
+//! A display shows each symbol by lighting a fixed pattern of segments, given by a
+//! `DisplayTable`. The wires driving the segments have been connected in an unknown order, so the
+//! patterns observed on the wires are scrambled. Each wire is labelled with one of the segment
+//! labels, and a `Wiring` gives the segment each wire is really connected to.
+//!
+//! Rather than relying on deductions that only hold for the digits of a seven-segment display,
+//! the consistent wirings are found by searching over assignments of wires to segments. Each
+//! wire is assigned in turn, and a partial assignment is abandoned as soon as an observed pattern
+//! cannot be any of the table's patterns of the same size: either a wire in the observed pattern
+//! maps to a segment outside every such pattern, or a wire outside it maps to a segment inside
+//! every such pattern. This works for any table with up to 32 segments.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+const INPUT_TOKEN_OUTPUT_SEPARATOR: &str = "|";
+
+/// The largest number of segments a display can have, as segment sets are held as `u32` masks.
+const MAX_SEGMENTS: usize = 32;
+
+pub type Segment = char;
+pub type Symbol = char;
+
+/// The segments of a display, and the pattern of segments lit to show each symbol.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DisplayTable {
+    /// The segment labels in order, where segment `segments[i]` is bit `i` of a pattern.
+    segments: Vec<Segment>,
+    symbols: Vec<(Symbol, u32)>,
+}
+
+/// The segment each wire of a display is connected to.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Wiring {
+    wire_to_segment: BTreeMap<char, Segment>,
+}
+
+/// One display's observed wire patterns, and the patterns of its output value.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Entry {
+    pub patterns: Vec<String>,
+    pub outputs: Vec<String>,
+}
+
+impl DisplayTable {
+    /// Returns a table where each symbol is shown by the segments listed with it, e.g.,
+    /// `('1', "cf")`. The display's segments are all those used by any symbol.
+    ///
+    /// Returns an error if a symbol is listed twice, two symbols share a pattern, or there are
+    /// more than 32 segments.
+    pub fn new(symbols: &[(Symbol, &str)]) -> Result<Self, String> {
+        let mut segments: Vec<Segment> = symbols.iter().flat_map(|(_, s)| s.chars()).collect();
+        segments.sort_unstable();
+        segments.dedup();
+        if segments.len() > MAX_SEGMENTS {
+            return Err(format!(
+                "A display cannot have more than {} segments",
+                MAX_SEGMENTS
+            ));
+        }
+
+        let mut table = Self {
+            segments,
+            symbols: Vec::with_capacity(symbols.len()),
+        };
+        for &(symbol, pattern) in symbols {
+            let mask = table.mask(pattern)?;
+            if let Some((other, _)) = table
+                .symbols
+                .iter()
+                .find(|&&(s, m)| s == symbol || m == mask)
+            {
+                return Err(format!(
+                    "Symbol '{}' has the same symbol or pattern as '{}'",
+                    symbol, other
+                ));
+            }
+            table.symbols.push((symbol, mask));
+        }
+
+        Ok(table)
+    }
+
+    /// Returns the table for the digits of a standard seven-segment display.
+    pub fn seven_segment_digits() -> Self {
+        Self::new(&[
+            ('0', "abcefg"),
+            ('1', "cf"),
+            ('2', "acdeg"),
+            ('3', "acdfg"),
+            ('4', "bcdf"),
+            ('5', "abdfg"),
+            ('6', "abdefg"),
+            ('7', "acf"),
+            ('8', "abcdefg"),
+            ('9', "abcdfg"),
+        ])
+        .expect("The seven-segment table is valid")
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Returns the symbol shown by lighting `segments`, if any.
+    pub fn symbol(&self, segments: &str) -> Option<Symbol> {
+        let mask = self.mask(segments).ok()?;
+        self.symbols
+            .iter()
+            .find(|&&(_, m)| m == mask)
+            .map(|&(symbol, _)| symbol)
+    }
+
+    /// Returns every wiring under which each of `observed` is the pattern of some symbol.
+    ///
+    /// Returns an error if an observed pattern uses a wire that is not labelled with one of the
+    /// display's segments.
+    pub fn wirings(&self, observed: &[&str]) -> Result<Vec<Wiring>, String> {
+        let observed = observed
+            .iter()
+            .map(|pattern| self.mask(pattern))
+            .collect::<Result<Vec<u32>, String>>()?;
+
+        // For each observed pattern, the segments in every symbol pattern of the same size, and
+        // the segments in any of them.
+        let constraints: Vec<(u32, u32, u32)> = observed
+            .iter()
+            .map(|&wires| {
+                let candidates = self
+                    .symbols
+                    .iter()
+                    .map(|&(_, mask)| mask)
+                    .filter(|mask| mask.count_ones() == wires.count_ones());
+                let (all, any) =
+                    candidates.fold((u32::MAX, 0), |(all, any), mask| (all & mask, any | mask));
+                (wires, all, any)
+            })
+            .collect();
+
+        let mut search = WiringSearch {
+            table: self,
+            observed: &observed,
+            constraints: &constraints,
+            assignment: Vec::with_capacity(self.segments.len()),
+            found: Vec::new(),
+        };
+        search.assign_next(0);
+
+        Ok(search.found)
+    }
+
+    /// Returns the only wiring under which each of `observed` is the pattern of some symbol.
+    ///
+    /// Returns an error if there is no such wiring, if there is more than one, or if an observed
+    /// pattern uses a wire that is not labelled with one of the display's segments.
+    pub fn deduce_wiring(&self, observed: &[&str]) -> Result<Wiring, String> {
+        let mut wirings = self.wirings(observed)?;
+
+        match wirings.len() {
+            0 => Err("The observed patterns are contradictory, as no wiring fits them".to_string()),
+            1 => Ok(wirings.remove(0)),
+            n => Err(format!(
+                "The observed patterns are ambiguous, as {} wirings fit them: {}",
+                n,
+                wirings
+                    .iter()
+                    .map(Wiring::to_string)
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            )),
+        }
+    }
+
+    /// Returns the bit mask of the segments labelled in `segments`.
+    fn mask(&self, segments: &str) -> Result<u32, String> {
+        segments.chars().try_fold(0, |mask, segment| {
+            self.segments
+                .binary_search(&segment)
+                .map(|index| mask | 1 << index)
+                .map_err(|_| format!("'{}' is not a segment of the display", segment))
+        })
+    }
+}
+
+/// The state of a search for the wirings consistent with a set of observed patterns.
+struct WiringSearch<'a> {
+    table: &'a DisplayTable,
+    observed: &'a [u32],
+    /// For each observed pattern, its wires, the segments in every symbol pattern of the same
+    /// size, and the segments in any of them.
+    constraints: &'a [(u32, u32, u32)],
+    /// `assignment[w]` is the index of the segment wire `w` is connected to.
+    assignment: Vec<usize>,
+    found: Vec<Wiring>,
+}
+
+impl WiringSearch<'_> {
+    /// Tries every unused segment for wire `wire`, then continues with the next wire, recording
+    /// every complete wiring that fits all the observed patterns.
+    fn assign_next(&mut self, wire: usize) {
+        let size = self.table.segments.len();
+        if wire == size {
+            if self.fits_observed() {
+                self.found.push(self.wiring());
+            }
+            return;
+        }
+
+        for segment in 0..size {
+            if self.assignment.contains(&segment) || !self.may_connect(wire, segment) {
+                continue;
+            }
+            self.assignment.push(segment);
+            self.assign_next(wire + 1);
+            self.assignment.pop();
+        }
+    }
+
+    /// Returns whether connecting `wire` to `segment` is consistent with every observed pattern.
+    fn may_connect(&self, wire: usize, segment: usize) -> bool {
+        self.constraints.iter().all(|&(wires, all, any)| {
+            if wires & 1 << wire != 0 {
+                any & 1 << segment != 0
+            } else {
+                all & 1 << segment == 0
+            }
+        })
+    }
+
+    /// Returns whether every observed pattern maps to a symbol pattern under the full assignment.
+    fn fits_observed(&self) -> bool {
+        self.observed.iter().all(|&wires| {
+            let segments = (0..self.assignment.len())
+                .filter(|wire| wires & 1 << wire != 0)
+                .fold(0, |mask, wire| mask | 1 << self.assignment[wire]);
+            self.table.symbols.iter().any(|&(_, mask)| mask == segments)
+        })
+    }
+
+    fn wiring(&self) -> Wiring {
+        let segments = &self.table.segments;
+        Wiring {
+            wire_to_segment: self
+                .assignment
+                .iter()
+                .enumerate()
+                .map(|(wire, &segment)| (segments[wire], segments[segment]))
+                .collect(),
+        }
+    }
+}
+
+impl Wiring {
+    /// Returns the segment `wire` is connected to, if it is one of the display's wires.
+    pub fn segment(&self, wire: char) -> Option<Segment> {
+        self.wire_to_segment.get(&wire).copied()
+    }
+
+    /// Returns the symbol shown when `wires` are lit, or `None` if that is not a symbol's pattern.
+    pub fn decode(&self, table: &DisplayTable, wires: &str) -> Option<Symbol> {
+        let segments = wires
+            .chars()
+            .map(|wire| self.segment(wire))
+            .collect::<Option<String>>()?;
+        table.symbol(&segments)
+    }
+}
+
+/// Lists each wire and its segment, e.g., "a->c b->f ...".
+impl fmt::Display for Wiring {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let pairs: Vec<String> = self
+            .wire_to_segment
+            .iter()
+            .map(|(wire, segment)| format!("{}->{}", wire, segment))
+            .collect();
+        write!(f, "{}", pairs.join(" "))
+    }
+}
+
+impl FromStr for Entry {
+    type Err = String;
+
+    /// Parses the space-separated observed patterns, a pipe, then the space-separated output
+    /// patterns, e.g., "be cfbegad ... edb | fdgacbe cefdb cefbgd gcbe".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (patterns, outputs) = s
+            .split_once(INPUT_TOKEN_OUTPUT_SEPARATOR)
+            .ok_or_else(|| format!("Missing '{}' in: {}", INPUT_TOKEN_OUTPUT_SEPARATOR, s))?;
+
+        let split = |patterns: &str| -> Vec<String> {
+            patterns.split_whitespace().map(String::from).collect()
+        };
+
+        Ok(Self {
+            patterns: split(patterns),
+            outputs: split(outputs),
+        })
+    }
+}
+
+impl Entry {
+    /// Returns the symbols shown by the outputs, using the only wiring consistent with both the
+    /// observed patterns and the outputs.
+    ///
+    /// Returns an error if the wiring is ambiguous or contradictory, or a pattern uses a wire
+    /// that is not one of `table`'s segments.
+    pub fn decode(&self, table: &DisplayTable) -> Result<String, String> {
+        let observed: Vec<&str> = self
+            .patterns
+            .iter()
+            .chain(&self.outputs)
+            .map(String::as_str)
+            .collect();
+        let wiring = table.deduce_wiring(&observed)?;
+
+        Ok(self
+            .outputs
+            .iter()
+            .map(|output| {
+                wiring
+                    .decode(table, output)
+                    .expect("The wiring fits every output")
+            })
+            .collect())
+    }
+}
+
+// Test data based on examples on the challenge page.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT_ONE_LINE: &str =
+        "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+
+    const TEST_INPUT: &str = "\
+be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe
+edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc
+fgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg
+fbegcd cbd adcefb dageb afcb bc aefdc ecdab fgdeca fcdbega | efabcd cedba gadfec cb
+aecbfdg fbg gf bafeg dbefa fcge gcbea fcaegb dgceab fcbdga | gecf egdcabf bgf bfgea
+fgeab ca afcebg bdacfeg cfaedg gcfdb baec bfadeg bafgc acf | gebdcfa ecba ca fadegcb
+dbcfg fgd bdegcaf fgec aegbdf ecdfab fbedc dacgb gdcebf gf | cefg dcbef fcge gbcadfe
+bdfegc cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd | ed bcgafe cdgba cbgef
+egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb
+gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce";
+
+    fn entries(input: &str) -> Vec<Entry> {
+        input.lines().map(|line| line.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_parse_entry() {
+        let entries = entries(TEST_INPUT);
+
+        assert_eq!(entries.len(), 10);
+        assert_eq!(entries[0].patterns.len(), 10);
+        assert_eq!(entries[0].patterns[4], "cgeb");
+        assert_eq!(entries[1].outputs, ["fcgedb", "cgb", "dgebacf", "gc"]);
+        assert!("be cfbegad".parse::<Entry>().is_err());
+    }
+
+    #[test]
+    fn test_new_table_errors() {
+        assert!(DisplayTable::new(&[('1', "cf"), ('1', "acf")]).is_err());
+        assert!(DisplayTable::new(&[('1', "cf"), ('7', "fc")]).is_err());
+        let too_many: String = (0..33)
+            .map(|i| char::from_u32(0x100 + i).unwrap())
+            .collect();
+        assert!(DisplayTable::new(&[('X', too_many.as_str())]).is_err());
+    }
+
+    #[test]
+    fn test_deduce_wiring() {
+        let table = DisplayTable::seven_segment_digits();
+        let entry: Entry = TEST_INPUT_ONE_LINE.parse().unwrap();
+        let observed: Vec<&str> = entry.patterns.iter().map(String::as_str).collect();
+        let wiring = table.deduce_wiring(&observed).unwrap();
+
+        assert_eq!(wiring.to_string(), "a->c b->f c->g d->a e->b f->d g->e");
+        assert_eq!(wiring.segment('d'), Some('a'));
+        assert_eq!(wiring.decode(&table, "ab"), Some('1'));
+        assert_eq!(wiring.decode(&table, "a"), None);
+        assert_eq!(entry.decode(&table), Ok("5353".to_string()));
+    }
+
+    #[test]
+    fn test_decode_entries() {
+        let table = DisplayTable::seven_segment_digits();
+        let decoded: Vec<String> = entries(TEST_INPUT)
+            .iter()
+            .map(|entry| entry.decode(&table).unwrap())
+            .collect();
+
+        assert_eq!(
+            decoded,
+            ["8394", "9781", "1197", "9361", "4873", "8418", "4548", "1625", "8717", "4315"]
+        );
+    }
+
+    #[test]
+    fn test_identity_wiring() {
+        let table = DisplayTable::seven_segment_digits();
+        let patterns = [
+            "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+        ];
+        let wiring = table.deduce_wiring(&patterns).unwrap();
+
+        assert!("abcdefg".chars().all(|c| wiring.segment(c) == Some(c)));
+    }
+
+    #[test]
+    fn test_ambiguous_and_contradictory() {
+        let table = DisplayTable::seven_segment_digits();
+
+        // Only the patterns for 1 and 7 only fix which wire is segment 'a'.
+        let wirings = table.wirings(&["cf", "acf"]).unwrap();
+        assert_eq!(wirings.len(), 2 * 24);
+        let error = table.deduce_wiring(&["cf", "acf"]).unwrap_err();
+        assert!(error.starts_with("The observed patterns are ambiguous, as 48 wirings"));
+
+        // No symbol lights exactly one segment.
+        assert_eq!(table.wirings(&["a"]), Ok(vec![]));
+        assert!(table
+            .deduce_wiring(&["cf", "a"])
+            .unwrap_err()
+            .contains("contradictory"));
+
+        assert!(table.wirings(&["xy"]).is_err());
+    }
+
+    #[test]
+    fn test_other_table() {
+        // A display whose symbols are letters, shown on four segments.
+        let table =
+            DisplayTable::new(&[('A', "wx"), ('B', "wxy"), ('C', "xz"), ('D', "wyz")]).unwrap();
+        assert_eq!(table.segments(), ['w', 'x', 'y', 'z']);
+
+        // Wires y and z are swapped, so the patterns seen are "wx", "wxz", "xy" and "wzy".
+        let entry: Entry = "wx wxz xy wzy | xy wxz".parse().unwrap();
+        assert_eq!(entry.decode(&table), Ok("CB".to_string()));
+    }
+}
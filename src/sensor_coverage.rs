@@ -0,0 +1,442 @@
+//! The areas covered by beacon sensors, as described in Advent of Code 2022 Day 15.
+//! https://adventofcode.com/2022/day/15
+//!
+//! Each sensor knows the location of its closest beacon, measured by Manhattan distance, so no
+//! other beacon can be as close to it. The points within that distance of a sensor form a
+//! diamond, and a row crosses each diamond in a single span of points, so the coverage of a row
+//! is found by merging one span per sensor.
+//!
+//! Finding the uncovered points in an area uses rotated coordinates `u = x + y` and
+//! `v = x - y`, in which each diamond is an axis-aligned square. The edges of the squares split
+//! the area into a grid of cells, each of which is either wholly covered by some square or not
+//! covered at all, so only one point per cell needs to be checked. The number of cells depends
+//! on the number of sensors, not the size of the area.
+
+use std::ops::{Range, RangeInclusive};
+use std::str::FromStr;
+
+const INPUT_TOKEN_SENSOR: &str = "Sensor at x=";
+const INPUT_TOKEN_COORDINATE_SEPARATOR: &str = ", y=";
+const INPUT_TOKEN_BEACON: &str = ": closest beacon is at x=";
+
+pub type Axis = i64;
+
+/// Holds a coordinate in 2D space as 'x' and 'y' values (which can be negative).
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Point {
+    pub x: Axis,
+    pub y: Axis,
+}
+
+impl Point {
+    pub fn new(x: Axis, y: Axis) -> Self {
+        Self { x, y }
+    }
+
+    pub fn manhattan_distance(&self, other: &Point) -> Axis {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+}
+
+/// The points within a Manhattan distance of `radius` of `center`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Diamond {
+    pub center: Point,
+    pub radius: Axis,
+}
+
+impl Diamond {
+    pub fn contains(&self, point: &Point) -> bool {
+        self.center.manhattan_distance(point) <= self.radius
+    }
+
+    /// Returns the `x` values of the points in row `y` within the diamond, or `None` if the row
+    /// does not cross it.
+    pub fn row_span(&self, y: Axis) -> Option<RangeInclusive<Axis>> {
+        let half_width = self.radius - (y - self.center.y).abs();
+        (half_width >= 0).then(|| self.center.x - half_width..=self.center.x + half_width)
+    }
+
+    /// Returns whether the point with rotated coordinates `u` and `v` is within the diamond.
+    fn contains_rotated(&self, u: Axis, v: Axis) -> bool {
+        let (center_u, center_v) = rotate(&self.center);
+        (u - center_u).abs() <= self.radius && (v - center_v).abs() <= self.radius
+    }
+}
+
+/// Returns the rotated coordinates `(u, v)` of `point`.
+fn rotate(point: &Point) -> (Axis, Axis) {
+    (point.x + point.y, point.x - point.y)
+}
+
+/// The points with `x` and `y` values between those of `min` and `max`, inclusive.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Rect {
+    pub min: Point,
+    pub max: Point,
+}
+
+/// Holds a sensor's location and the location of its closest beacon.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Sensor {
+    pub location: Point,
+    pub closest_beacon: Point,
+}
+
+impl FromStr for Sensor {
+    type Err = String;
+
+    /// Parses a line of the form "Sensor at x=2, y=18: closest beacon is at x=-2, y=15".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let malformed = || format!("Malformed sensor: {}", s);
+        let parse_point = |s: &str| -> Option<Point> {
+            let (x, y) = s.split_once(INPUT_TOKEN_COORDINATE_SEPARATOR)?;
+            Some(Point::new(x.parse().ok()?, y.parse().ok()?))
+        };
+
+        let (location, closest_beacon) = s
+            .trim()
+            .strip_prefix(INPUT_TOKEN_SENSOR)
+            .and_then(|s| s.split_once(INPUT_TOKEN_BEACON))
+            .ok_or_else(malformed)?;
+
+        Ok(Self {
+            location: parse_point(location).ok_or_else(malformed)?,
+            closest_beacon: parse_point(closest_beacon).ok_or_else(malformed)?,
+        })
+    }
+}
+
+impl Sensor {
+    /// Returns the points no further from the sensor than its closest beacon.
+    pub fn coverage(&self) -> Diamond {
+        Diamond {
+            center: self.location,
+            radius: self.location.manhattan_distance(&self.closest_beacon),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Coverage {
+    sensors: Vec<Sensor>,
+    diamonds: Vec<Diamond>,
+}
+
+impl FromStr for Coverage {
+    type Err = String;
+
+    /// Parses one sensor per line, skipping empty lines.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let sensors = s
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<Sensor>, _>>()?;
+
+        Ok(Self::new(sensors))
+    }
+}
+
+impl Coverage {
+    pub fn new(sensors: Vec<Sensor>) -> Self {
+        let diamonds = sensors.iter().map(Sensor::coverage).collect();
+        Self { sensors, diamonds }
+    }
+
+    pub fn sensors(&self) -> &[Sensor] {
+        &self.sensors
+    }
+
+    /// Returns whether any sensor covers `point`.
+    pub fn is_covered(&self, point: &Point) -> bool {
+        self.diamonds.iter().any(|diamond| diamond.contains(point))
+    }
+
+    /// Returns the covered parts of row `y` as non-overlapping, non-adjacent spans of `x` values,
+    /// in increasing order.
+    pub fn row_spans(&self, y: Axis) -> Vec<RangeInclusive<Axis>> {
+        let mut spans: Vec<RangeInclusive<Axis>> = self
+            .diamonds
+            .iter()
+            .filter_map(|diamond| diamond.row_span(y))
+            .collect();
+        spans.sort_unstable_by_key(|span| *span.start());
+
+        let mut merged: Vec<RangeInclusive<Axis>> = Vec::new();
+        for span in spans {
+            match merged.last_mut() {
+                Some(last) if *span.start() <= *last.end() + 1 => {
+                    *last = *last.start()..=*last.end().max(span.end());
+                }
+                _ => merged.push(span),
+            }
+        }
+
+        merged
+    }
+
+    /// Returns the number of covered points in row `y`.
+    pub fn covered_length(&self, y: Axis) -> u64 {
+        self.row_spans(y)
+            .iter()
+            .map(|span| span.start().abs_diff(*span.end()) + 1)
+            .sum()
+    }
+
+    /// Returns the number of points in row `y` that cannot contain a beacon, i.e., the covered
+    /// points other than the known beacons.
+    pub fn beacon_free_length(&self, y: Axis) -> u64 {
+        let mut beacons: Vec<Point> = self
+            .sensors
+            .iter()
+            .map(|sensor| sensor.closest_beacon)
+            .filter(|beacon| beacon.y == y)
+            .collect();
+        beacons.sort_unstable();
+        beacons.dedup();
+
+        // A beacon is always covered by the sensor it is closest to.
+        self.covered_length(y) - beacons.len() as u64
+    }
+
+    /// Returns every point in `rect` that no sensor covers, ordered by row and then column.
+    pub fn uncovered_points(&self, rect: &Rect) -> Vec<Point> {
+        self.uncovered_points_up_to(rect, usize::MAX)
+    }
+
+    /// As `uncovered_points`, but stops once `limit` points have been found. If more than `limit`
+    /// points are uncovered, which of them are returned is unspecified.
+    pub fn uncovered_points_up_to(&self, rect: &Rect, limit: usize) -> Vec<Point> {
+        if rect.min.x > rect.max.x || rect.min.y > rect.max.y {
+            return Vec::new();
+        }
+
+        // The rotated coordinates of the points in `rect` lie within these bounds, inclusive.
+        let u_bounds = (rect.min.x + rect.min.y, rect.max.x + rect.max.y);
+        let v_bounds = (rect.min.x - rect.max.y, rect.max.x - rect.min.y);
+
+        // Each cell of the grid runs from one edge up to, but not including, the next.
+        let edges = |bounds: (Axis, Axis), center: fn(&Point) -> Axis| -> Vec<Axis> {
+            let mut edges = vec![bounds.0, bounds.1 + 1];
+            for diamond in &self.diamonds {
+                let center = center(&diamond.center);
+                edges.push(center - diamond.radius);
+                edges.push(center + diamond.radius + 1);
+            }
+            edges.retain(|&edge| bounds.0 <= edge && edge <= bounds.1 + 1);
+            edges.sort_unstable();
+            edges.dedup();
+            edges
+        };
+        let u_edges = edges(u_bounds, |p| rotate(p).0);
+        let v_edges = edges(v_bounds, |p| rotate(p).1);
+
+        let mut points = Vec::new();
+        'cells: for u_cell in u_edges.windows(2) {
+            for v_cell in v_edges.windows(2) {
+                if points.len() >= limit {
+                    break 'cells;
+                }
+                let covered = self
+                    .diamonds
+                    .iter()
+                    .any(|diamond| diamond.contains_rotated(u_cell[0], v_cell[0]));
+                if !covered {
+                    add_cell_points(
+                        rect,
+                        u_cell[0]..u_cell[1],
+                        v_cell[0]..v_cell[1],
+                        limit,
+                        &mut points,
+                    );
+                }
+            }
+        }
+
+        points.sort_unstable_by_key(|point| (point.y, point.x));
+        points
+    }
+}
+
+/// Adds the points in `rect` whose rotated coordinates are in the ranges `u` and `v` to `points`,
+/// until `points` holds `limit` points.
+fn add_cell_points(
+    rect: &Rect,
+    u: Range<Axis>,
+    v: Range<Axis>,
+    limit: usize,
+    points: &mut Vec<Point>,
+) {
+    // The values of `u` for which some `v` in the cell keeps `x` and `y` within `rect`.
+    let u_first = u
+        .start
+        .max(v.start + 2 * rect.min.y)
+        .max(2 * rect.min.x - v.end + 1);
+    let u_last = (u.end - 1)
+        .min(2 * rect.max.x - v.start)
+        .min(v.end - 1 + 2 * rect.max.y);
+
+    for u in u_first..=u_last {
+        // The bounds on `v` for this `u` that keep `x` and `y` within `rect`.
+        let v_min = v.start.max(2 * rect.min.x - u).max(u - 2 * rect.max.y);
+        let v_max = (v.end - 1).min(2 * rect.max.x - u).min(u - 2 * rect.min.y);
+
+        // Only points where `u` and `v` are both odd or both even have integer coordinates.
+        let v_first = if (v_min - u).rem_euclid(2) == 0 {
+            v_min
+        } else {
+            v_min + 1
+        };
+        for v in (v_first..=v_max).step_by(2) {
+            if points.len() >= limit {
+                return;
+            }
+            points.push(Point::new((u + v) / 2, (u - v) / 2));
+        }
+    }
+}
+
+// Test data based on examples on the challenge page.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "\
+Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+Sensor at x=9, y=16: closest beacon is at x=10, y=16
+Sensor at x=13, y=2: closest beacon is at x=15, y=3
+Sensor at x=12, y=14: closest beacon is at x=10, y=16
+Sensor at x=10, y=20: closest beacon is at x=10, y=16
+Sensor at x=14, y=17: closest beacon is at x=10, y=16
+Sensor at x=8, y=7: closest beacon is at x=2, y=10
+Sensor at x=2, y=0: closest beacon is at x=2, y=10
+Sensor at x=0, y=11: closest beacon is at x=2, y=10
+Sensor at x=20, y=14: closest beacon is at x=25, y=17
+Sensor at x=17, y=20: closest beacon is at x=21, y=22
+Sensor at x=16, y=7: closest beacon is at x=15, y=3
+Sensor at x=14, y=3: closest beacon is at x=15, y=3
+Sensor at x=20, y=1: closest beacon is at x=15, y=3
+";
+
+    fn rect(min: Axis, max: Axis) -> Rect {
+        Rect {
+            min: Point::new(min, min),
+            max: Point::new(max, max),
+        }
+    }
+
+    /// Returns the uncovered points in `rect` by checking every point.
+    fn uncovered_points_slowly(coverage: &Coverage, rect: &Rect) -> Vec<Point> {
+        (rect.min.y..=rect.max.y)
+            .flat_map(|y| (rect.min.x..=rect.max.x).map(move |x| Point::new(x, y)))
+            .filter(|point| !coverage.is_covered(point))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            "Sensor at x=2, y=18: closest beacon is at x=-2, y=15".parse(),
+            Ok(Sensor {
+                location: Point::new(2, 18),
+                closest_beacon: Point::new(-2, 15),
+            })
+        );
+        assert!("Sensor at x=2, y=18: closest beacon is at x=-2"
+            .parse::<Sensor>()
+            .is_err());
+        assert!("Sensor at x=2 y=18: closest beacon is at x=-2, y=15"
+            .parse::<Sensor>()
+            .is_err());
+
+        let coverage: Coverage = TEST_INPUT.parse().unwrap();
+        assert_eq!(coverage.sensors().len(), 14);
+    }
+
+    #[test]
+    fn test_diamond() {
+        let sensor: Sensor = "Sensor at x=8, y=7: closest beacon is at x=2, y=10"
+            .parse()
+            .unwrap();
+        let diamond = sensor.coverage();
+
+        assert_eq!(diamond.radius, 9);
+        assert!(diamond.contains(&Point::new(8, -2)));
+        assert!(!diamond.contains(&Point::new(8, -3)));
+        assert_eq!(diamond.row_span(10), Some(2..=14));
+        assert_eq!(diamond.row_span(16), Some(8..=8));
+        assert_eq!(diamond.row_span(17), None);
+    }
+
+    #[test]
+    fn test_row_coverage() {
+        let coverage: Coverage = TEST_INPUT.parse().unwrap();
+
+        assert_eq!(coverage.row_spans(10), vec![-2..=24]);
+        assert_eq!(coverage.covered_length(10), 27);
+        assert_eq!(coverage.beacon_free_length(10), 26);
+        assert_eq!(coverage.row_spans(11), vec![-3..=13, 15..=25]);
+        assert_eq!(coverage.row_spans(-100), vec![]);
+    }
+
+    #[test]
+    fn test_uncovered_points() {
+        let coverage: Coverage = TEST_INPUT.parse().unwrap();
+
+        assert_eq!(
+            coverage.uncovered_points(&rect(0, 20)),
+            vec![Point::new(14, 11)]
+        );
+
+        for rect in [
+            rect(-5, 30),
+            rect(10, 12),
+            Rect {
+                min: Point::new(-8, 3),
+                max: Point::new(27, 6),
+            },
+            rect(3, 2),
+        ] {
+            assert_eq!(
+                coverage.uncovered_points(&rect),
+                uncovered_points_slowly(&coverage, &rect)
+            );
+        }
+    }
+
+    #[test]
+    fn test_uncovered_points_up_to() {
+        let coverage: Coverage = TEST_INPUT.parse().unwrap();
+
+        assert_eq!(
+            coverage.uncovered_points_up_to(&rect(0, 20), 2),
+            vec![Point::new(14, 11)]
+        );
+        assert_eq!(coverage.uncovered_points_up_to(&rect(0, 20), 0), vec![]);
+
+        let all = coverage.uncovered_points(&rect(-5, 30));
+        let some = coverage.uncovered_points_up_to(&rect(-5, 30), 2);
+        assert_eq!(some.len(), 2);
+        assert!(some.iter().all(|point| all.contains(point)));
+    }
+
+    #[test]
+    fn test_large_coordinates() {
+        let coverage: Coverage = "\
+Sensor at x=5000000000, y=-5000000000: closest beacon is at x=5000000002, y=-5000000001"
+            .parse()
+            .unwrap();
+
+        assert_eq!(coverage.covered_length(-5000000000), 7);
+        let corner = Rect {
+            min: Point::new(5000000000, -5000000004),
+            max: Point::new(5000000004, -5000000000),
+        };
+        assert_eq!(
+            coverage.uncovered_points(&corner),
+            uncovered_points_slowly(&coverage, &corner)
+        );
+        assert_eq!(coverage.uncovered_points(&corner).len(), 25 - 10);
+    }
+}
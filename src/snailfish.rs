@@ -0,0 +1,510 @@
+//! Snailfish numbers, as defined in Advent of Code 2021 Day 18.
+//! https://adventofcode.com/2021/day/18
+//!
+//! A Snailfish number is either a regular number or a pair of Snailfish numbers. Adding two
+//! Snailfish numbers creates a new pair which is then "reduced" by repeatedly exploding deeply
+//! nested pairs and splitting large regular numbers. The reduction can optionally be traced to
+//! compare each step against the worked examples on the challenge page.
+
+use std::fmt::{Display, Error, Formatter};
+use std::iter::{Peekable, Sum};
+use std::ops::Add;
+use std::str::{Chars, FromStr};
+
+/// Regular numbers are parsed as `u8` values but stored as `Int`. Reduction never increases the
+/// total of the regular numbers in a Snailfish number, as an explode only moves values into its
+/// neighbours (or discards them) and a split preserves its value, so no regular number can grow
+/// beyond the total of the numbers added. The wider type keeps this from overflowing.
+type Int = u32;
+
+/// Pairs nested inside this many pairs explode.
+const EXPLODE_DEPTH: usize = 4;
+
+/// Regular numbers of this value or greater split.
+const SPLIT_THRESHOLD: Int = 10;
+
+#[derive(Debug)]
+struct ExplodeData<'a> {
+    node_to_explode: Option<&'a mut SnailfishNumber>,
+    nearest_left: Option<&'a mut SnailfishNumber>,
+    nearest_right: Option<&'a mut SnailfishNumber>,
+}
+
+/// A Snailfish number, which is either a `Regular` number or a `Compound` pair of Snailfish
+/// numbers.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum SnailfishNumber {
+    Regular(Int),
+    Compound {
+        left: Box<SnailfishNumber>,
+        right: Box<SnailfishNumber>,
+    },
+}
+
+/// The operations that change a Snailfish number during addition and reduction.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReductionAction {
+    Addition,
+    Explode,
+    Split,
+}
+
+/// A single step of an addition, holding the action performed and the Snailfish number that
+/// resulted from it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReductionStep {
+    pub action: ReductionAction,
+    pub result: SnailfishNumber,
+}
+
+/// Writes the step in the same format as the worked examples in the challenge, e.g.,
+/// "after explode:  [[[[0,7],4],[7,[[8,4],9]]],[1,1]]".
+impl Display for ReductionStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let label = match self.action {
+            ReductionAction::Addition => "after addition:",
+            ReductionAction::Explode => "after explode:",
+            ReductionAction::Split => "after split:",
+        };
+
+        write!(f, "{:<16}{}", label, self.result)
+    }
+}
+
+impl SnailfishNumber {
+    /// Returns a new compound `SnailfishNumber` with `left` and `right` as its elements. The
+    /// result is not reduced.
+    pub fn pair(left: SnailfishNumber, right: SnailfishNumber) -> Self {
+        Self::Compound {
+            left: Box::new(left),
+            right: Box::new(right),
+        }
+    }
+
+    /// Returns the magnitude of this number. The magnitude of a regular number is its value,
+    /// and the magnitude of a pair is 3 times the magnitude of its left element plus 2 times the
+    /// magnitude of its right element.
+    pub fn magnitude(&self) -> u32 {
+        match self {
+            Self::Compound { left, right } => 3 * left.magnitude() + 2 * right.magnitude(),
+            Self::Regular(reg) => *reg,
+        }
+    }
+
+    /// Reduces this number using explodes and splits until no more changes are required.
+    pub fn reduce(&mut self) {
+        while self.explode() || self.split() {}
+    }
+
+    /// Reduces this number in the same way as `reduce`, but returns every explode and split
+    /// performed, along with the number that resulted from each.
+    pub fn reduce_with_trace(&mut self) -> Vec<ReductionStep> {
+        let mut trace = Vec::new();
+
+        loop {
+            let action = if self.explode() {
+                ReductionAction::Explode
+            } else if self.split() {
+                ReductionAction::Split
+            } else {
+                break;
+            };
+
+            trace.push(ReductionStep {
+                action,
+                result: self.clone(),
+            });
+        }
+
+        trace
+    }
+
+    /// Returns the reduced sum of `self` and `rhs`, along with a trace of every step taken to
+    /// reach it. The first step in the trace is the unreduced addition.
+    pub fn add_with_trace(self, rhs: SnailfishNumber) -> (Self, Vec<ReductionStep>) {
+        let mut result = Self::pair(self, rhs);
+        let mut trace = vec![ReductionStep {
+            action: ReductionAction::Addition,
+            result: result.clone(),
+        }];
+
+        trace.append(&mut result.reduce_with_trace());
+        (result, trace)
+    }
+
+    /// Searches this object for the first explode action that is required, if any. If
+    /// required, the modifications explained in the challenge are made and `true` is returned.
+    /// Otherwise, `false` is returned and no changes are made. From the challenge, the
+    /// modifications apply to "any pair ... nested inside four pairs" and changes are made to
+    /// the leftmost such pair.
+    ///
+    /// "To explode a pair, the pair's left value is added to the first regular number to the
+    /// left of the exploding pair (if any), and the pair's right value is added to the first
+    /// regular number to the right of the exploding pair (if any). Exploding pairs will always
+    /// consist of two regular numbers. Then, the entire exploding pair is replaced with the
+    /// regular number 0."
+    fn explode(&mut self) -> bool {
+        let mut explode_data = ExplodeData {
+            node_to_explode: None,
+            nearest_left: None,
+            nearest_right: None,
+        };
+
+        Self::explode_recurse(self, 0, &mut explode_data);
+
+        let Some(node_to_explode) = explode_data.node_to_explode else {
+            return false;
+        };
+
+        if let Self::Compound { left, right } = node_to_explode {
+            if let (Some(Self::Regular(nl)), Self::Regular(explode_left)) =
+                (explode_data.nearest_left, left.as_ref())
+            {
+                *nl += explode_left;
+            }
+
+            if let (Some(Self::Regular(nr)), Self::Regular(explode_right)) =
+                (explode_data.nearest_right, right.as_ref())
+            {
+                *nr += explode_right;
+            }
+        }
+
+        *node_to_explode = Self::Regular(0);
+        true
+    }
+
+    /// Recursively walks the node of Snailfish numbers starting at `node` looking for any pair
+    /// that is "nested inside four pairs" of parent pairs. `depth` is used to track the current
+    /// depth of recursion. If a node needs exploding, updates `explode_data` to point to the
+    /// node. The nearest number to the left and the nearest number to the right are also tracked
+    /// in this data.
+    fn explode_recurse<'a>(
+        node: &'a mut SnailfishNumber,
+        depth: usize,
+        explode_data: &mut ExplodeData<'a>,
+    ) {
+        // Implementation note: this causes borrow problems if included in 'match' statement below.
+        if let Self::Compound { .. } = node {
+            if depth == EXPLODE_DEPTH && explode_data.node_to_explode.is_none() {
+                explode_data.node_to_explode = Some(node);
+                return;
+            }
+        }
+
+        match node {
+            Self::Compound { left, right } => {
+                Self::explode_recurse(left, depth + 1, explode_data);
+
+                if explode_data.nearest_right.is_some() {
+                    return;
+                }
+
+                Self::explode_recurse(right, depth + 1, explode_data);
+            }
+            Self::Regular(_) => {
+                if explode_data.node_to_explode.is_none() {
+                    explode_data.nearest_left = Some(node);
+                } else if explode_data.nearest_right.is_none() {
+                    explode_data.nearest_right = Some(node);
+                }
+            }
+        }
+    }
+
+    /// Searches this object for the first split action that is required, if any, i.e., the first
+    /// regular number which is "10 or greater". If such an action is required, replaces the
+    /// number with a pair where:
+    ///     the left element is the original number divided by two and rounded down, and
+    ///     the right element is the original number divided by two and rounded up.
+    ///
+    /// Returns true if a split action is performed, false otherwise.
+    fn split(&mut self) -> bool {
+        if let Some(node_to_split) = Self::split_recurse(self) {
+            if let Self::Regular(existing) = *node_to_split {
+                *node_to_split = Self::pair(
+                    Self::Regular(existing / 2),
+                    Self::Regular(existing - existing / 2),
+                );
+
+                return true;
+            } else {
+                panic!("Internal error: split() expected a regular number");
+            }
+        }
+
+        false
+    }
+
+    /// Recursively walks the node of Snailfish numbers starting at `node` looking for any regular
+    /// number greater or equal to 10. If found, the node holding this number is returned.
+    fn split_recurse(node: &mut SnailfishNumber) -> Option<&mut SnailfishNumber> {
+        match node {
+            Self::Compound { left, right } => {
+                Self::split_recurse(left).or_else(|| Self::split_recurse(right))
+            }
+            Self::Regular(reg) => {
+                if *reg >= SPLIT_THRESHOLD {
+                    Some(node)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Parses and returns the Snailfish number at the start of `chars`, consuming the characters
+    /// that form it.
+    fn parse_recurse(chars: &mut Peekable<Chars<'_>>) -> Result<Self, String> {
+        match chars.peek() {
+            Some('[') => {
+                chars.next();
+                let left = Self::parse_recurse(chars)?;
+                Self::expect_char(chars, ',')?;
+                let right = Self::parse_recurse(chars)?;
+                Self::expect_char(chars, ']')?;
+
+                Ok(Self::pair(left, right))
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let mut digits = String::new();
+                while let Some(d) = chars.next_if(char::is_ascii_digit) {
+                    digits.push(d);
+                }
+
+                digits
+                    .parse::<u8>()
+                    .map(|n| Self::Regular(n.into()))
+                    .map_err(|_| format!("Regular number '{}' is too large", digits))
+            }
+            Some(c) => Err(format!("Unexpected character '{}'", c)),
+            None => Err("Unexpected end of input".to_string()),
+        }
+    }
+
+    /// Consumes the next character of `chars`, returning an error if it is not `expected`.
+    fn expect_char(chars: &mut Peekable<Chars<'_>>, expected: char) -> Result<(), String> {
+        match chars.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(format!("Expected '{}' but found '{}'", expected, c)),
+            None => Err(format!("Expected '{}' but reached end of input", expected)),
+        }
+    }
+}
+
+/// Parses a Snailfish number in the text form used in the challenge, e.g., "[[1,2],3]".
+impl FromStr for SnailfishNumber {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.trim().chars().peekable();
+        let number = Self::parse_recurse(&mut chars)?;
+
+        if let Some(c) = chars.next() {
+            return Err(format!("Unexpected character '{}' after end of number", c));
+        }
+
+        Ok(number)
+    }
+}
+
+/// Writes a Snailfish number in text form.
+impl Display for SnailfishNumber {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            Self::Compound { left, right } => write!(f, "[{},{}]", left, right),
+            Self::Regular(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+/// Returns the addition of two Snailfish numbers following the challenge criteria. The result is
+/// a new pair composed of the numbers passed in, which is then reduced.
+impl Add for SnailfishNumber {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let mut result = Self::pair(self, rhs);
+        result.reduce();
+        result
+    }
+}
+
+/// Adds each Snailfish number to the running total in turn.
+///
+/// # Panics
+///
+/// Panics if the iterator is empty, as there is no Snailfish number representing zero.
+impl Sum for SnailfishNumber {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.reduce(|total, n| total + n)
+            .expect("Cannot sum an empty list of Snailfish numbers")
+    }
+}
+
+// Test using data from the examples on the challenge page.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn num(s: &str) -> SnailfishNumber {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            num("[3,4]"),
+            SnailfishNumber::pair(SnailfishNumber::Regular(3), SnailfishNumber::Regular(4))
+        );
+        assert_eq!(
+            num("[[1,2],3]"),
+            SnailfishNumber::pair(
+                SnailfishNumber::pair(SnailfishNumber::Regular(1), SnailfishNumber::Regular(2)),
+                SnailfishNumber::Regular(3)
+            )
+        );
+        assert_eq!(
+            num("[9,[8,7]]"),
+            SnailfishNumber::pair(
+                SnailfishNumber::Regular(9),
+                SnailfishNumber::pair(SnailfishNumber::Regular(8), SnailfishNumber::Regular(7))
+            )
+        );
+        assert_eq!(num("[15,[0,13]]").to_string(), "[15,[0,13]]");
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for input in [
+            "[1,2]",
+            "[[1,9],[8,5]]",
+            "[[[[1,2],[3,4]],[[5,6],[7,8]]],9]",
+            "[[[9,[3,8]],[[0,9],6]],[[[3,7],[4,9]],3]]",
+            "[[[[1,3],[5,3]],[[1,3],[8,7]]],[[[4,9],[6,9]],[[8,2],[7,3]]]]",
+        ] {
+            assert_eq!(num(input).to_string(), input);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!("[1,2".parse::<SnailfishNumber>().is_err());
+        assert!("[1;2]".parse::<SnailfishNumber>().is_err());
+        assert!("[1,2]]".parse::<SnailfishNumber>().is_err());
+        assert!("[1,999]".parse::<SnailfishNumber>().is_err());
+        assert!("".parse::<SnailfishNumber>().is_err());
+    }
+
+    #[test]
+    fn test_explode() {
+        let examples = [
+            ("[[[[[9,8],1],2],3],4]", "[[[[0,9],2],3],4]"),
+            ("[7,[6,[5,[4,[3,2]]]]]", "[7,[6,[5,[7,0]]]]"),
+            ("[[6,[5,[4,[3,2]]]],1]", "[[6,[5,[7,0]]],3]"),
+            (
+                "[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]",
+                "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+            ),
+            (
+                "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+                "[[3,[2,[8,0]]],[9,[5,[7,0]]]]",
+            ),
+        ];
+
+        for (before, after) in examples {
+            let mut n = num(before);
+            assert!(n.explode());
+            assert_eq!(n, num(after));
+        }
+
+        assert!(!num("[[[[0,9],2],3],4]").explode());
+    }
+
+    #[test]
+    fn test_reduce_large_regular_numbers() {
+        let mut n = num("[[[[[9,250],10],2],3],4]");
+        n.reduce();
+        assert_eq!(
+            n.to_string(),
+            "[[[[7,7],[6,7]],[[7,8],[7,7]]],[[[8,0],[8,8]],[[8,8],[8,9]]]]"
+        );
+    }
+
+    #[test]
+    fn test_split() {
+        let mut input0 = SnailfishNumber::Regular(10);
+        assert!(input0.split());
+        assert_eq!(input0, num("[5,5]"));
+
+        let mut input1 = SnailfishNumber::Regular(11);
+        assert!(input1.split());
+        assert_eq!(input1, num("[5,6]"));
+
+        let mut input2 = SnailfishNumber::Regular(12);
+        assert!(input2.split());
+        assert_eq!(input2, num("[6,6]"));
+
+        assert!(!num("[9,9]").split());
+    }
+
+    #[test]
+    fn test_reduce() {
+        let mut reduce0 = num("[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]");
+        reduce0.reduce();
+        assert_eq!(reduce0, num("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]"));
+    }
+
+    #[test]
+    fn test_add() {
+        let result = num("[[[[4,3],4],4],[7,[[8,4],9]]]") + num("[1,1]");
+        assert_eq!(result, num("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]"));
+    }
+
+    #[test]
+    fn test_add_with_trace() {
+        let (result, trace) = num("[[[[4,3],4],4],[7,[[8,4],9]]]").add_with_trace(num("[1,1]"));
+        assert_eq!(result, num("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]"));
+
+        let trace_text: Vec<String> = trace.iter().map(|step| step.to_string()).collect();
+        assert_eq!(
+            trace_text,
+            vec![
+                "after addition: [[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]",
+                "after explode:  [[[[0,7],4],[7,[[8,4],9]]],[1,1]]",
+                "after explode:  [[[[0,7],4],[15,[0,13]]],[1,1]]",
+                "after split:    [[[[0,7],4],[[7,8],[0,13]]],[1,1]]",
+                "after split:    [[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]",
+                "after explode:  [[[[0,7],4],[[7,8],[6,0]]],[8,1]]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sum() {
+        let total: SnailfishNumber = ["[1,1]", "[2,2]", "[3,3]", "[4,4]", "[5,5]", "[6,6]"]
+            .iter()
+            .map(|s| num(s))
+            .sum();
+        assert_eq!(total, num("[[[[5,0],[7,4]],[5,5]],[6,6]]"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sum_empty() {
+        let _: SnailfishNumber = Vec::new().into_iter().sum();
+    }
+
+    #[test]
+    fn test_magnitude() {
+        assert_eq!(num("[[1,2],[[3,4],5]]").magnitude(), 143);
+        assert_eq!(num("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]").magnitude(), 1384);
+        assert_eq!(num("[[[[1,1],[2,2]],[3,3]],[4,4]]").magnitude(), 445);
+        assert_eq!(num("[[[[3,0],[5,3]],[4,4]],[5,5]]").magnitude(), 791);
+        assert_eq!(num("[[[[5,0],[7,4]],[5,5]],[6,6]]").magnitude(), 1137);
+        assert_eq!(
+            num("[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]").magnitude(),
+            3488
+        );
+    }
+}
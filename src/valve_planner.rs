@@ -0,0 +1,570 @@
+//! Planning which valves to open in a network of tunnels, as described in Advent of Code 2022 Day
+//! 16. https://adventofcode.com/2022/day/16
+//!
+//! Each valve has a flow rate and is connected to other valves by tunnels. Moving through a
+//! tunnel takes one minute, as does opening a valve, after which the valve releases its flow rate
+//! in pressure every minute until the time limit. Any number of agents (in the challenge, you and
+//! an elephant) start at the same valve and work in parallel, and the plan is the schedule of
+//! which agent opens which valve in which minute that releases the most pressure in total.
+//!
+//! Only the valves with a non-zero flow rate are worth visiting, and there are few of them, so a
+//! set of them can be held as a bitmask. The planner first finds, for each set of valves a single
+//! agent could open in the time available, the most pressure that agent could release by opening
+//! exactly those valves. As agents never open the same valve, the best plan for several agents is
+//! the best combination of disjoint sets, which is found with a branch-and-bound search.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+const INPUT_TOKEN_VALVE: &str = "Valve ";
+const INPUT_TOKEN_FLOW_RATE: &str = " has flow rate=";
+const INPUT_TOKEN_TUNNEL: &str = "; tunnel leads to valve ";
+const INPUT_TOKEN_TUNNELS: &str = "; tunnels lead to valves ";
+
+/// The largest number of valves with a non-zero flow rate that can be planned for, as each is
+/// represented by one bit of a `ValveSet`.
+const MAX_USEFUL_VALVES: usize = ValveSet::BITS as usize;
+
+pub type FlowRate = u32;
+pub type Minute = u32;
+
+/// A set of valves with a non-zero flow rate, where bit `i` represents the `i`th such valve.
+type ValveSet = u64;
+
+/// The valves and the tunnels between them. Valves are referred to by their identifiers, e.g.,
+/// "AA", and are held in identifier order so that plans are reproducible.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValveNetwork {
+    identifiers: Vec<String>,
+    rates: Vec<FlowRate>,
+    tunnels: Vec<Vec<usize>>,
+}
+
+/// A valve being opened as part of a `Plan`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValveOpening {
+    /// The agent that opens the valve, numbered from 0.
+    pub agent: usize,
+    pub valve: String,
+    /// The minute during which the valve is opened, where the first minute is 1. The valve
+    /// releases pressure from the following minute onwards.
+    pub minute: Minute,
+    /// The total pressure the valve releases before the time limit.
+    pub pressure: FlowRate,
+}
+
+/// The schedule of valve openings that releases the most pressure.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Plan {
+    pub total_pressure: FlowRate,
+    /// The valves opened, in the order they are opened. Valves opened in the same minute are in
+    /// agent order.
+    pub openings: Vec<ValveOpening>,
+}
+
+/// The most pressure found so far that a single agent could release by opening exactly a given
+/// set of valves, and the useful valve indexes and minutes of those openings.
+#[derive(Clone, Debug)]
+struct Route {
+    pressure: FlowRate,
+    openings: Vec<(usize, Minute)>,
+}
+
+/// The state used while searching for the best `Route` for each set of valves.
+struct RouteSearch<'a> {
+    rates: &'a [FlowRate],
+    /// `distances[a][b]` is the travel time from position `a` to position `b`, where position 0 is
+    /// the starting valve and position `i + 1` is useful valve `i`, or `None` if there is no path.
+    distances: &'a [Vec<Option<Minute>>],
+    time_limit: Minute,
+    best_routes: HashMap<ValveSet, Route>,
+    /// The most pressure released on reaching each (position, elapsed minutes, opened valves)
+    /// state. Reaching a state again with no more pressure cannot lead to anything better.
+    seen: HashMap<(usize, Minute, ValveSet), FlowRate>,
+}
+
+impl RouteSearch<'_> {
+    fn visit(
+        &mut self,
+        position: usize,
+        elapsed: Minute,
+        opened: ValveSet,
+        pressure: FlowRate,
+        route: &mut Vec<(usize, Minute)>,
+    ) {
+        match self.seen.get(&(position, elapsed, opened)) {
+            Some(&previous) if previous >= pressure => return,
+            _ => {
+                self.seen.insert((position, elapsed, opened), pressure);
+            }
+        }
+
+        if self
+            .best_routes
+            .get(&opened)
+            .is_none_or(|best| pressure > best.pressure)
+        {
+            self.best_routes.insert(
+                opened,
+                Route {
+                    pressure,
+                    openings: route.clone(),
+                },
+            );
+        }
+
+        for valve in 0..self.rates.len() {
+            if opened & (1 << valve) != 0 {
+                continue;
+            }
+            let Some(distance) = self.distances[position][valve + 1] else {
+                continue;
+            };
+
+            // Moving there and opening the valve must leave at least one minute of flow.
+            let minute_opened = elapsed + distance + 1;
+            if minute_opened >= self.time_limit {
+                continue;
+            }
+
+            let released = self.rates[valve] * (self.time_limit - minute_opened);
+            route.push((valve, minute_opened));
+            self.visit(
+                valve + 1,
+                minute_opened,
+                opened | (1 << valve),
+                pressure + released,
+                route,
+            );
+            route.pop();
+        }
+    }
+}
+
+/// Chooses up to `agents_left` more routes from `routes[from..]` that use none of the valves in
+/// `used` and that release the most pressure in total when added to `pressure`. `best` holds the
+/// best total found so far and the indexes of the routes giving it.
+//
+// `routes` is sorted by decreasing pressure, so no later route can do better than the current one
+// for each remaining agent. Once that bound cannot beat the best total found, the search stops.
+fn choose_routes(
+    routes: &[(ValveSet, Route)],
+    from: usize,
+    agents_left: usize,
+    used: ValveSet,
+    pressure: FlowRate,
+    chosen: &mut Vec<usize>,
+    best: &mut (FlowRate, Vec<usize>),
+) {
+    // Any remaining agents may be left with nothing to do.
+    if pressure > best.0 {
+        *best = (pressure, chosen.clone());
+    }
+    if agents_left == 0 {
+        return;
+    }
+
+    for (index, (set, route)) in routes.iter().enumerate().skip(from) {
+        if pressure + route.pressure * agents_left as FlowRate <= best.0 {
+            break;
+        }
+        if set & used != 0 {
+            continue;
+        }
+
+        chosen.push(index);
+        choose_routes(
+            routes,
+            index + 1,
+            agents_left - 1,
+            used | set,
+            pressure + route.pressure,
+            chosen,
+            best,
+        );
+        chosen.pop();
+    }
+}
+
+impl ValveNetwork {
+    /// Returns the flow rate of `valve`, or `None` if there is no such valve.
+    pub fn rate(&self, valve: &str) -> Option<FlowRate> {
+        self.index_of(valve).ok().map(|i| self.rates[i])
+    }
+
+    /// Returns the number of minutes needed to travel from valve `from` to valve `to`, or `None`
+    /// if either valve does not exist or there is no path between them.
+    pub fn distance(&self, from: &str, to: &str) -> Option<Minute> {
+        let from = self.index_of(from).ok()?;
+        let to = self.index_of(to).ok()?;
+        self.distances_from(from)[to]
+    }
+
+    /// Returns the plan that releases the most pressure when `agents` agents start at valve
+    /// `start` and have `time_limit` minutes.
+    pub fn plan(&self, start: &str, agents: usize, time_limit: Minute) -> Result<Plan, String> {
+        self.plan_with_open_valves(start, agents, time_limit, &[])
+    }
+
+    /// Returns the plan that releases the most pressure in the same way as `plan`, except that
+    /// the valves in `already_open` are open from the start, so release no extra pressure.
+    pub fn plan_with_open_valves(
+        &self,
+        start: &str,
+        agents: usize,
+        time_limit: Minute,
+        already_open: &[&str],
+    ) -> Result<Plan, String> {
+        if agents == 0 {
+            return Err("At least one agent is needed to open valves".to_string());
+        }
+
+        let start = self.index_of(start)?;
+        let already_open = already_open
+            .iter()
+            .map(|valve| self.index_of(valve))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let useful: Vec<usize> = (0..self.identifiers.len())
+            .filter(|i| self.rates[*i] > 0 && !already_open.contains(i))
+            .collect();
+        if useful.len() > MAX_USEFUL_VALVES {
+            return Err(format!(
+                "Plans can include at most {} valves with a non-zero flow rate, but there are {}",
+                MAX_USEFUL_VALVES,
+                useful.len()
+            ));
+        }
+
+        let positions: Vec<usize> = [start].into_iter().chain(useful.iter().copied()).collect();
+        let distances: Vec<Vec<Option<Minute>>> = positions
+            .iter()
+            .map(|&from| {
+                let all = self.distances_from(from);
+                positions.iter().map(|&to| all[to]).collect()
+            })
+            .collect();
+        let rates: Vec<FlowRate> = useful.iter().map(|&i| self.rates[i]).collect();
+
+        let mut search = RouteSearch {
+            rates: &rates,
+            distances: &distances,
+            time_limit,
+            best_routes: HashMap::new(),
+            seen: HashMap::new(),
+        };
+        search.visit(0, 0, 0, 0, &mut Vec::new());
+
+        let mut routes: Vec<(ValveSet, Route)> = search
+            .best_routes
+            .into_iter()
+            .filter(|(set, _)| *set != 0)
+            .collect();
+        routes.sort_unstable_by_key(|(set, route)| (std::cmp::Reverse(route.pressure), *set));
+
+        let mut best = (0, Vec::new());
+        choose_routes(&routes, 0, agents, 0, 0, &mut Vec::new(), &mut best);
+
+        let mut openings: Vec<ValveOpening> = best
+            .1
+            .iter()
+            .enumerate()
+            .flat_map(|(agent, &index)| {
+                routes[index]
+                    .1
+                    .openings
+                    .iter()
+                    .map(move |&(valve, minute)| (agent, valve, minute))
+            })
+            .map(|(agent, valve, minute)| ValveOpening {
+                agent,
+                valve: self.identifiers[useful[valve]].clone(),
+                minute,
+                pressure: rates[valve] * (time_limit - minute),
+            })
+            .collect();
+        openings.sort_by_key(|o| (o.minute, o.agent));
+
+        Ok(Plan {
+            total_pressure: best.0,
+            openings,
+        })
+    }
+
+    fn index_of(&self, valve: &str) -> Result<usize, String> {
+        self.identifiers
+            .binary_search_by(|id| id.as_str().cmp(valve))
+            .map_err(|_| format!("There is no valve '{}'", valve))
+    }
+
+    /// Returns the travel time from valve `from` to every valve, found with a breadth-first
+    /// search, or `None` for valves that cannot be reached.
+    fn distances_from(&self, from: usize) -> Vec<Option<Minute>> {
+        let mut distances = vec![None; self.identifiers.len()];
+        distances[from] = Some(0);
+        let mut leading_edge = vec![from];
+        let mut distance = 0;
+
+        while !leading_edge.is_empty() {
+            distance += 1;
+            let mut new_leading_edge = Vec::new();
+            for valve in leading_edge {
+                for &next in &self.tunnels[valve] {
+                    if distances[next].is_none() {
+                        distances[next] = Some(distance);
+                        new_leading_edge.push(next);
+                    }
+                }
+            }
+            leading_edge = new_leading_edge;
+        }
+
+        distances
+    }
+}
+
+/// Parses a line in one of the following forms, depending on the number of connecting tunnels,
+/// returning the valve's identifier, flow rate and the identifiers of the connected valves:
+///     Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+///     Valve HH has flow rate=22; tunnel leads to valve GG
+fn parse_line(line: &str) -> Result<(&str, FlowRate, Vec<&str>), String> {
+    let invalid = || format!("Invalid valve description '{}'", line);
+
+    let (identifier, flow_rate_onwards) = line
+        .strip_prefix(INPUT_TOKEN_VALVE)
+        .and_then(|rest| rest.split_once(INPUT_TOKEN_FLOW_RATE))
+        .ok_or_else(invalid)?;
+
+    let (flow_rate, connected_valves) = flow_rate_onwards
+        .split_once(INPUT_TOKEN_TUNNEL)
+        .or_else(|| flow_rate_onwards.split_once(INPUT_TOKEN_TUNNELS))
+        .ok_or_else(invalid)?;
+
+    let flow_rate = flow_rate.parse().map_err(|_| invalid())?;
+    Ok((
+        identifier,
+        flow_rate,
+        connected_valves.split(", ").collect(),
+    ))
+}
+
+/// Parses the challenge input, which describes one valve per line. Empty lines are skipped.
+impl FromStr for ValveNetwork {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(parse_line)
+            .collect::<Result<Vec<_>, _>>()?;
+        lines.sort_unstable_by_key(|(identifier, _, _)| *identifier);
+
+        let identifiers: Vec<String> = lines.iter().map(|(id, _, _)| id.to_string()).collect();
+        if let Some(pair) = identifiers.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(format!("Valve '{}' is described more than once", pair[0]));
+        }
+
+        let mut network = Self {
+            identifiers,
+            rates: lines.iter().map(|(_, rate, _)| *rate).collect(),
+            tunnels: Vec::new(),
+        };
+
+        network.tunnels = lines
+            .iter()
+            .map(|(_, _, connected)| {
+                connected
+                    .iter()
+                    .map(|valve| network.index_of(valve))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(network)
+    }
+}
+
+/// Writes one line for each valve opened, followed by the total pressure released.
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for o in &self.openings {
+            writeln!(
+                f,
+                "Minute {}: agent {} opens valve {}, releasing {} pressure",
+                o.minute, o.agent, o.valve, o.pressure
+            )?;
+        }
+        writeln!(f, "Total pressure released: {}", self.total_pressure)
+    }
+}
+
+// Test data based on examples on the challenge page.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "\
+Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+Valve BB has flow rate=13; tunnels lead to valves CC, AA
+Valve CC has flow rate=2; tunnels lead to valves DD, BB
+Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
+Valve EE has flow rate=3; tunnels lead to valves FF, DD
+Valve FF has flow rate=0; tunnels lead to valves EE, GG
+Valve GG has flow rate=0; tunnels lead to valves FF, HH
+Valve HH has flow rate=22; tunnel leads to valve GG
+Valve II has flow rate=0; tunnels lead to valves AA, JJ
+Valve JJ has flow rate=21; tunnel leads to valve II
+";
+
+    fn test_network() -> ValveNetwork {
+        TEST_INPUT.parse().unwrap()
+    }
+
+    fn total(start: &str, agents: usize, time_limit: Minute, already_open: &[&str]) -> FlowRate {
+        test_network()
+            .plan_with_open_valves(start, agents, time_limit, already_open)
+            .unwrap()
+            .total_pressure
+    }
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            parse_line("Valve AA has flow rate=0; tunnels lead to valves DD, II, BB"),
+            Ok(("AA", 0, vec!["DD", "II", "BB"]))
+        );
+        assert_eq!(
+            parse_line("Valve HH has flow rate=22; tunnel leads to valve GG"),
+            Ok(("HH", 22, vec!["GG"]))
+        );
+        assert!(parse_line("Valve HH has flow rate=x; tunnel leads to valve GG").is_err());
+        assert!(parse_line("Valve HH has flow rate=22").is_err());
+    }
+
+    #[test]
+    fn test_parse_network() {
+        let network = test_network();
+        assert_eq!(network.identifiers.len(), 10);
+        assert_eq!(network.rate("AA"), Some(0));
+        assert_eq!(network.rate("JJ"), Some(21));
+        assert_eq!(network.rate("ZZ"), None);
+
+        assert!("Valve AA has flow rate=0; tunnel leads to valve BB"
+            .parse::<ValveNetwork>()
+            .is_err());
+        assert!(format!(
+            "{}Valve AA has flow rate=1; tunnel leads to valve BB",
+            TEST_INPUT
+        )
+        .parse::<ValveNetwork>()
+        .is_err());
+    }
+
+    #[test]
+    fn test_distance() {
+        let network = test_network();
+        let expected = [
+            ("AA", "BB", 1),
+            ("AA", "CC", 2),
+            ("AA", "HH", 5),
+            ("AA", "JJ", 2),
+            ("BB", "HH", 6),
+            ("CC", "II", 3),
+            ("DD", "JJ", 3),
+            ("EE", "II", 3),
+            ("FF", "HH", 2),
+            ("GG", "JJ", 6),
+            ("HH", "JJ", 7),
+            ("II", "JJ", 1),
+        ];
+
+        for (a, b, distance) in expected {
+            assert_eq!(network.distance(a, b), Some(distance));
+            assert_eq!(network.distance(b, a), Some(distance));
+        }
+        assert_eq!(network.distance("AA", "AA"), Some(0));
+        assert_eq!(network.distance("AA", "ZZ"), None);
+    }
+
+    #[test]
+    fn test_one_agent() {
+        assert_eq!(total("AA", 1, 30, &[]), 1651);
+        assert_eq!(total("AA", 1, 2, &[]), 0);
+        assert_eq!(total("AA", 1, 3, &[]), 20);
+        assert_eq!(total("AA", 1, 3, &["DD"]), 13);
+        assert_eq!(total("II", 1, 3, &[]), 21);
+        assert_eq!(total("FF", 1, 4, &["DD"]), 22);
+        assert_eq!(total("HH", 1, 4, &[]), 66);
+        assert_eq!(total("GG", 1, 6, &[]), 88);
+        assert_eq!(total("GG", 1, 7, &[]), 113);
+    }
+
+    #[test]
+    fn test_two_agents() {
+        assert_eq!(total("AA", 2, 26, &[]), 1707);
+        assert_eq!(total("AA", 2, 2, &[]), 0);
+        assert_eq!(total("AA", 2, 3, &[]), 20 + 13);
+        assert_eq!(total("AA", 2, 4, &[]), 20 * 2 + 13 * 2);
+        assert_eq!(total("AA", 2, 5, &[]), 20 * 3 + 21 * 2 + 3);
+    }
+
+    #[test]
+    fn test_more_agents_than_valves() {
+        // With an agent for each valve, every valve that can be reached in time is opened as
+        // soon as possible. HH is too far away to release any pressure.
+        assert_eq!(
+            total("AA", 10, 6, &[]),
+            20 * 4 + 13 * 4 + 21 * 3 + 2 * 3 + 3 * 3
+        );
+        assert_eq!(total("AA", 10, 30, &[]), total("AA", 6, 30, &[]));
+    }
+
+    #[test]
+    fn test_schedule() {
+        let plan = test_network().plan("AA", 1, 30).unwrap();
+
+        assert_eq!(
+            plan.to_string(),
+            "\
+Minute 2: agent 0 opens valve DD, releasing 560 pressure
+Minute 5: agent 0 opens valve BB, releasing 325 pressure
+Minute 9: agent 0 opens valve JJ, releasing 441 pressure
+Minute 17: agent 0 opens valve HH, releasing 286 pressure
+Minute 21: agent 0 opens valve EE, releasing 27 pressure
+Minute 24: agent 0 opens valve CC, releasing 12 pressure
+Total pressure released: 1651
+"
+        );
+    }
+
+    #[test]
+    fn test_schedule_two_agents() {
+        let plan = test_network().plan("AA", 2, 26).unwrap();
+
+        let mut agent_valves: Vec<Vec<&str>> = vec![Vec::new(); 2];
+        for o in &plan.openings {
+            agent_valves[o.agent].push(&o.valve);
+        }
+        agent_valves.sort();
+        assert_eq!(
+            agent_valves,
+            vec![vec!["DD", "HH", "EE"], vec!["JJ", "BB", "CC"]]
+        );
+
+        assert_eq!(
+            plan.openings.iter().map(|o| o.pressure).sum::<FlowRate>(),
+            plan.total_pressure
+        );
+    }
+
+    #[test]
+    fn test_plan_errors() {
+        let network = test_network();
+        assert!(network.plan("ZZ", 1, 30).is_err());
+        assert!(network.plan("AA", 0, 30).is_err());
+        assert!(network.plan_with_open_valves("AA", 1, 30, &["ZZ"]).is_err());
+    }
+}